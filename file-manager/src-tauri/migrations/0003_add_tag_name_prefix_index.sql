@@ -0,0 +1,9 @@
+-- 添加标签名称前缀搜索索引
+-- 优化 search_tags 前缀模式（name LIKE 'kw%'）的查询性能
+
+-- 默认的 B-tree 索引在非 C 排序规则下无法用于 LIKE 前缀匹配，
+-- 使用 text_pattern_ops 创建专用索引
+CREATE INDEX IF NOT EXISTS idx_tags_name_pattern ON tags (name text_pattern_ops);
+
+-- 说明：包含模式（name ILIKE '%kw%'）依赖 0002 中基于 pg_trgm 的 idx_tags_name_trgm 索引，
+-- 如果数据库未启用 pg_trgm 扩展，大量标签时包含模式会退化为全表扫描
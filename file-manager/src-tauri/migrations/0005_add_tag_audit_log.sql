@@ -0,0 +1,14 @@
+-- 添加标签审计日志
+-- 记录标签名称等字段的修改历史（修改前后的值），便于追溯误操作
+
+CREATE TABLE IF NOT EXISTS tag_audit_log (
+    id SERIAL PRIMARY KEY,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    action VARCHAR(20) NOT NULL, -- 目前只有 'rename'
+    old_value TEXT, -- 修改前的值
+    new_value TEXT, -- 修改后的值
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 按标签查看修改历史
+CREATE INDEX IF NOT EXISTS idx_tag_audit_log_tag_id ON tag_audit_log(tag_id, id DESC);
@@ -0,0 +1,44 @@
+-- 添加搜索索引（SQLite）
+-- 优化搜索性能；SQLite 没有 pg_trgm，模糊搜索仍需扫描，只创建复合索引和视图
+
+-- 创建复合索引，优化标签搜索（查询某个标签关联的所有文件）
+CREATE INDEX IF NOT EXISTS idx_file_tags_tag_file_composite ON file_tags(tag_id, file_id);
+
+-- 创建视图，方便文件标签查询
+CREATE VIEW IF NOT EXISTS file_with_tags AS
+SELECT
+    f.id as file_id,
+    f.current_path,
+    f.file_type,
+    f.file_size,
+    f.created_at as file_created_at,
+    f.updated_at as file_updated_at,
+    json_group_array(
+        json_object(
+            'tag_id', t.id,
+            'tag_name', t.name,
+            'tag_color', t.color,
+            'tag_font_color', t.font_color
+        )
+    ) FILTER (WHERE t.id IS NOT NULL) as tags
+FROM files f
+LEFT JOIN file_tags ft ON f.id = ft.file_id
+LEFT JOIN tags t ON ft.tag_id = t.id AND t.deleted_at IS NULL
+WHERE f.deleted_at IS NULL
+GROUP BY f.id, f.current_path, f.file_type, f.file_size, f.created_at, f.updated_at;
+
+-- 创建视图，方便标签统计
+CREATE VIEW IF NOT EXISTS tag_statistics AS
+SELECT
+    t.id as tag_id,
+    t.name as tag_name,
+    t.color as tag_color,
+    t.font_color as tag_font_color,
+    t.parent_id,
+    t.usage_count,
+    COUNT(DISTINCT ft.file_id) as file_count
+FROM tags t
+LEFT JOIN file_tags ft ON t.id = ft.tag_id
+LEFT JOIN files f ON ft.file_id = f.id AND f.deleted_at IS NULL
+WHERE t.deleted_at IS NULL
+GROUP BY t.id, t.name, t.color, t.font_color, t.parent_id, t.usage_count;
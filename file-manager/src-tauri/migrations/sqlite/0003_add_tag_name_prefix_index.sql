@@ -0,0 +1,5 @@
+-- 添加标签名称前缀搜索索引（SQLite）
+-- 优化 search_tags 前缀模式（name LIKE 'kw%'）的查询性能
+
+-- SQLite 的 LIKE 默认不区分大小写，只有 NOCASE 排序规则的索引才能用于前缀匹配
+CREATE INDEX IF NOT EXISTS idx_tags_name_pattern ON tags (name COLLATE NOCASE);
@@ -0,0 +1,14 @@
+-- 添加文件操作日志（SQLite）
+-- 记录重命名、移动和永久删除，用于撤销最近一次操作
+
+CREATE TABLE IF NOT EXISTS file_operations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    operation VARCHAR(20) NOT NULL, -- 'rename'、'move' 或 'delete'
+    source_path TEXT NOT NULL, -- 操作前的路径
+    dest_path TEXT, -- 操作后的路径（永久删除时为空）
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    undone_at TIMESTAMP -- 已撤销的操作不再参与撤销
+);
+
+-- 撤销时按 id 倒序查找最近一条未撤销的操作
+CREATE INDEX IF NOT EXISTS idx_file_operations_pending ON file_operations(id DESC) WHERE undone_at IS NULL;
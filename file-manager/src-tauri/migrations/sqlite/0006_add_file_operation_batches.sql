@@ -0,0 +1,8 @@
+-- 为文件操作日志添加批次号（SQLite）
+-- 批量移动、整理目录等一次调用产生的多条记录共享同一批次号（该批第一条记录的 id），
+-- 撤销时整批撤销；批次号为空的记录各自视为一批
+
+ALTER TABLE file_operations ADD COLUMN batch_id INTEGER;
+
+-- 撤销时按批次号查找同批记录
+CREATE INDEX IF NOT EXISTS idx_file_operations_batch_id ON file_operations(batch_id);
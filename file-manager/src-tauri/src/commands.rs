@@ -31,11 +31,11 @@
 //! ═══════════════════════════════════════════════════════════════════════════
 
 use crate::config::GlobalConfigManager;
-use crate::database::GlobalDatabase;
-use crate::models::file_system::{DirectoryInfo, SearchResult};
-use crate::services::{FileSystemService, TagService};
-use crate::models::tag::Tag;
-use tauri::State;
+use crate::database::{DatabaseConfig, GlobalDatabase};
+use crate::models::file_system::{BatchOutcome, Capabilities, ConflictPolicy, ContentSearchResult, CopyProgress, CreateError, DirectoryInfo, EntryFilter, FileItem, FollowSymlinks, OrganizeReport, PreflightReport, ReconcileReport, SanitizedName, SearchResult, ShellKind, SortBy, TextPreview};
+use crate::services::{DatabaseService, FileSystemService, TagService};
+use crate::models::tag::{BulkTagResult, Tag, TagSearchMode, TagStats};
+use tauri::{AppHandle, Emitter, State};
 
 /// 问候命令（示例命令）
 ///
@@ -54,14 +54,91 @@ pub async fn greet(name: &str) -> Result<String, String> {
 /// 列出指定目录下的所有文件和文件夹
 ///
 /// # 参数
-/// - `path`: 目录路径
+/// - `path`: 目录路径，`"drives:"` 表示驱动盘列表（Unix 为挂载点列表）
+/// - `filter`: 条目类型过滤（"all"、"files_only"、"folders_only"），默认为 "all"
+/// - `extensions`: 只显示这些扩展名的文件（如 `["png", "jpg"]`），不区分大小写，文件夹始终显示
+/// - `with_child_count`: 是否统计文件夹的子项数量（默认 false，目录中文件夹较多时会变慢）
+/// - `global_config`: 全局配置管理器状态
 ///
 /// # 返回
 /// - `Ok(DirectoryInfo)`: 目录信息，包含文件列表和统计信息
 /// - `Err(String)`: 错误信息
 #[tauri::command]
-pub async fn list_directory(path: String) -> Result<DirectoryInfo, String> {
-    FileSystemService::list_directory(&path)
+pub async fn list_directory(
+    path: String,
+    filter: Option<EntryFilter>,
+    extensions: Option<Vec<String>>,
+    with_child_count: Option<bool>,
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<DirectoryInfo, String> {
+    FileSystemService::list_directory_async(
+        path,
+        filter.unwrap_or_default(),
+        extensions,
+        with_child_count.unwrap_or_default(),
+        global_config.get_display_options(),
+    )
+    .await
+}
+
+/// 分页获取目录内容
+///
+/// 适用于包含大量文件的目录，只返回请求的一页
+///
+/// # 参数
+/// - `path`: 目录路径
+/// - `page`: 页码（从1开始）
+/// - `page_size`: 每页数量
+/// - `sort`: 排序方式（"name"、"size"、"modified"），默认为 "name"
+/// - `global_config`: 全局配置管理器状态
+///
+/// # 返回
+/// - `Ok(SearchResult)`: 当前页的文件列表及总数
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn list_directory_paged(
+    path: String,
+    page: usize,
+    page_size: usize,
+    sort: Option<SortBy>,
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<SearchResult, String> {
+    FileSystemService::list_directory_paged(&path, page, page_size, sort, &global_config.get_display_options())
+}
+
+/// 获取路径的父级链
+///
+/// 返回从驱动盘根目录（Unix 为 `/`）到指定路径的所有文件夹，用于渲染面包屑导航
+///
+/// # 参数
+/// - `path`: 目录路径，`"drives:"` 返回空列表
+/// - `global_config`: 全局配置管理器状态
+///
+/// # 返回
+/// - `Ok(Vec<FileItem>)`: 父级链（从根到自身）
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn get_parent_chain(
+    path: String,
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<Vec<FileItem>, String> {
+    FileSystemService::get_parent_chain(&path, &global_config.get_display_options())
+}
+
+/// 统计目录项数量
+///
+/// 只统计指定目录下的文件和文件夹数量，比 list_directory 开销更小
+///
+/// # 参数
+/// - `path`: 目录路径
+/// - `include_hidden`: 是否统计隐藏文件
+///
+/// # 返回
+/// - `Ok((usize, usize))`: (文件数, 文件夹数)
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn count_entries(path: String, include_hidden: bool) -> Result<(usize, usize), String> {
+    FileSystemService::count_entries(&path, include_hidden)
 }
 
 /// 获取用户主目录
@@ -82,16 +159,48 @@ pub async fn get_home_directory(
     FileSystemService::get_home_directory(&*global_config)
 }
 
+/// 获取用户主目录的内容
+///
+/// 应用启动时一次调用同时完成主目录解析和目录列表；主目录无法获取时
+/// 回退为驱动盘列表（Windows）或根目录（Unix）
+///
+/// # 参数
+/// - `global_config`: 全局配置管理器状态
+///
+/// # 返回
+/// - `Ok(DirectoryInfo)`: 主目录（或回退目录）的目录信息
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn get_home_listing(
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<DirectoryInfo, String> {
+    FileSystemService::get_home_listing(&*global_config)
+}
+
+/// 获取文件系统服务在当前平台上支持的功能
+///
+/// # 返回
+/// - `Capabilities`: 功能标志（驱动盘、回收站、符号链接）和操作系统名称
+#[tauri::command]
+pub async fn get_capabilities() -> Capabilities {
+    FileSystemService::capabilities()
+}
+
 /// 获取所有驱动盘列表
 ///
-/// 获取 Windows 系统中所有可用的驱动盘列表
+/// 获取 Windows 系统中所有可用的驱动盘列表，Unix 系统上返回挂载点列表
+///
+/// # 参数
+/// - `global_config`: 全局配置管理器状态
 ///
 /// # 返回
 /// - `Ok(DirectoryInfo)`: 包含所有驱动盘的目录信息
 /// - `Err(String)`: 错误信息
 #[tauri::command]
-pub async fn list_drives() -> Result<DirectoryInfo, String> {
-    FileSystemService::list_drives()
+pub async fn list_drives(
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<DirectoryInfo, String> {
+    FileSystemService::list_drives(&global_config.get_display_options())
 }
 
 /// 检查路径是否存在且为目录
@@ -110,6 +219,228 @@ pub async fn check_path_exists(path: String) -> Result<bool, String> {
     FileSystemService::check_path_exists(&path)
 }
 
+/// 批量检查路径是否存在
+///
+/// 文件和文件夹都视为存在，用于操作前校验选中项
+///
+/// # 参数
+/// - `paths`: 路径列表
+///
+/// # 返回
+/// - `Ok(Vec<(String, bool)>)`: 与输入顺序一致的 (路径, 是否存在) 列表
+#[tauri::command]
+pub async fn check_paths_exist(paths: Vec<String>) -> Result<Vec<(String, bool)>, String> {
+    Ok(FileSystemService::check_paths_exist(&paths))
+}
+
+/// 批量获取文件信息
+///
+/// 用于展示选中项的详细信息，单个路径失败不影响其他路径
+///
+/// # 参数
+/// - `paths`: 路径列表
+/// - `global_config`: 全局配置管理器状态
+///
+/// # 返回
+/// - `Ok(Vec<Result<FileItem, String>>)`: 与输入顺序一致的结果列表
+#[tauri::command]
+pub async fn get_file_infos(
+    paths: Vec<String>,
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<Vec<Result<FileItem, String>>, String> {
+    Ok(FileSystemService::get_file_infos(&paths, &global_config.get_display_options()))
+}
+
+/// 按文件内容搜索
+///
+/// 在目录下递归查找文本内容包含关键词的文件（不区分大小写），跳过大文件和二进制文件，
+/// 匹配数量超过配置的 `max_results` 时提前停止
+///
+/// # 参数
+/// - `root`: 搜索的根目录
+/// - `query`: 要查找的文本
+/// - `max_file_size`: 参与搜索的文件大小上限（字节）
+/// - `global_config`: 全局配置管理器状态
+///
+/// # 返回
+/// - `Ok(ContentSearchResult)`: 匹配的文件列表及是否被截断
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn search_content(
+    root: String,
+    query: String,
+    max_file_size: u64,
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<ContentSearchResult, String> {
+    FileSystemService::search_content(
+        &root,
+        &query,
+        max_file_size,
+        global_config.get_max_results(),
+        &global_config.get_display_options(),
+    )
+}
+
+/// 预览文本文件
+///
+/// 读取文件开头的部分内容用于预览面板，二进制文件返回错误
+///
+/// # 参数
+/// - `path`: 文件路径
+/// - `max_bytes`: 最多读取的字节数
+///
+/// # 返回
+/// - `Ok(TextPreview)`: 预览内容及是否截断
+/// - `Err(String)`: 错误信息（二进制文件返回 "不是文本文件"）
+#[tauri::command]
+pub async fn preview_text(path: String, max_bytes: usize) -> Result<TextPreview, String> {
+    FileSystemService::preview_text(&path, max_bytes)
+}
+
+/// 生成图片缩略图
+///
+/// 用于图库视图，缩略图按文件修改时间缓存，图片未修改时直接返回缓存
+///
+/// # 参数
+/// - `path`: 图片文件路径
+/// - `max_dim`: 缩略图最长边的像素数
+///
+/// # 返回
+/// - `Ok(Vec<u8>)`: PNG 格式的缩略图数据
+/// - `Err(String)`: 错误信息（非图片文件返回 "不支持的预览类型"）
+#[tauri::command]
+pub async fn generate_thumbnail(path: String, max_dim: u32) -> Result<Vec<u8>, String> {
+    tokio::task::spawn_blocking(move || FileSystemService::generate_thumbnail(&path, max_dim))
+        .await
+        .map_err(|e| format!("生成缩略图失败: {}", e))?
+}
+
+/// 将路径格式化为可粘贴到终端的形式
+///
+/// 路径包含空格或特殊字符时按目标 shell 的规则加引号
+///
+/// # 参数
+/// - `path`: 文件路径
+/// - `shell`: 目标 shell（"cmd"、"powershell"、"posix_sh"）
+///
+/// # 返回
+/// - `String`: 加引号或转义后的路径
+#[tauri::command]
+pub async fn format_path_for_shell(path: String, shell: ShellKind) -> String {
+    FileSystemService::format_path_for_shell(&path, shell)
+}
+
+/// 清理文件名
+///
+/// 替换当前系统不允许的文件名字符，前端可在重命名前提示用户实际使用的名称
+///
+/// # 参数
+/// - `name`: 原始文件名
+///
+/// # 返回
+/// - `SanitizedName`: 清理后的名称及是否被修改
+#[tauri::command]
+pub async fn sanitize_file_name(name: String) -> SanitizedName {
+    let sanitized = FileSystemService::sanitize_name(&name);
+    SanitizedName {
+        changed: sanitized != name,
+        name: sanitized,
+    }
+}
+
+/// 创建文件夹
+///
+/// 开启 `sanitize_file_names` 配置时先清理名称中的非法字符
+///
+/// # 参数
+/// - `global_config`: 全局配置管理器
+/// - `parent_path`: 父目录路径
+/// - `name`: 新文件夹名称
+///
+/// # 返回
+/// - `Ok(String)`: 新文件夹的完整路径
+/// - `Err(CreateError)`: 错误信息，名称冲突时 `suggestion` 为可用的建议名称
+#[tauri::command]
+pub async fn create_directory(
+    global_config: State<'_, GlobalConfigManager>,
+    parent_path: String,
+    name: String,
+) -> Result<String, CreateError> {
+    let name = sanitize_if_enabled(&global_config, name);
+    FileSystemService::create_directory(&parent_path, &name)
+}
+
+/// 创建空文件
+///
+/// 开启 `sanitize_file_names` 配置时先清理名称中的非法字符
+///
+/// # 参数
+/// - `global_config`: 全局配置管理器
+/// - `parent_path`: 父目录路径
+/// - `name`: 新文件名称
+///
+/// # 返回
+/// - `Ok(String)`: 新文件的完整路径
+/// - `Err(CreateError)`: 错误信息，名称冲突时 `suggestion` 为可用的建议名称
+#[tauri::command]
+pub async fn create_file(
+    global_config: State<'_, GlobalConfigManager>,
+    parent_path: String,
+    name: String,
+) -> Result<String, CreateError> {
+    let name = sanitize_if_enabled(&global_config, name);
+    FileSystemService::create_file(&parent_path, &name)
+}
+
+/// 开启 `sanitize_file_names` 配置时清理名称，否则原样返回
+fn sanitize_if_enabled(global_config: &GlobalConfigManager, name: String) -> String {
+    if global_config.get_sanitize_file_names() {
+        FileSystemService::sanitize_name(&name)
+    } else {
+        name
+    }
+}
+
+/// 设置只读属性
+///
+/// 切换文件/文件夹的只读属性（Unix 为所有者写权限位）
+///
+/// # 参数
+/// - `path`: 文件/文件夹路径
+/// - `readonly`: 是否设为只读
+///
+/// # 返回
+/// - `Ok(())`: 操作成功
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn set_readonly(path: String, readonly: bool) -> Result<(), String> {
+    FileSystemService::set_readonly(&path, readonly)
+}
+
+/// 保存文件内容
+///
+/// 以原子替换的方式写入文件（先写临时文件再重命名），供文本编辑器保存使用。
+/// 如果文件在 files 表中有记录，会同步更新文件大小
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `path`: 目标文件路径
+/// - `contents`: 文件内容（字节数组）
+/// - `create_new`: 为 true 时只允许创建新文件，不覆盖已有文件
+///
+/// # 返回
+/// - `Ok(())`: 操作成功
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn write_file(
+    db: State<'_, GlobalDatabase>,
+    path: String,
+    contents: Vec<u8>,
+    create_new: bool,
+) -> Result<(), String> {
+    FileSystemService::write_file(&*db, &path, &contents, create_new).await
+}
+
 /// 剪切文件（移动文件）
 ///
 /// 将指定的文件/文件夹移动到目标目录
@@ -117,6 +448,7 @@ pub async fn check_path_exists(path: String) -> Result<bool, String> {
 ///
 /// # 参数
 /// - `db`: 全局数据库实例
+/// - `file_system`: 文件系统服务状态（限制并发文件操作数量）
 /// - `paths`: 要剪切的文件/文件夹路径列表
 /// - `target_path`: 目标目录路径
 ///
@@ -126,10 +458,71 @@ pub async fn check_path_exists(path: String) -> Result<bool, String> {
 #[tauri::command]
 pub async fn cut_files(
     db: State<'_, GlobalDatabase>,
+    file_system: State<'_, FileSystemService>,
     paths: Vec<String>,
     target_path: String,
 ) -> Result<(), String> {
-    FileSystemService::cut_files(&*db, &paths, &target_path).await
+    file_system
+        .run_limited(FileSystemService::cut_files(&*db, &paths, &target_path))
+        .await
+}
+
+/// 移动文件
+///
+/// 将指定的文件/文件夹移动到目标目录，支持跨磁盘移动
+/// 移动后同步更新 files 表中的路径，文件夹会同时更新其下所有子文件记录
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `file_system`: 文件系统服务状态（限制并发文件操作数量）
+/// - `paths`: 要移动的文件/文件夹路径列表
+/// - `target_path`: 目标目录路径
+/// - `policy`: 冲突处理策略（"error"、"skip"、"overwrite"、"rename"），默认为 "error"
+/// - `operation_id`: 操作ID（可选），传入后可通过 `cancel_file_operation` 取消
+///
+/// # 返回
+/// - `Ok(BatchOutcome)`: 全部完成，或被取消时附带已移动的路径
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn move_files(
+    db: State<'_, GlobalDatabase>,
+    file_system: State<'_, FileSystemService>,
+    paths: Vec<String>,
+    target_path: String,
+    policy: Option<ConflictPolicy>,
+    operation_id: Option<String>,
+) -> Result<BatchOutcome, String> {
+    let token = operation_id.as_deref().map(|id| file_system.register_operation(id));
+    let result = file_system
+        .run_limited(FileSystemService::move_files(&*db, &paths, &target_path, policy, token.as_ref()))
+        .await;
+    if let Some(id) = operation_id.as_deref() {
+        file_system.finish_operation(id);
+    }
+    result
+}
+
+/// 按扩展名整理目录
+///
+/// 将目录下的文件移动到以扩展名命名的子文件夹中（无扩展名的文件移动到 `other/`），同名文件自动重命名
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `file_system`: 文件系统服务状态（限制并发文件操作数量）
+/// - `dir`: 要整理的目录路径
+///
+/// # 返回
+/// - `Ok(OrganizeReport)`: 移动的文件数和新建的子文件夹
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn organize_by_extension(
+    db: State<'_, GlobalDatabase>,
+    file_system: State<'_, FileSystemService>,
+    dir: String,
+) -> Result<OrganizeReport, String> {
+    file_system
+        .run_limited(FileSystemService::organize_by_extension(&*db, &dir))
+        .await
 }
 
 /// 复制文件
@@ -140,19 +533,141 @@ pub async fn cut_files(
 ///
 /// # 参数
 /// - `db`: 全局数据库实例
+/// - `global_config`: 全局配置管理器状态
+/// - `file_system`: 文件系统服务状态（限制并发文件操作数量）
 /// - `paths`: 要复制的文件/文件夹路径列表
 /// - `target_path`: 目标目录路径
+/// - `follow_symlinks`: 符号链接跟随策略（"never"、"files_only"、"always"），默认为 "always"
+/// - `verify`: 是否在复制后校验 SHA-256（不一致时删除目标文件并报错），默认为 false
+/// - `operation_id`: 操作ID（可选），传入后可通过 `cancel_file_operation` 取消
+/// - `app`: 应用句柄，复制过程中发送 `copy-progress` 事件（载荷为 `CopyProgress`）
 ///
 /// # 返回
-/// - `Ok(())`: 操作成功
+/// - `Ok(BatchOutcome)`: 全部完成，或被取消时附带已复制的路径
 /// - `Err(String)`: 错误信息
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn copy_files(
+    app: AppHandle,
     db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
+    file_system: State<'_, FileSystemService>,
     paths: Vec<String>,
     target_path: String,
-) -> Result<(), String> {
-    FileSystemService::copy_files(&*db, &paths, &target_path).await
+    follow_symlinks: Option<FollowSymlinks>,
+    verify: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<BatchOutcome, String> {
+    let token = operation_id.as_deref().map(|id| file_system.register_operation(id));
+    let emit_progress = |progress: &CopyProgress| {
+        let _ = app.emit("copy-progress", progress);
+    };
+    let result = file_system
+        .run_limited(FileSystemService::copy_files(
+            &*db,
+            &*global_config,
+            &paths,
+            &target_path,
+            follow_symlinks,
+            verify,
+            token.as_ref(),
+            Some(&emit_progress),
+        ))
+        .await;
+    if let Some(id) = operation_id.as_deref() {
+        file_system.finish_operation(id);
+    }
+    result
+}
+
+/// 取消进行中的复制或移动操作
+///
+/// 操作在处理完当前项后停止，复制到一半的目标会被清理
+///
+/// # 参数
+/// - `file_system`: 文件系统服务状态
+/// - `operation_id`: 发起复制/移动时传入的操作ID
+///
+/// # 返回
+/// - `Ok(true)`: 已通知操作取消
+/// - `Ok(false)`: 操作不存在或已结束
+#[tauri::command]
+pub async fn cancel_file_operation(
+    file_system: State<'_, FileSystemService>,
+    operation_id: String,
+) -> Result<bool, String> {
+    Ok(file_system.cancel_operation(&operation_id))
+}
+
+/// 计算目录总大小
+///
+/// 递归统计目录下所有文件的字节数
+///
+/// # 参数
+/// - `path`: 目录路径
+/// - `follow_symlinks`: 符号链接跟随策略（"never"、"files_only"、"always"），默认为 "never"
+///
+/// # 返回
+/// - `Ok(u64)`: 目录总大小（字节）
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn get_directory_size(
+    path: String,
+    follow_symlinks: Option<FollowSymlinks>,
+) -> Result<u64, String> {
+    FileSystemService::get_directory_size(&path, follow_symlinks)
+}
+
+/// 导出目录树快照
+///
+/// 生成包含名称、大小和类型的嵌套 JSON，用于生成清单或备份
+///
+/// # 参数
+/// - `root`: 根目录路径
+/// - `max_depth`: 导出的最大层级
+/// - `global_config`: 全局配置管理器状态
+///
+/// # 返回
+/// - `Ok(String)`: 目录树快照的 JSON 字符串，节点数超过配置的 `max_results` 时 `truncated` 为 true
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn export_tree(
+    root: String,
+    max_depth: usize,
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<String, String> {
+    FileSystemService::export_tree(&root, max_depth, global_config.get_max_results())
+}
+
+/// 获取路径所在磁盘的可用空间
+///
+/// 复制前用于检查目标磁盘容量
+///
+/// # 参数
+/// - `path`: 文件或目录路径
+///
+/// # 返回
+/// - `Ok(u64)`: 可用空间（字节）
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn get_free_space(path: String) -> Result<u64, String> {
+    FileSystemService::available_space(&path)
+}
+
+/// 复制前预检
+///
+/// 汇总待复制内容总大小、目标磁盘可用空间和名称冲突，不执行复制
+///
+/// # 参数
+/// - `paths`: 要复制的文件/文件夹路径列表
+/// - `target_path`: 目标目录路径
+///
+/// # 返回
+/// - `Ok(PreflightReport)`: 预检结果
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn preflight_copy(paths: Vec<String>, target_path: String) -> Result<PreflightReport, String> {
+    FileSystemService::preflight_copy(&paths, &target_path)
 }
 
 /// 获取标签列表
@@ -163,8 +678,9 @@ pub async fn copy_files(
 ///
 /// # 参数
 /// - `db`: 全局数据库实例
-/// - `limit`: 返回的标签数量限制，默认为 10
-/// - `mode`: 排序模式，"most_used" 或 "recent_used"
+/// - `global_config`: 全局配置管理器状态
+/// - `limit`: 返回的标签数量限制，默认使用全局配置（10）
+/// - `mode`: 排序模式，"most_used" 或 "recent_used"，默认使用全局配置（"most_used"）
 ///
 /// # 返回
 /// - `Ok(Vec<Tag>)`: 标签列表
@@ -172,10 +688,11 @@ pub async fn copy_files(
 #[tauri::command]
 pub async fn get_tag_list(
     db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
     limit: Option<i32>,
     mode: Option<String>,
 ) -> Result<Vec<Tag>, String> {
-    TagService::get_tag_list(&*db, limit, mode).await
+    TagService::get_tag_list(&*db, &*global_config, limit, mode).await
 }
 
 /// 搜索标签
@@ -184,8 +701,11 @@ pub async fn get_tag_list(
 ///
 /// # 参数
 /// - `db`: 全局数据库实例
+/// - `global_config`: 全局配置管理器状态
 /// - `keyword`: 搜索关键词
-/// - `limit`: 返回的标签数量限制，默认为 50
+/// - `limit`: 返回的标签数量限制，默认使用全局配置（50）
+/// - `case_sensitive`: 是否区分大小写，默认为 false
+/// - `search_mode`: 搜索模式，"contains"（包含，默认）或 "prefix"（前缀）
 ///
 /// # 返回
 /// - `Ok(Vec<Tag>)`: 匹配的标签列表
@@ -193,47 +713,113 @@ pub async fn get_tag_list(
 #[tauri::command]
 pub async fn search_tags(
     db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
     keyword: String,
     limit: Option<i32>,
+    case_sensitive: Option<bool>,
+    search_mode: Option<TagSearchMode>,
 ) -> Result<Vec<Tag>, String> {
-    TagService::search_tags(&*db, keyword, limit).await
+    TagService::search_tags(&*db, &*global_config, keyword, limit, case_sensitive, search_mode).await
 }
 
 /// 创建新标签
 ///
 /// 使用指定名称创建一个新标签，其它字段使用数据库默认值：
-/// - color: '#FFFF00'
-/// - font_color: '#000000'
+/// - color: '#FFFF00'（配置了 default_tag_colors 时按标签数量循环取用其中的颜色）
+/// - font_color: '#000000'（使用 default_tag_colors 时按背景深浅自动选择）
 /// - usage_count: 0
 /// - parent_id: NULL
 ///
 /// # 参数
 /// - `db`: 全局数据库实例
+/// - `global_config`: 全局配置管理器状态
 /// - `name`: 标签名称
+/// - `restore_if_deleted`: 存在同名的已删除标签时是否恢复该标签（默认 false，新建标签）
 ///
 /// # 返回
-/// - `Ok(Tag)`: 创建成功的标签
+/// - `Ok(Tag)`: 创建或恢复的标签
 /// - `Err(String)`: 错误信息（名称为空或重复等）
 #[tauri::command]
 pub async fn create_tag(
     db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
     name: String,
+    restore_if_deleted: Option<bool>,
 ) -> Result<Tag, String> {
-    TagService::create_tag(&*db, name).await
+    TagService::create_tag(&*db, &*global_config, name, restore_if_deleted.unwrap_or_default()).await
 }
 
-/// 修改标签
+/// 批量创建标签
 ///
-/// 修改指定标签的信息，可以修改标签名称、背景颜色、字体颜色和父级标签。
-/// 如果某个字段传入None，表示不修改该字段；如果传入Some(None)，表示将该字段设置为NULL。
+/// 用于导入标签列表：名称去重后在一个事务中创建（同名的已删除标签会被恢复），已存在和校验失败的名称记录在结果中
 ///
 /// # 参数
 /// - `db`: 全局数据库实例
-/// - `id`: 标签ID
-/// - `name`: 新标签名称（可选，None表示不修改）
-/// - `color`: 新背景颜色（可选，None表示不修改，Some(None)表示设置为NULL）
-/// - `font_color`: 新字体颜色（可选，None表示不修改，Some(None)表示设置为NULL）
+/// - `global_config`: 全局配置管理器（提供默认颜色列表）
+/// - `names`: 标签名称列表
+///
+/// # 返回
+/// - `Ok(BulkTagResult)`: 创建、跳过和校验失败的名称
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn create_tags_bulk(
+    db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
+    names: Vec<String>,
+) -> Result<BulkTagResult, String> {
+    TagService::create_tags_bulk(&*db, &*global_config, names).await
+}
+
+/// 获取单个标签
+///
+/// 用于编辑标签对话框加载标签的当前信息
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `id`: 标签ID
+///
+/// # 返回
+/// - `Ok(Tag)`: 标签信息
+/// - `Err(String)`: 错误信息（标签不存在）
+#[tauri::command]
+pub async fn get_tag(db: State<'_, GlobalDatabase>, id: i32) -> Result<Tag, String> {
+    TagService::get_tag(&*db, id).await
+}
+
+/// 重命名标签
+///
+/// 只修改标签名称，修改前后的名称会记录到标签审计日志
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `id`: 标签ID
+/// - `new_name`: 新名称
+///
+/// # 返回
+/// - `Ok(Tag)`: 重命名后的标签
+/// - `Err(String)`: 错误信息（标签不存在、名称重复等）
+#[tauri::command]
+pub async fn rename_tag(
+    db: State<'_, GlobalDatabase>,
+    id: i32,
+    new_name: String,
+) -> Result<Tag, String> {
+    TagService::rename_tag(&*db, id, &new_name).await
+}
+
+/// 修改标签
+///
+/// 修改指定标签的信息，可以修改标签名称、背景颜色、字体颜色和父级标签。
+/// 如果某个字段传入None，表示不修改该字段；如果传入Some(None)，表示将该字段设置为NULL。
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `id`: 标签ID
+/// - `name`: 新标签名称（可选，None表示不修改）
+/// - `color`: 新背景颜色（可选，None表示不修改，Some(None)表示设置为NULL）
+/// - `font_color`: 新字体颜色（可选，None表示不修改，Some(None)表示设置为NULL）
 /// - `parent_id`: 新父标签ID（可选，None表示不修改，Some(None)表示设置为NULL）
+/// - `auto_contrast`: 只设置背景颜色时是否根据背景亮度自动选择黑色或白色字体，默认为 false
 ///
 /// # 返回
 /// - `Ok(Tag)`: 修改后的标签
@@ -246,29 +832,71 @@ pub async fn modify_tag(
     color: Option<Option<String>>,
     font_color: Option<Option<String>>,
     parent_id: Option<Option<i32>>,
+    auto_contrast: Option<bool>,
 ) -> Result<Tag, String> {
-    TagService::modify_tag(&*db, id, name, color, font_color, parent_id).await
+    TagService::modify_tag(&*db, id, name, color, font_color, parent_id, auto_contrast).await
 }
 
 /// 重命名文件或文件夹
 ///
-/// 将指定路径的文件或文件夹重命名为新名称，并更新数据库中的路径记录
+/// 将指定路径的文件或文件夹重命名为新名称，并更新数据库中的路径记录。
+/// 开启 `sanitize_file_names` 配置时先清理新名称中的非法字符
 ///
 /// # 参数
 /// - `db`: 全局数据库实例
+/// - `global_config`: 全局配置管理器
 /// - `old_path`: 原文件/文件夹路径
 /// - `new_name`: 新名称
 ///
 /// # 返回
-/// - `Ok(())`: 操作成功
+/// - `Ok(String)`: 实际使用的名称（被清理时与 `new_name` 不同）
 /// - `Err(String)`: 错误信息
 #[tauri::command]
 pub async fn rename_file(
     db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
     old_path: String,
     new_name: String,
+) -> Result<String, String> {
+    let new_name = sanitize_if_enabled(&global_config, new_name);
+    FileSystemService::rename_file(&*db, &old_path, &new_name).await?;
+    Ok(new_name)
+}
+
+/// 重命名文件并保留原扩展名
+///
+/// 只修改文件名主体，原扩展名会自动追加到新名称后
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `old_path`: 原文件路径
+/// - `new_base_name`: 新的文件名（不含扩展名）
+///
+/// # 返回
+/// - `Ok(())`: 操作成功
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn rename_keep_extension(
+    db: State<'_, GlobalDatabase>,
+    old_path: String,
+    new_base_name: String,
 ) -> Result<(), String> {
-    FileSystemService::rename_file(&*db, &old_path, &new_name).await
+    FileSystemService::rename_keep_extension(&*db, &old_path, &new_base_name).await
+}
+
+/// 撤销最近一次文件操作
+///
+/// 支持撤销重命名和移动，最近一次操作为永久删除时返回 "无法撤销该操作"
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+///
+/// # 返回
+/// - `Ok(())`: 撤销成功
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn undo_last_operation(db: State<'_, GlobalDatabase>) -> Result<(), String> {
+    FileSystemService::undo_last(&*db).await
 }
 
 /// 删除文件或文件夹
@@ -284,30 +912,178 @@ pub async fn rename_file(
 #[tauri::command]
 pub async fn delete_files(
     db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
     paths: Vec<String>,
 ) -> Result<(), String> {
-    FileSystemService::delete_files(&*db, &paths).await
+    FileSystemService::delete_files(&*db, &*global_config, &paths).await
+}
+
+/// 逐个删除文件或文件夹
+///
+/// 单个路径删除失败不影响其他路径，便于界面分别提示成功和失败的项
+///
+/// # 参数
+/// - `paths`: 要删除的文件/文件夹路径列表
+///
+/// # 返回
+/// - `Ok(Vec<(String, Result<(), String>)>)`: 每个路径及其删除结果
+/// - `Err(String)`: 数据库错误
+#[tauri::command]
+pub async fn delete_files_each(
+    db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
+    paths: Vec<String>,
+) -> Result<Vec<(String, Result<(), String>)>, String> {
+    FileSystemService::delete_files_each(&*db, &*global_config, &paths).await
+}
+
+/// 按文件名查找已登记的文件
+///
+/// 在数据库中查找文件名完全一致的文件记录，无需遍历文件系统
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `name`: 文件/文件夹名称
+/// - `global_config`: 全局配置管理器状态
+///
+/// # 返回
+/// - `Ok(Vec<FileItem>)`: 匹配的文件列表
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn find_files_by_name(
+    db: State<'_, GlobalDatabase>,
+    name: String,
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<Vec<FileItem>, String> {
+    FileSystemService::find_by_name(&*db, &name, &global_config.get_display_options()).await
 }
 
 /// 批量添加标签到文件/文件夹
 ///
-/// 为指定的文件/文件夹列表添加标签。如果文件记录不存在，会自动创建。
+/// 为指定的文件/文件夹列表添加标签。如果文件记录不存在，会自动创建
+/// （全局配置 auto_register_files 为 false 时不自动创建）。
 ///
 /// # 参数
 /// - `db`: 全局数据库实例
+/// - `global_config`: 全局配置管理器状态
 /// - `paths`: 要添加标签的文件/文件夹路径列表
 /// - `tag_id`: 标签ID
 ///
 /// # 返回
 /// - `Ok(())`: 操作成功
-/// - `Err(String)`: 错误信息
+/// - `Err(String)`: 错误信息（关闭自动登记时，未登记的文件返回 "文件未注册"）
 #[tauri::command]
 pub async fn add_tags_to_files(
     db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
     paths: Vec<String>,
     tag_id: i32,
 ) -> Result<(), String> {
-    TagService::add_tags_to_files(&*db, paths, tag_id).await
+    TagService::add_tags_to_files(&*db, &*global_config, paths, tag_id).await
+}
+
+/// 批量为文件/文件夹添加多个标签
+///
+/// 在一个事务中为所有路径添加所有标签，任一路径失败时整体回滚
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `global_config`: 全局配置管理器状态
+/// - `paths`: 要添加标签的文件/文件夹路径列表
+/// - `tag_ids`: 标签ID列表
+///
+/// # 返回
+/// - `Ok(())`: 操作成功
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn add_multiple_tags_to_files(
+    db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
+    paths: Vec<String>,
+    tag_ids: Vec<i32>,
+) -> Result<(), String> {
+    TagService::add_multiple_tags_to_files(&*db, &*global_config, paths, tag_ids).await
+}
+
+/// 清除文件/文件夹上的所有标签
+///
+/// 用于"重置标签"操作，没有文件记录的路径会被跳过
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `paths`: 文件/文件夹路径列表
+///
+/// # 返回
+/// - `Ok(())`: 操作成功
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn clear_tags_from_files(
+    db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
+    paths: Vec<String>,
+) -> Result<(), String> {
+    TagService::clear_tags_from_files(&*db, &*global_config, paths).await
+}
+
+/// 统计标签的使用趋势
+///
+/// 按日期统计每天被添加该标签的文件数量，用于使用趋势图表
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `global_config`: 全局配置管理器状态
+/// - `tag_id`: 标签ID
+///
+/// # 返回
+/// - `Ok(Vec<(String, i64)>)`: (日期 YYYY-MM-DD, 文件数量) 列表，按日期升序
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn get_tag_activity(
+    db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
+    tag_id: i32,
+) -> Result<Vec<(String, i64)>, String> {
+    TagService::tag_activity(&*db, &*global_config, tag_id).await
+}
+
+/// 根据扩展名推荐标签
+///
+/// 推荐同扩展名文件上最常用的标签，加快打标签操作
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `global_config`: 全局配置管理器状态
+/// - `path`: 待打标签的文件路径
+///
+/// # 返回
+/// - `Ok(Vec<Tag>)`: 推荐的标签列表，按同扩展名文件上出现的次数降序
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn suggest_tags(
+    db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
+    path: String,
+) -> Result<Vec<Tag>, String> {
+    TagService::suggest_tags(&*db, &*global_config, &path).await
+}
+
+/// 获取标签统计概况
+///
+/// 返回标签总数、有标签的文件数、平均每个文件的标签数以及使用次数最多的5个标签
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `global_config`: 全局配置管理器状态
+///
+/// # 返回
+/// - `Ok(TagStats)`: 统计结果
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn get_tag_statistics(
+    db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<TagStats, String> {
+    TagService::get_statistics(&*db, &*global_config).await
 }
 
 /// 根据标签ID搜索文件
@@ -316,9 +1092,11 @@ pub async fn add_tags_to_files(
 ///
 /// # 参数
 /// - `db`: 全局数据库实例
+/// - `global_config`: 全局配置管理器状态
 /// - `tag_id`: 标签ID
 /// - `page`: 页码（从1开始），默认为1
-/// - `page_size`: 每页数量，默认为50
+/// - `page_size`: 每页数量，默认使用全局配置（50）
+/// - `base_path`: 基准目录（可选），指定时为每项填充相对路径用于显示
 ///
 /// # 返回
 /// - `Ok(SearchResult)`: 搜索结果
@@ -326,9 +1104,173 @@ pub async fn add_tags_to_files(
 #[tauri::command]
 pub async fn search_files_by_tag(
     db: State<'_, GlobalDatabase>,
+    global_config: State<'_, GlobalConfigManager>,
     tag_id: i32,
     page: Option<usize>,
     page_size: Option<usize>,
+    base_path: Option<String>,
+) -> Result<SearchResult, String> {
+    let mut result = TagService::search_files_by_tag(&*db, &*global_config, tag_id, page, page_size).await?;
+    FileSystemService::relativize_result(&mut result, base_path.as_deref());
+    Ok(result)
+}
+
+/// 在已登记的文件中按路径搜索
+///
+/// 只查询数据库中已登记的文件，不遍历文件系统
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `query`: 路径关键词（不区分大小写的包含匹配）
+/// - `page`: 页码（从1开始）
+/// - `page_size`: 每页数量
+/// - `base_path`: 基准目录（可选），指定时为每项填充相对路径用于显示
+/// - `global_config`: 全局配置管理器状态
+///
+/// # 返回
+/// - `Ok(SearchResult)`: 搜索结果
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn search_registered_files(
+    db: State<'_, GlobalDatabase>,
+    query: String,
+    page: usize,
+    page_size: usize,
+    base_path: Option<String>,
+    global_config: State<'_, GlobalConfigManager>,
 ) -> Result<SearchResult, String> {
-    TagService::search_files_by_tag(&*db, tag_id, page, page_size).await
-}
\ No newline at end of file
+    let display = global_config.get_display_options();
+    let mut result = FileSystemService::search_registered_files(&*db, &query, page, page_size, &display).await?;
+    FileSystemService::relativize_result(&mut result, base_path.as_deref());
+    Ok(result)
+}
+
+/// 列出未添加标签的文件
+///
+/// 返回已登记但没有任何标签的文件，便于用户整理
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `limit`: 最多返回的记录数
+/// - `offset`: 跳过的记录数
+/// - `global_config`: 全局配置管理器状态
+///
+/// # 返回
+/// - `Ok(Vec<FileItem>)`: 文件列表
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn list_untagged_files(
+    db: State<'_, GlobalDatabase>,
+    limit: usize,
+    offset: usize,
+    global_config: State<'_, GlobalConfigManager>,
+) -> Result<Vec<FileItem>, String> {
+    FileSystemService::list_untagged(&*db, limit, offset, &global_config.get_display_options()).await
+}
+
+/// 对账文件记录与磁盘
+///
+/// 软删除磁盘上已不存在的文件记录（如在应用外被删除的文件）
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+///
+/// # 返回
+/// - `Ok(ReconcileReport)`: 检查和软删除的记录数
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn reconcile_files(db: State<'_, GlobalDatabase>) -> Result<ReconcileReport, String> {
+    FileSystemService::reconcile(&*db).await
+}
+
+/// 检查数据库健康状态
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+///
+/// # 返回
+/// - `Ok(bool)`: 数据库是否可用
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn check_database_health(db: State<'_, GlobalDatabase>) -> Result<bool, String> {
+    DatabaseService::check_health(&*db).await
+}
+
+/// 重新建立数据库连接
+///
+/// 健康检查失败时由前端调用，关闭失效的连接池并重新初始化
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+///
+/// # 返回
+/// - `Ok(())`: 重新连接成功
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn reconnect_database(db: State<'_, GlobalDatabase>) -> Result<(), String> {
+    DatabaseService::reconnect(&*db).await
+}
+
+/// 清理过期的软删除记录
+///
+/// 维护操作：永久删除软删除超过指定天数的文件和标签记录及其标签关联
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `older_than_days`: 保留天数
+///
+/// # 返回
+/// - `Ok(u64)`: 删除的记录数
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn purge_deleted_records(db: State<'_, GlobalDatabase>, older_than_days: i64) -> Result<u64, String> {
+    DatabaseService::purge_deleted(&*db, older_than_days).await
+}
+
+/// 获取最近一次数据库错误
+///
+/// 用于在界面上展示查询失败的原因，便于用户反馈问题
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+///
+/// # 返回
+/// - `Ok(Option<String>)`: 最近一次错误信息，尚未出错时为 None
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn get_last_db_error(db: State<'_, GlobalDatabase>) -> Result<Option<String>, String> {
+    Ok(DatabaseService::last_error(&*db))
+}
+
+/// 获取当前数据库配置
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+///
+/// # 返回
+/// - `Ok(DatabaseConfig)`: 当前数据库配置（密码已隐藏）
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn get_current_db_config(db: State<'_, GlobalDatabase>) -> Result<DatabaseConfig, String> {
+    Ok(DatabaseService::current_config(&*db))
+}
+
+/// 切换数据库
+///
+/// 在运行时切换到新的数据库配置，无需修改配置文件或重启应用。
+/// 新数据库连接并迁移成功后才会替换当前数据库
+///
+/// # 参数
+/// - `db`: 全局数据库实例
+/// - `config`: 新的数据库配置
+///
+/// # 返回
+/// - `Ok(())`: 切换成功
+/// - `Err(String)`: 错误信息
+#[tauri::command]
+pub async fn switch_database(
+    db: State<'_, GlobalDatabase>,
+    config: DatabaseConfig,
+) -> Result<(), String> {
+    DatabaseService::switch_database(&*db, config).await
+}
@@ -13,12 +13,276 @@ pub struct GlobalConfig {
     /// 用户主目录路径（可选）
     /// 如果设置，get_home_directory 将优先使用此路径
     pub home_path: Option<String>,
+    /// 是否自动登记文件（默认：true）
+    /// 为 false 时，只能为已登记在 files 表中的文件添加标签
+    #[serde(default = "default_true")]
+    pub auto_register_files: bool,
+    /// 查询默认值（调用方未指定数量、排序等参数时使用）
+    #[serde(default)]
+    pub query_defaults: QueryDefaults,
+    /// 递归复制目录时允许的最大层级，防止异常目录结构导致栈溢出
+    #[serde(default = "default_max_directory_depth")]
+    pub max_directory_depth: usize,
+    /// 同时执行的文件操作（复制、移动）数量上限，None 表示使用 CPU 核心数
+    #[serde(default)]
+    pub max_concurrent_file_ops: Option<usize>,
+    /// 额外的受保护路径，与内置列表合并；受保护路径及其上级目录禁止删除
+    #[serde(default)]
+    pub protected_paths: Vec<String>,
+    /// 流式复制大文件时的缓冲区大小（字节）
+    #[serde(default = "default_copy_buffer_size")]
+    pub copy_buffer_size: usize,
+    /// 超过该大小（字节）的文件复制时改为分块读写
+    #[serde(default = "default_stream_copy_threshold")]
+    pub stream_copy_threshold: u64,
+    /// 文件时间戳的展示时区（默认：UTC），数据库中的时间始终以 UTC 存储
+    #[serde(default)]
+    pub display_timezone: DisplayTimezone,
+    /// 新建标签的默认颜色（`#RRGGBB`），未指定颜色时按已有标签数量依次循环取用；为空时使用数据库默认颜色
+    #[serde(default)]
+    pub default_tag_colors: Vec<String>,
+    /// 是否增量维护标签使用次数（默认：false）
+    /// 为 true 时在添加/删除关联的同一事务中原子加减，否则每次操作后重新统计
+    #[serde(default)]
+    pub incremental_usage_count: bool,
+    /// 增量维护时全量重新统计使用次数的间隔（秒），用于修正偏差；为 0 时不定期统计
+    #[serde(default = "default_usage_recount_interval_secs")]
+    pub usage_recount_interval_secs: u64,
+    /// 批量添加标签时一次允许的最大路径数量，超过时直接拒绝
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+    /// 新建和重命名时是否自动替换当前系统不允许的文件名字符（默认：false）
+    #[serde(default)]
+    pub sanitize_file_names: bool,
+    /// 慢查询阈值（毫秒），耗时超过该值的标签查询会输出警告日志
+    #[serde(default = "default_slow_query_threshold_ms")]
+    pub slow_query_threshold_ms: u64,
+    /// get_home_directory 依次尝试的主目录来源，第一个有值的来源生效
+    /// `config` 表示 home_path，`env:NAME` 表示环境变量，`env:A+B` 表示拼接多个环境变量
+    #[serde(default = "default_home_resolution")]
+    pub home_resolution: Vec<String>,
+    /// 判断文件是否隐藏的策略（默认：both），决定目录列表中 `is_hidden` 的取值
+    #[serde(default)]
+    pub hidden_detection: HiddenDetection,
+    /// 文件大小的展示单位制（默认：binary），决定 `FileItem.size_display` 的格式
+    #[serde(default)]
+    pub size_units: SizeUnits,
+    /// 驱动盘列表中显示的驱动盘类型（仅 Windows，默认：全部显示）
+    #[serde(default = "default_drive_types")]
+    pub drive_types: Vec<DriveType>,
+    /// 递归遍历最多返回的结果数量（按内容搜索的匹配文件数、导出目录树的节点数），
+    /// 达到上限后停止遍历并标记结果被截断
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
 }
 
+/// 时间戳展示时区
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayTimezone {
+    /// UTC，时间以 `Z` 结尾
+    #[default]
+    #[serde(rename = "utc")]
+    Utc,
+    /// 系统本地时区，时间带 `+08:00` 形式的偏移
+    #[serde(rename = "local")]
+    Local,
+}
+
+/// 隐藏文件判断策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HiddenDetection {
+    /// 文件名以 `.` 开头（Unix 惯例）
+    #[serde(rename = "dot_prefix")]
+    DotPrefix,
+    /// 系统的隐藏属性（Windows 的隐藏属性、macOS 的 UF_HIDDEN 标志）
+    #[serde(rename = "platform_attribute")]
+    PlatformAttribute,
+    /// 满足以上任一条件即视为隐藏
+    #[default]
+    #[serde(rename = "both")]
+    Both,
+}
+
+/// 文件大小展示单位制
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SizeUnits {
+    /// 二进制单位，按 1024 进位（KiB、MiB、GiB）
+    #[default]
+    #[serde(rename = "binary")]
+    Binary,
+    /// 十进制单位，按 1000 进位（KB、MB、GB）
+    #[serde(rename = "decimal")]
+    Decimal,
+}
+
+/// 驱动盘类型（对应 Windows `GetDriveType` 的返回值）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DriveType {
+    /// 本地硬盘
+    #[serde(rename = "fixed")]
+    Fixed,
+    /// 可移动磁盘（U 盘、读卡器等）
+    #[serde(rename = "removable")]
+    Removable,
+    /// 网络驱动器
+    #[serde(rename = "network")]
+    Network,
+    /// 光驱
+    #[serde(rename = "cdrom")]
+    Cdrom,
+    /// 内存盘
+    #[serde(rename = "ramdisk")]
+    Ramdisk,
+}
+
+impl DriveType {
+    /// 全部驱动盘类型
+    pub const ALL: [DriveType; 5] = [
+        DriveType::Fixed,
+        DriveType::Removable,
+        DriveType::Network,
+        DriveType::Cdrom,
+        DriveType::Ramdisk,
+    ];
+}
+
+fn default_drive_types() -> Vec<DriveType> {
+    DriveType::ALL.to_vec()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_directory_depth() -> usize {
+    256
+}
+
+fn default_max_results() -> usize {
+    10_000
+}
+
+fn default_copy_buffer_size() -> usize {
+    1024 * 1024
+}
+
+fn default_stream_copy_threshold() -> u64 {
+    64 * 1024 * 1024
+}
+
+fn default_usage_recount_interval_secs() -> u64 {
+    3600
+}
+
+fn default_max_batch_size() -> usize {
+    10000
+}
+
+fn default_slow_query_threshold_ms() -> u64 {
+    500
+}
+
+fn default_home_resolution() -> Vec<String> {
+    let sources: &[&str] = if cfg!(windows) {
+        &["config", "env:USERPROFILE", "env:HOMEDRIVE+HOMEPATH"]
+    } else if cfg!(unix) {
+        &["env:HOME"]
+    } else {
+        &[]
+    };
+    sources.iter().map(|source| source.to_string()).collect()
+}
+
+/// 内置的受保护路径（系统根目录和系统目录）
+const BUILTIN_PROTECTED_PATHS: &[&str] = &[
+    "/",
+    "/System",
+    "/bin",
+    "/etc",
+    "/usr",
+    r"C:\",
+    r"C:\Windows",
+    r"C:\Program Files",
+    r"C:\Program Files (x86)",
+];
+
 impl Default for GlobalConfig {
     fn default() -> Self {
         Self {
             home_path: None,
+            auto_register_files: true,
+            query_defaults: QueryDefaults::default(),
+            max_directory_depth: default_max_directory_depth(),
+            max_concurrent_file_ops: None,
+            protected_paths: Vec::new(),
+            copy_buffer_size: default_copy_buffer_size(),
+            stream_copy_threshold: default_stream_copy_threshold(),
+            display_timezone: DisplayTimezone::default(),
+            default_tag_colors: Vec::new(),
+            incremental_usage_count: false,
+            usage_recount_interval_secs: default_usage_recount_interval_secs(),
+            max_batch_size: default_max_batch_size(),
+            sanitize_file_names: false,
+            slow_query_threshold_ms: default_slow_query_threshold_ms(),
+            home_resolution: default_home_resolution(),
+            hidden_detection: HiddenDetection::default(),
+            size_units: SizeUnits::default(),
+            drive_types: default_drive_types(),
+            max_results: default_max_results(),
+        }
+    }
+}
+
+/// 查询默认值配置
+///
+/// 对应配置文件中的 `[query_defaults]` 配置节，未设置的字段使用默认值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryDefaults {
+    /// 获取标签列表的默认数量
+    pub tag_list_limit: i32,
+    /// 获取标签列表的默认排序模式："most_used" 或 "recent_used"
+    pub tag_list_mode: String,
+    /// 搜索标签的默认返回数量
+    pub search_tags_limit: i32,
+    /// 分页查询的默认每页数量
+    pub page_size: usize,
+}
+
+impl Default for QueryDefaults {
+    fn default() -> Self {
+        Self {
+            tag_list_limit: 10,
+            tag_list_mode: "most_used".to_string(),
+            search_tags_limit: 50,
+            page_size: 50,
+        }
+    }
+}
+
+/// 构建 FileItem 时使用的展示选项
+///
+/// 由 `GlobalConfigManager::get_display_options` 从全局配置读取，
+/// 命令层读取后传给列出目录、搜索等构建 FileItem 的服务方法
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// 时间戳展示时区
+    pub timezone: DisplayTimezone,
+    /// 隐藏文件判断策略
+    pub hidden_detection: HiddenDetection,
+    /// 文件大小展示单位制
+    pub size_units: SizeUnits,
+    /// 驱动盘列表中显示的驱动盘类型（仅 Windows）
+    pub drive_types: Vec<DriveType>,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            timezone: DisplayTimezone::default(),
+            hidden_detection: HiddenDetection::default(),
+            size_units: SizeUnits::default(),
+            drive_types: default_drive_types(),
         }
     }
 }
@@ -26,7 +290,10 @@ impl Default for GlobalConfig {
 impl GlobalConfig {
     /// 创建新的全局配置
     pub fn new(home_path: Option<String>) -> Self {
-        Self { home_path }
+        Self {
+            home_path,
+            ..Self::default()
+        }
     }
 
     /// 从 TOML 文件加载配置
@@ -52,6 +319,10 @@ impl GlobalConfig {
         let mut config: GlobalConfig = toml::from_str(&content)
             .map_err(|e| format!("解析全局配置文件失败 {}: {}", path.display(), e))?;
 
+        if let Some(color) = config.default_tag_colors.iter().find(|c| !crate::utils::is_hex_color(c)) {
+            return Err(format!("默认标签颜色格式无效 {}: {}", path.display(), color));
+        }
+
         // 如果 home_path 是空字符串，则转换为 None
         if let Some(ref home_path) = config.home_path {
             if home_path.is_empty() {
@@ -131,6 +402,149 @@ impl GlobalConfigManager {
         config.home_path = path;
     }
 
+    /// 是否自动登记文件
+    ///
+    /// # 返回
+    /// - `true`: 添加标签时自动在 files 表中创建不存在的文件记录
+    /// - `false`: 只允许为已登记的文件添加标签
+    pub fn get_auto_register_files(&self) -> bool {
+        let config = self.config.read().unwrap();
+        config.auto_register_files
+    }
+
+    /// 获取文件操作并发数上限
+    ///
+    /// 未配置时使用 CPU 核心数
+    pub fn get_max_concurrent_file_ops(&self) -> usize {
+        let config = self.config.read().unwrap();
+        config.max_concurrent_file_ops.unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        })
+    }
+
+    /// 获取递归复制目录的最大层级
+    pub fn get_max_directory_depth(&self) -> usize {
+        let config = self.config.read().unwrap();
+        config.max_directory_depth
+    }
+
+    /// 获取递归遍历最多返回的结果数量
+    pub fn get_max_results(&self) -> usize {
+        let config = self.config.read().unwrap();
+        config.max_results
+    }
+
+    /// 获取流式复制的缓冲区大小
+    pub fn get_copy_buffer_size(&self) -> usize {
+        let config = self.config.read().unwrap();
+        config.copy_buffer_size
+    }
+
+    /// 获取改为流式复制的文件大小阈值
+    pub fn get_stream_copy_threshold(&self) -> u64 {
+        let config = self.config.read().unwrap();
+        config.stream_copy_threshold
+    }
+
+    /// 获取时间戳展示时区
+    pub fn get_display_timezone(&self) -> DisplayTimezone {
+        let config = self.config.read().unwrap();
+        config.display_timezone
+    }
+
+    /// 获取构建 FileItem 时使用的展示选项
+    pub fn get_display_options(&self) -> DisplayOptions {
+        let config = self.config.read().unwrap();
+        DisplayOptions {
+            timezone: config.display_timezone,
+            hidden_detection: config.hidden_detection,
+            size_units: config.size_units,
+            drive_types: config.drive_types.clone(),
+        }
+    }
+
+    /// 获取新建标签的默认颜色列表
+    pub fn get_default_tag_colors(&self) -> Vec<String> {
+        let config = self.config.read().unwrap();
+        config.default_tag_colors.clone()
+    }
+
+    /// 是否增量维护标签使用次数
+    pub fn get_incremental_usage_count(&self) -> bool {
+        let config = self.config.read().unwrap();
+        config.incremental_usage_count
+    }
+
+    /// 获取全量重新统计标签使用次数的间隔
+    pub fn get_usage_recount_interval(&self) -> std::time::Duration {
+        let config = self.config.read().unwrap();
+        std::time::Duration::from_secs(config.usage_recount_interval_secs)
+    }
+
+    /// 获取批量添加标签时允许的最大路径数量
+    pub fn get_max_batch_size(&self) -> usize {
+        let config = self.config.read().unwrap();
+        config.max_batch_size
+    }
+
+    /// 新建和重命名时是否自动清理文件名
+    pub fn get_sanitize_file_names(&self) -> bool {
+        let config = self.config.read().unwrap();
+        config.sanitize_file_names
+    }
+
+    /// 获取慢查询阈值
+    pub fn get_slow_query_threshold(&self) -> std::time::Duration {
+        let config = self.config.read().unwrap();
+        std::time::Duration::from_millis(config.slow_query_threshold_ms)
+    }
+
+    /// 获取隐藏文件判断策略
+    pub fn get_hidden_detection(&self) -> HiddenDetection {
+        let config = self.config.read().unwrap();
+        config.hidden_detection
+    }
+
+    /// 获取文件大小展示单位制
+    pub fn get_size_units(&self) -> SizeUnits {
+        let config = self.config.read().unwrap();
+        config.size_units
+    }
+
+    /// 获取驱动盘列表中显示的驱动盘类型
+    pub fn get_drive_types(&self) -> Vec<DriveType> {
+        let config = self.config.read().unwrap();
+        config.drive_types.clone()
+    }
+
+    /// 获取主目录来源的解析顺序
+    pub fn get_home_resolution(&self) -> Vec<String> {
+        let config = self.config.read().unwrap();
+        config.home_resolution.clone()
+    }
+
+    /// 获取受保护路径列表
+    ///
+    /// 包含内置的系统路径、用户主目录（系统主目录和配置的主目录）以及配置中追加的路径
+    pub fn get_protected_paths(&self) -> Vec<String> {
+        let config = self.config.read().unwrap();
+        let mut paths: Vec<String> = BUILTIN_PROTECTED_PATHS.iter().map(|p| p.to_string()).collect();
+        if let Some(home) = dirs::home_dir() {
+            paths.push(home.to_string_lossy().to_string());
+        }
+        paths.extend(config.home_path.iter().cloned());
+        paths.extend(config.protected_paths.iter().cloned());
+        paths
+    }
+
+    /// 获取查询默认值
+    pub fn get_query_defaults(&self) -> QueryDefaults {
+        let config = self.config.read().unwrap();
+        config.query_defaults.clone()
+    }
+
     /// 获取完整的配置对象（克隆）
     pub fn get_config(&self) -> GlobalConfig {
         let config = self.config.read().unwrap();
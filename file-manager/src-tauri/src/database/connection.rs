@@ -2,14 +2,16 @@
 //!
 //! 提供数据库连接池管理和连接操作
 
-use sqlx::{Pool, Postgres, Sqlite};
+use sqlx::{Executor, Pool, Postgres, Sqlite};
+use sqlx::migrate::Migrator;
 use sqlx::postgres::PgPoolOptions;
-use sqlx::sqlite::SqlitePoolOptions;
-use std::sync::Arc;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use std::sync::{Arc, PoisonError, RwLock};
 use tokio::sync::Mutex;
 
-use crate::database::config::DatabaseConfig;
+use crate::database::config::{DatabaseConfig, DatabaseType};
 use crate::database::error::{DatabaseError, DatabaseResult};
+use crate::database::lock::{FileLock, MIGRATION_LOCK_TIMEOUT, PG_MIGRATION_LOCK_KEY};
 
 /// 数据库连接枚举
 pub enum DatabaseConnection {
@@ -25,6 +27,8 @@ pub struct DatabaseManager {
     config: DatabaseConfig,
     /// 数据库连接池（使用Arc和Mutex实现线程安全）
     connection: Arc<Mutex<Option<DatabaseConnection>>>,
+    /// 最近一次遇到的数据库错误（初始化、重连、健康检查、迁移）
+    last_error: std::sync::Mutex<Option<String>>,
 }
 
 impl DatabaseManager {
@@ -33,6 +37,7 @@ impl DatabaseManager {
         Self {
             config,
             connection: Arc::new(Mutex::new(None)),
+            last_error: std::sync::Mutex::new(None),
         }
     }
 
@@ -41,6 +46,27 @@ impl DatabaseManager {
         &self.config
     }
 
+    /// 获取数据库类型（无需获取连接）
+    pub fn db_type(&self) -> DatabaseType {
+        self.config.db_type
+    }
+
+    /// 获取最近一次遇到的数据库错误
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// 失败时记录错误信息，结果原样返回
+    fn track<T>(&self, result: DatabaseResult<T>) -> DatabaseResult<T> {
+        if let Err(e) = &result {
+            *self.last_error.lock().unwrap_or_else(PoisonError::into_inner) = Some(e.to_string());
+        }
+        result
+    }
+
     /// 初始化数据库连接
     pub async fn init(&self) -> DatabaseResult<()> {
         let mut connection = self.connection.lock().await;
@@ -49,16 +75,62 @@ impl DatabaseManager {
             return Ok(());
         }
 
+        *connection = Some(self.track(self.create_connection().await)?);
+        Ok(())
+    }
+
+    /// 重新建立数据库连接
+    ///
+    /// 关闭当前连接池（可能持有已失效的连接）后重新初始化。
+    /// 整个过程持有互斥锁，并发调用时会依次执行，不会互相关闭对方新建的连接池。
+    pub async fn reconnect(&self) -> DatabaseResult<()> {
+        let mut connection = self.connection.lock().await;
+
+        if let Some(conn) = connection.take() {
+            match conn {
+                DatabaseConnection::Postgres(pool) => {
+                    pool.close().await;
+                }
+                DatabaseConnection::Sqlite(pool) => {
+                    pool.close().await;
+                }
+            }
+        }
+
+        *connection = Some(self.track(self.create_connection().await)?);
+        Ok(())
+    }
+
+    /// 根据配置创建新的数据库连接池
+    async fn create_connection(&self) -> DatabaseResult<DatabaseConnection> {
         let db_connection = match self.config.db_type {
-            crate::database::config::DatabaseType::Postgres => {
+            DatabaseType::Postgres => {
                 let conn_str = self.config.connection_string()
                     .map_err(|e| DatabaseError::Config(e))?;
 
-                let pool = PgPoolOptions::new()
+                let mut pool_options = PgPoolOptions::new()
                     .max_connections(self.config.max_connections)
-                    .acquire_timeout(std::time::Duration::from_secs(self.config.connect_timeout))
-                    .connect_lazy(&conn_str)
-                    .map_err(|e| DatabaseError::Connection(e.to_string()))?;
+                    .min_connections(self.config.min_connections.unwrap_or(0))
+                    .acquire_timeout(std::time::Duration::from_secs(self.config.connect_timeout));
+
+                // 为每个新建立的连接设置语句超时，防止长时间运行的查询阻塞应用
+                if let Some(timeout_ms) = self.config.statement_timeout_ms {
+                    pool_options = pool_options.after_connect(move |conn, _meta| {
+                        Box::pin(async move {
+                            conn.execute(format!("SET statement_timeout = {}", timeout_ms).as_str())
+                                .await?;
+                            Ok(())
+                        })
+                    });
+                }
+
+                // 非延迟模式下立即建立连接，数据库不可达时初始化直接失败
+                let pool = if self.config.lazy {
+                    pool_options.connect_lazy(&conn_str)
+                } else {
+                    pool_options.connect(&conn_str).await
+                }
+                .map_err(|e| DatabaseError::Connection(e.to_string()))?;
 
                 // 测试连接
                 sqlx::query("SELECT 1")
@@ -68,7 +140,7 @@ impl DatabaseManager {
 
                 DatabaseConnection::Postgres(pool)
             }
-            crate::database::config::DatabaseType::Sqlite => {
+            DatabaseType::Sqlite => {
                 let conn_str = self.config.connection_string()
                     .map_err(|e| DatabaseError::Config(e))?;
 
@@ -85,14 +157,48 @@ impl DatabaseManager {
                 }
 
                 println!("SQLite连接字符串: {}", conn_str);
-                let pool = SqlitePoolOptions::new()
-                    .max_connections(self.config.max_connections)
+                // 外键约束是连接级别的设置，需要在每个新建立的连接上显式开启或关闭
+                let foreign_keys = if self.config.foreign_keys { "ON" } else { "OFF" };
+                // cache_size 同样是连接级别的设置
+                let cache_size = self.config.sqlite_cache_size;
+                let max_connections = self.config.effective_max_connections();
+                if max_connections < self.config.max_connections {
+                    eprintln!(
+                        "SQLite 最大连接数 {} 超过上限，已调整为 {}",
+                        self.config.max_connections, max_connections
+                    );
+                }
+                let pool_options = SqlitePoolOptions::new()
+                    .max_connections(max_connections)
+                    .min_connections(self.config.min_connections.unwrap_or(0).min(max_connections))
                     .acquire_timeout(std::time::Duration::from_secs(self.config.connect_timeout))
-                    .connect_lazy(&conn_str)
-                    .map_err(|e| {
-                        println!("SQLite连接失败: {}", e);
-                        DatabaseError::Connection(e.to_string())
-                    })?;
+                    .after_connect(move |conn, _meta| {
+                        Box::pin(async move {
+                            conn.execute(format!("PRAGMA foreign_keys = {}", foreign_keys).as_str())
+                                .await?;
+                            if let Some(cache_size) = cache_size {
+                                conn.execute(format!("PRAGMA cache_size = {}", cache_size).as_str())
+                                    .await?;
+                            }
+                            Ok(())
+                        })
+                    });
+                // page_size 必须在切换日志模式等任何写入之前设置，因此通过连接选项而不是 after_connect 设置；
+                // 它只对尚未写入内容的新数据库生效，对已有数据的数据库是无害的空操作
+                let mut connect_options: SqliteConnectOptions = conn_str.parse()
+                    .map_err(|e: sqlx::Error| DatabaseError::Config(e.to_string()))?;
+                if let Some(page_size) = self.config.sqlite_page_size {
+                    connect_options = connect_options.page_size(page_size);
+                }
+                let pool = if self.config.lazy {
+                    Ok(pool_options.connect_lazy_with(connect_options))
+                } else {
+                    pool_options.connect_with(connect_options).await
+                }
+                .map_err(|e| {
+                    println!("SQLite连接失败: {}", e);
+                    DatabaseError::Connection(e.to_string())
+                })?;
 
                 // 测试连接
                 sqlx::query("SELECT 1")
@@ -104,8 +210,7 @@ impl DatabaseManager {
             }
         };
 
-        *connection = Some(db_connection);
-        Ok(())
+        Ok(db_connection)
     }
 
     /// 获取数据库连接
@@ -143,6 +248,12 @@ impl DatabaseManager {
 
     /// 检查数据库连接状态
     pub async fn check_health(&self) -> DatabaseResult<bool> {
+        let result = self.ping().await;
+        self.track(result)
+    }
+
+    /// 执行 `SELECT 1` 检查连接是否可用
+    async fn ping(&self) -> DatabaseResult<bool> {
         let connection = self.get_connection().await?;
 
         match connection {
@@ -165,17 +276,57 @@ impl DatabaseManager {
 
     /// 执行数据库迁移
     pub async fn migrate(&self) -> DatabaseResult<()> {
+        let result = self.run_migrations().await;
+        self.track(result)
+    }
+
+    /// 运行当前数据库类型对应的迁移脚本（见 `migrator`）
+    ///
+    /// 迁移期间持有迁移锁，多个实例同时启动时依次执行；
+    /// 后执行的实例看到迁移已应用，不会重复执行
+    async fn run_migrations(&self) -> DatabaseResult<()> {
         let connection = self.get_connection().await?;
 
         match connection {
             DatabaseConnectionRef::Postgres(pool) => {
-                sqlx::migrate!("./migrations")
-                    .run(&pool)
+                // advisory lock 属于会话级，加锁、迁移和解锁必须使用同一个连接
+                let mut conn = pool
+                    .acquire()
                     .await
-                    .map_err(|e| DatabaseError::Migration(e.to_string()))?;
+                    .map_err(|e| DatabaseError::Connection(e.to_string()))?;
+
+                sqlx::query("SELECT pg_advisory_lock($1)")
+                    .bind(PG_MIGRATION_LOCK_KEY)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("获取迁移锁失败: {}", e)))?;
+
+                let result = migrator(DatabaseType::Postgres)
+                    .run(&mut *conn)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(e.to_string()));
+
+                // 无论迁移是否成功都释放锁
+                let unlocked = sqlx::query("SELECT pg_advisory_unlock($1)")
+                    .bind(PG_MIGRATION_LOCK_KEY)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("释放迁移锁失败: {}", e)));
+
+                result?;
+                unlocked?;
             }
             DatabaseConnectionRef::Sqlite(pool) => {
-                sqlx::migrate!("./migrations")
+                let _lock = match self.migration_lock_path() {
+                    Some(lock_path) => Some(
+                        FileLock::acquire(&lock_path, MIGRATION_LOCK_TIMEOUT)
+                            .await
+                            .map_err(DatabaseError::Migration)?,
+                    ),
+                    None => None,
+                };
+
+                migrator(DatabaseType::Sqlite)
                     .run(&pool)
                     .await
                     .map_err(|e| DatabaseError::Migration(e.to_string()))?;
@@ -184,6 +335,102 @@ impl DatabaseManager {
 
         Ok(())
     }
+
+    /// 检查数据库架构版本是否不低于程序需要的版本
+    ///
+    /// 关闭自动迁移时使用：比较 `_sqlx_migrations` 中已成功应用的最大版本与
+    /// 对应迁移目录中最新的迁移版本，未执行过迁移时视为版本 0
+    pub async fn verify_schema_version(&self) -> DatabaseResult<()> {
+        let result = self.check_schema_version().await;
+        self.track(result)
+    }
+
+    async fn check_schema_version(&self) -> DatabaseResult<()> {
+        let connection = self.get_connection().await?;
+        let required = required_schema_version(connection.kind());
+
+        let current: Option<i64> = match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                let exists: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+                    .fetch_one(&pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("查询架构版本失败: {}", e)))?;
+                if exists {
+                    sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+                        .fetch_one(&pool)
+                        .await
+                        .map_err(|e| DatabaseError::Migration(format!("查询架构版本失败: {}", e)))?
+                } else {
+                    None
+                }
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                let exists: bool = sqlx::query_scalar(
+                    "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+                )
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("查询架构版本失败: {}", e)))?;
+                if exists {
+                    sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+                        .fetch_one(&pool)
+                        .await
+                        .map_err(|e| DatabaseError::Migration(format!("查询架构版本失败: {}", e)))?
+                } else {
+                    None
+                }
+            }
+        };
+
+        let current = current.unwrap_or(0);
+        if current < required {
+            return Err(DatabaseError::Migration(format!(
+                "数据库架构版本过旧: 当前 {}，至少需要 {}，请先执行迁移",
+                current, required
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// SQLite 迁移锁文件路径（数据库文件旁的 `.migrate.lock` 文件）
+    ///
+    /// 内存数据库只属于当前进程，不需要加锁
+    fn migration_lock_path(&self) -> Option<std::path::PathBuf> {
+        let sqlite_path = self.config.sqlite_path.as_deref()?;
+        if sqlite_path.is_empty() || sqlite_path.contains(":memory:") {
+            return None;
+        }
+
+        Some(std::path::PathBuf::from(format!("{}.migrate.lock", sqlite_path)))
+    }
+}
+
+/// `verify_schema` 检查的核心表及其关键列
+const EXPECTED_SCHEMA: &[(&str, &[&str])] = &[
+    ("files", &["id", "current_path", "file_type", "file_size", "deleted_at"]),
+    ("tags", &["id", "name", "color", "font_color", "parent_id", "usage_count", "deleted_at"]),
+    ("file_tags", &["file_id", "tag_id"]),
+];
+
+/// 数据库类型对应的迁移脚本
+///
+/// PostgreSQL 使用 migrations 目录；SQLite 不支持其中的 plpgsql 函数、pg_trgm 等语法，
+/// 使用 migrations/sqlite 目录下的等价脚本，两套脚本的版本号一一对应
+fn migrator(kind: DatabaseType) -> Migrator {
+    match kind {
+        DatabaseType::Postgres => sqlx::migrate!("./migrations"),
+        DatabaseType::Sqlite => sqlx::migrate!("./migrations/sqlite"),
+    }
+}
+
+/// 程序需要的数据库架构版本（对应迁移目录中最新的迁移版本）
+pub fn required_schema_version(kind: DatabaseType) -> i64 {
+    migrator(kind)
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0)
 }
 
 /// 数据库连接引用枚举
@@ -195,6 +442,14 @@ pub enum DatabaseConnectionRef {
 }
 
 impl DatabaseConnectionRef {
+    /// 获取连接池对应的数据库类型
+    pub fn kind(&self) -> DatabaseType {
+        match self {
+            DatabaseConnectionRef::Postgres(_) => DatabaseType::Postgres,
+            DatabaseConnectionRef::Sqlite(_) => DatabaseType::Sqlite,
+        }
+    }
+
     /// 获取PostgreSQL连接池（如果是PostgreSQL类型）
     pub fn as_postgres(&self) -> Option<&Pool<Postgres>> {
         match self {
@@ -213,46 +468,252 @@ impl DatabaseConnectionRef {
 }
 
 /// 全局数据库管理器实例
+///
+/// 内部的管理器可在运行时整体替换（切换数据库），因此使用读写锁保存，
+/// 各方法先取出当前管理器的 Arc 再执行异步操作，不会跨 await 持有锁
 pub struct GlobalDatabase {
-    manager: Arc<DatabaseManager>,
+    manager: RwLock<Arc<DatabaseManager>>,
 }
 
 impl GlobalDatabase {
     /// 创建全局数据库实例
     pub fn new(config: DatabaseConfig) -> Self {
         Self {
-            manager: Arc::new(DatabaseManager::new(config)),
+            manager: RwLock::new(Arc::new(DatabaseManager::new(config))),
         }
     }
 
-    /// 获取数据库管理器引用
-    pub fn manager(&self) -> &DatabaseManager {
-        &self.manager
+    /// 获取当前数据库管理器
+    pub fn manager(&self) -> Arc<DatabaseManager> {
+        self.manager
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// 获取当前数据库配置
+    pub fn config(&self) -> DatabaseConfig {
+        self.manager().config().clone()
+    }
+
+    /// 获取数据库类型（无需获取连接）
+    pub fn db_type(&self) -> DatabaseType {
+        self.manager().db_type()
+    }
+
+    /// 获取当前数据库最近一次遇到的错误
+    ///
+    /// 切换数据库后从新数据库重新开始记录
+    pub fn last_error(&self) -> Option<String> {
+        self.manager().last_error()
     }
 
     /// 初始化全局数据库连接
     pub async fn init(&self) -> DatabaseResult<()> {
-        self.manager.init().await
+        self.manager().init().await
     }
 
     /// 获取数据库连接
     pub async fn get_connection(&self) -> DatabaseResult<DatabaseConnectionRef> {
-        self.manager.get_connection().await
+        self.manager().get_connection().await
     }
 
     /// 检查数据库健康状态
     pub async fn check_health(&self) -> DatabaseResult<bool> {
-        self.manager.check_health().await
+        self.manager().check_health().await
     }
 
     /// 执行数据库迁移
     pub async fn migrate(&self) -> DatabaseResult<()> {
-        self.manager.migrate().await
+        self.manager().migrate().await
+    }
+
+    /// 检查数据库架构版本是否满足要求（不执行迁移）
+    pub async fn verify_schema_version(&self) -> DatabaseResult<()> {
+        self.manager().verify_schema_version().await
+    }
+
+    /// 重新建立数据库连接
+    pub async fn reconnect(&self) -> DatabaseResult<()> {
+        self.manager().reconnect().await
     }
 
     /// 关闭数据库连接
     pub async fn close(&self) -> DatabaseResult<()> {
-        self.manager.close().await
+        self.manager().close().await
+    }
+
+    /// 永久删除软删除时间早于指定天数的文件和标签记录
+    ///
+    /// 先删除相关的 file_tags 关联（SQLite 可能未启用外键级联），再删除记录本身。
+    /// 仍有未到期子标签的标签暂不删除，避免 `parent_id` 级联删除子标签
+    ///
+    /// # 参数
+    /// - `older_than_days`: 保留天数，`deleted_at` 早于当前时间减去该天数的记录会被删除
+    ///
+    /// # 返回
+    /// - `Ok(u64)`: 删除的文件和标签记录总数
+    /// - `Err(String)`: 错误信息
+    pub async fn purge_deleted(&self, older_than_days: i64) -> Result<u64, String> {
+        if older_than_days < 0 {
+            return Err(format!("保留天数不能为负数: {}", older_than_days));
+        }
+
+        let connection = self
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let kind = connection.kind();
+        match connection {
+            DatabaseConnectionRef::Postgres(pool) => Self::purge_deleted_postgres(&pool, older_than_days).await,
+            DatabaseConnectionRef::Sqlite(pool) => Self::purge_deleted_sqlite(&pool, older_than_days).await,
+        }
+        .map_err(|e| format!("清理已删除记录失败（{:?}）: {}", kind, e))
+    }
+
+    /// PostgreSQL 实现：永久删除过期的软删除记录
+    async fn purge_deleted_postgres(pool: &Pool<Postgres>, older_than_days: i64) -> Result<u64, sqlx::Error> {
+        let purgeable_tags = r#"
+            SELECT t.id FROM tags t
+            WHERE t.deleted_at < NOW() - make_interval(days => $1)
+            AND NOT EXISTS (
+                SELECT 1 FROM tags c
+                WHERE c.parent_id = t.id
+                AND (c.deleted_at IS NULL OR c.deleted_at >= NOW() - make_interval(days => $1))
+            )
+        "#;
+        let days = older_than_days.min(i32::MAX as i64) as i32;
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(&format!(
+            r#"
+            DELETE FROM file_tags
+            WHERE file_id IN (SELECT id FROM files WHERE deleted_at < NOW() - make_interval(days => $1))
+            OR tag_id IN ({})
+            "#,
+            purgeable_tags
+        ))
+        .bind(days)
+        .execute(&mut *tx)
+        .await?;
+
+        let files = sqlx::query("DELETE FROM files WHERE deleted_at < NOW() - make_interval(days => $1)")
+            .bind(days)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        let tags = sqlx::query(&format!("DELETE FROM tags WHERE id IN ({})", purgeable_tags))
+            .bind(days)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+        Ok(files + tags)
+    }
+
+    /// SQLite 实现：永久删除过期的软删除记录
+    async fn purge_deleted_sqlite(pool: &Pool<Sqlite>, older_than_days: i64) -> Result<u64, sqlx::Error> {
+        let purgeable_tags = r#"
+            SELECT t.id FROM tags t
+            WHERE t.deleted_at < datetime('now', ?1)
+            AND NOT EXISTS (
+                SELECT 1 FROM tags c
+                WHERE c.parent_id = t.id
+                AND (c.deleted_at IS NULL OR c.deleted_at >= datetime('now', ?1))
+            )
+        "#;
+        let modifier = format!("-{} days", older_than_days);
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(&format!(
+            r#"
+            DELETE FROM file_tags
+            WHERE file_id IN (SELECT id FROM files WHERE deleted_at < datetime('now', ?1))
+            OR tag_id IN ({})
+            "#,
+            purgeable_tags
+        ))
+        .bind(&modifier)
+        .execute(&mut *tx)
+        .await?;
+
+        let files = sqlx::query("DELETE FROM files WHERE deleted_at < datetime('now', ?1)")
+            .bind(&modifier)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        let tags = sqlx::query(&format!("DELETE FROM tags WHERE id IN ({})", purgeable_tags))
+            .bind(&modifier)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+        Ok(files + tags)
+    }
+
+    /// 检查核心表及其关键列是否存在，用于诊断迁移未完成的数据库
+    ///
+    /// 与 `verify_schema_version` 不同，发现问题时不报错，而是逐条列出
+    ///
+    /// # 返回
+    /// - `Ok(Vec<String>)`: 发现的问题（缺少的表或列），为空表示架构完整
+    /// - `Err(String)`: 查询表结构失败
+    pub async fn verify_schema(&self) -> Result<Vec<String>, String> {
+        let connection = self
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let mut problems = Vec::new();
+        for &(table, expected_columns) in EXPECTED_SCHEMA {
+            let columns: Vec<String> = match &connection {
+                DatabaseConnectionRef::Postgres(pool) => sqlx::query_scalar(
+                    "SELECT column_name::TEXT FROM information_schema.columns WHERE table_schema = current_schema() AND table_name = $1",
+                )
+                .bind(table)
+                .fetch_all(pool)
+                .await,
+                DatabaseConnectionRef::Sqlite(pool) => sqlx::query_scalar("SELECT name FROM pragma_table_info(?1)")
+                    .bind(table)
+                    .fetch_all(pool)
+                    .await,
+            }
+            .map_err(|e| format!("查询表结构失败 {}: {}", table, e))?;
+
+            if columns.is_empty() {
+                problems.push(format!("缺少表: {}", table));
+                continue;
+            }
+            problems.extend(
+                expected_columns
+                    .iter()
+                    .filter(|column| !columns.iter().any(|c| c == *column))
+                    .map(|column| format!("表 {} 缺少列: {}", table, column)),
+            );
+        }
+
+        Ok(problems)
+    }
+
+    /// 用另一个数据库实例替换当前数据库
+    ///
+    /// 替换后关闭原数据库的连接池。已取得原连接池的进行中操作不受影响，
+    /// 之后获取的连接均来自新数据库
+    ///
+    /// # 参数
+    /// - `other`: 已初始化的新数据库实例
+    pub async fn replace(&self, other: GlobalDatabase) -> DatabaseResult<()> {
+        let new_manager = other.manager();
+        let old_manager = std::mem::replace(
+            &mut *self.manager.write().unwrap_or_else(PoisonError::into_inner),
+            new_manager,
+        );
+        old_manager.close().await
     }
 
     /// 从默认配置初始化数据库（应用启动时调用）
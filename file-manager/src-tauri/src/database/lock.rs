@@ -0,0 +1,77 @@
+//! 数据库迁移锁模块
+//!
+//! 多个应用实例同时启动时，保证同一时间只有一个实例执行迁移：
+//! PostgreSQL 使用会话级 advisory lock，SQLite 使用数据库文件旁的锁文件
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// PostgreSQL 迁移使用的 advisory lock 键（"filemgr" 的 ASCII 编码）
+pub const PG_MIGRATION_LOCK_KEY: i64 = 0x0066_696c_656d_6772;
+
+/// 等待迁移锁的最长时间
+pub const MIGRATION_LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 锁文件超过该时长未释放时视为持有者已崩溃，允许接管
+const STALE_LOCK_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// 等待锁文件释放时的轮询间隔
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 基于锁文件的互斥锁
+///
+/// 通过 `create_new` 原子地创建锁文件，析构时删除锁文件释放锁
+#[derive(Debug)]
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// 获取锁，锁已被占用时轮询等待
+    ///
+    /// # 参数
+    /// - `path`: 锁文件路径
+    /// - `timeout`: 最长等待时间
+    ///
+    /// # 返回
+    /// - `Ok(FileLock)`: 持有锁的守卫，drop 时释放
+    /// - `Err(String)`: 等待超时（"等待迁移锁超时"）或创建锁文件失败
+    pub async fn acquire(path: &Path, timeout: Duration) -> Result<Self, String> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            match fs::OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(_) => return Ok(Self { path: path.to_path_buf() }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if Self::is_stale(path) {
+                        let _ = fs::remove_file(path);
+                        continue;
+                    }
+                }
+                Err(e) => return Err(format!("创建锁文件失败 {}: {}", path.display(), e)),
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err(format!("等待迁移锁超时: {}", path.display()));
+            }
+            tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+        }
+    }
+
+    /// 锁文件是否已超过 `STALE_LOCK_AGE` 未释放
+    fn is_stale(path: &Path) -> bool {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > STALE_LOCK_AGE)
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
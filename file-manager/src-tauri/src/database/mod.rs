@@ -5,10 +5,15 @@
 pub mod config;
 pub mod connection;
 pub mod error;
+pub mod lock;
+pub mod retry;
+pub mod slow_query;
 
 #[cfg(test)]
 mod tests;
 
 pub use config::DatabaseConfig;
 pub use connection::{DatabaseConnection, DatabaseManager, DatabaseConnectionRef, GlobalDatabase};
-pub use error::{DatabaseError, DatabaseResult};
\ No newline at end of file
+pub use error::{DatabaseError, DatabaseResult};
+pub use retry::retry_sqlite_busy;
+pub use slow_query::log_slow;
\ No newline at end of file
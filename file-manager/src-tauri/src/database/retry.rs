@@ -0,0 +1,53 @@
+//! 数据库重试模块
+//!
+//! 提供 SQLite 写操作在数据库忙碌/锁定时的重试功能
+
+use std::future::Future;
+use std::time::Duration;
+
+/// SQLite 忙碌时的最大重试次数
+pub const SQLITE_BUSY_MAX_RETRIES: u32 = 5;
+
+/// SQLite 忙碌时每次重试前的等待时间（毫秒）
+pub const SQLITE_BUSY_RETRY_DELAY_MS: u64 = 50;
+
+/// 判断错误是否为 SQLite 忙碌/锁定错误
+///
+/// SQLite 扩展错误码的低 8 位为主错误码：5 为 SQLITE_BUSY，6 为 SQLITE_LOCKED
+pub fn is_sqlite_busy(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(db_err) => db_err
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .map(|code| matches!(code & 0xFF, 5 | 6))
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// 执行 SQLite 写操作，遇到忙碌/锁定错误时短暂等待后重试
+///
+/// 其它错误立即返回，不进行重试
+///
+/// # 参数
+/// - `operation`: 每次调用都会重新构建并执行查询的闭包
+///
+/// # 返回
+/// - `Ok(T)`: 查询结果
+/// - `Err(sqlx::Error)`: 非忙碌错误，或重试次数用尽后的最后一次错误
+pub async fn retry_sqlite_busy<T, F, Fut>(mut operation: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation().await {
+            Err(e) if is_sqlite_busy(&e) && attempt < SQLITE_BUSY_MAX_RETRIES => {
+                attempt += 1;
+                tokio::time::sleep(Duration::from_millis(SQLITE_BUSY_RETRY_DELAY_MS * attempt as u64)).await;
+            }
+            result => return result,
+        }
+    }
+}
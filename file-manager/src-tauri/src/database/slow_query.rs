@@ -0,0 +1,49 @@
+//! 慢查询日志模块
+//!
+//! 记录耗时超过阈值的数据库操作，用于定位大数据量下的性能问题
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::database::config::DatabaseType;
+
+/// 执行数据库操作，耗时超过阈值时输出慢查询日志
+///
+/// # 参数
+/// - `operation`: 操作名称，出现在日志中
+/// - `kind`: 执行操作的数据库类型，出现在日志中
+/// - `threshold`: 慢查询阈值，通常取自全局配置的 `slow_query_threshold_ms`
+/// - `query`: 要执行的数据库操作
+///
+/// # 返回
+/// 操作本身的结果
+pub async fn log_slow<T>(
+    operation: &str,
+    kind: DatabaseType,
+    threshold: Duration,
+    query: impl Future<Output = T>,
+) -> T {
+    let started = Instant::now();
+    let output = query.await;
+    report_if_slow(operation, kind, started.elapsed(), threshold);
+    output
+}
+
+/// 耗时达到阈值时输出警告到标准错误
+///
+/// # 返回
+/// 是否输出了慢查询日志
+pub(crate) fn report_if_slow(operation: &str, kind: DatabaseType, elapsed: Duration, threshold: Duration) -> bool {
+    if elapsed < threshold {
+        return false;
+    }
+
+    eprintln!(
+        "慢查询: {} ({:?}) 耗时 {} ms，超过阈值 {} ms",
+        operation,
+        kind,
+        elapsed.as_millis(),
+        threshold.as_millis()
+    );
+    true
+}
@@ -6,10 +6,15 @@ mod services;
 mod system;
 mod utils;
 
+#[cfg(test)]
+mod test_support;
+
 use tauri::Manager;
 
 use crate::config::GlobalConfigManager;
-use crate::system::init::init_database;
+use crate::database::GlobalDatabase;
+use crate::services::{FileSystemService, TagService};
+use crate::system::init::{init_database, resolve_config_path};
 use crate::system::runtime::RuntimeManager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -20,16 +25,21 @@ pub fn run() {
         .setup(|app| {
             // 加载全局配置（应用启动时读取）
             // 优先从配置文件加载，失败则使用默认配置
-            let global_config = GlobalConfigManager::from_toml_file("config/global.toml")
+            let global_config = GlobalConfigManager::from_toml_file(resolve_config_path("global.toml"))
                 .unwrap_or_else(|e| {
                     eprintln!("从配置文件加载全局配置失败: {}, 使用默认配置", e);
                     GlobalConfigManager::from_default()
                 });
+
+            // 文件系统服务持有文件操作并发限制器，上限来自全局配置
+            app.manage(FileSystemService::from_config(&global_config));
+            let incremental_usage_count = global_config.get_incremental_usage_count();
+            let usage_recount_interval = global_config.get_usage_recount_interval();
             app.manage(global_config);
 
             // 创建 Tokio 运行时管理器（与 Tauri 应用生命周期一致）
             // 优先从配置文件加载配置，失败则使用默认配置
-            let runtime_manager = RuntimeManager::from_config_file("config/runtime.toml")
+            let runtime_manager = RuntimeManager::from_config_file(resolve_config_path("runtime.toml"))
                 .unwrap_or_else(|e| {
                     eprintln!("从配置文件加载运行时配置失败: {}, 使用默认配置", e);
                     RuntimeManager::new().expect("创建Tokio运行时失败")
@@ -39,10 +49,27 @@ pub fn run() {
             // 使用运行时管理器执行异步初始化任务
             // 数据库失败直接终止程序
             let db = runtime_manager.block_on(async {
-                init_database("config/database.toml").await
+                init_database(resolve_config_path("database.toml")).await
             }).unwrap();
             app.manage(db);
 
+            // 增量维护标签使用次数时，定期全量重新统计以修正偏差（间隔为 0 时不统计）
+            if incremental_usage_count && !usage_recount_interval.is_zero() {
+                let app_handle = app.handle().clone();
+                runtime_manager.spawn(async move {
+                    let mut ticker = tokio::time::interval(usage_recount_interval);
+                    // 第一次 tick 立即返回，跳过启动时的统计
+                    ticker.tick().await;
+                    loop {
+                        ticker.tick().await;
+                        let db = app_handle.state::<GlobalDatabase>();
+                        if let Err(e) = TagService::recount_all_usage(&db).await {
+                            eprintln!("重新统计标签使用次数失败: {}", e);
+                        }
+                    }
+                });
+            }
+
             // 将运行时管理器存储到应用状态，供后续使用
             // 注意：必须在数据库初始化之后存储，因为 block_on 需要运行时保持存活
             app.manage(runtime_manager);
@@ -52,19 +79,63 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::greet,
             commands::list_directory,
+            commands::list_directory_paged,
+            commands::get_parent_chain,
+            commands::count_entries,
             commands::get_home_directory,
+            commands::get_home_listing,
             commands::list_drives,
+            commands::get_capabilities,
             commands::check_path_exists,
+            commands::check_paths_exist,
+            commands::get_file_infos,
+            commands::search_content,
+            commands::preview_text,
+            commands::generate_thumbnail,
+            commands::sanitize_file_name,
+            commands::format_path_for_shell,
+            commands::create_directory,
+            commands::create_file,
+            commands::set_readonly,
+            commands::write_file,
             commands::cut_files,
+            commands::move_files,
+            commands::organize_by_extension,
             commands::copy_files,
+            commands::cancel_file_operation,
+            commands::get_directory_size,
+            commands::export_tree,
+            commands::get_free_space,
+            commands::preflight_copy,
             commands::rename_file,
+            commands::rename_keep_extension,
             commands::delete_files,
+            commands::delete_files_each,
+            commands::undo_last_operation,
+            commands::find_files_by_name,
+            commands::search_registered_files,
+            commands::list_untagged_files,
+            commands::reconcile_files,
             commands::get_tag_list,
             commands::search_tags,
             commands::create_tag,
+            commands::create_tags_bulk,
+            commands::get_tag,
+            commands::rename_tag,
             commands::modify_tag,
             commands::add_tags_to_files,
-            commands::search_files_by_tag
+            commands::add_multiple_tags_to_files,
+            commands::clear_tags_from_files,
+            commands::search_files_by_tag,
+            commands::get_tag_activity,
+            commands::suggest_tags,
+            commands::get_tag_statistics,
+            commands::check_database_health,
+            commands::reconnect_database,
+            commands::purge_deleted_records,
+            commands::get_last_db_error,
+            commands::get_current_db_config,
+            commands::switch_database
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
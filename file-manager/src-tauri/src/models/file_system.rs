@@ -15,14 +15,155 @@ pub struct FileItem {
     pub file_type: String,
     /// 文件大小（字节）
     pub size: u64,
+    /// 便于阅读的文件大小（如 `1.5 KiB`），单位制由全局配置决定
+    #[serde(default)]
+    pub size_display: String,
     /// 修改日期（ISO 8601 格式）
     pub modified_date: String,
     /// 创建日期（ISO 8601 格式）
     pub created_date: String,
     /// 文件扩展名（仅文件）
     pub extension: Option<String>,
+    /// 文件分类：image、video、audio、document、archive、code 或 other（文件夹为 other）
+    pub category: String,
     /// 是否为隐藏文件
     pub is_hidden: bool,
+    /// 是否为符号链接
+    pub is_symlink: bool,
+    /// 是否为只读
+    pub readonly: bool,
+    /// Unix 权限位（仅 Unix 系统有值）
+    pub mode: Option<u32>,
+    /// 文件首次登记到数据库的时间（仅来自数据库的列表有值）
+    pub registered_at: Option<String>,
+    /// 数据库记录最后更新时间（仅来自数据库的列表有值）
+    pub db_updated_at: Option<String>,
+    /// 相对于调用方指定基准目录的路径（仅搜索结果指定了基准目录时有值）
+    pub relative_path: Option<String>,
+    /// 文件夹的直接子项数量（仅列目录时要求统计的文件夹有值）
+    #[serde(default)]
+    pub child_count: Option<usize>,
+}
+
+/// 符号链接跟随策略
+///
+/// 递归遍历目录（复制、统计大小等）时决定如何处理符号链接
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FollowSymlinks {
+    /// 从不跟随：符号链接既不递归也不读取其目标
+    #[serde(rename = "never")]
+    Never,
+    /// 仅跟随指向文件的符号链接，指向目录的符号链接不递归
+    #[serde(rename = "files_only")]
+    FilesOnly,
+    /// 始终跟随（通过已访问的规范路径集合防止循环）
+    #[serde(rename = "always")]
+    Always,
+}
+
+/// 目录列表排序方式
+///
+/// 无论按哪个字段排序，文件夹始终排在文件之前
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortBy {
+    /// 按名称升序
+    #[serde(rename = "name")]
+    Name,
+    /// 按大小升序，大小相同时按名称
+    #[serde(rename = "size")]
+    Size,
+    /// 按修改时间倒序，时间相同时按名称
+    #[serde(rename = "modified")]
+    Modified,
+}
+
+/// 目录列表条目类型过滤
+///
+/// 在排序和统计之前剔除不需要的条目，默认为 `All`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntryFilter {
+    /// 文件和文件夹都保留
+    #[default]
+    #[serde(rename = "all")]
+    All,
+    /// 只保留文件
+    #[serde(rename = "files_only")]
+    FilesOnly,
+    /// 只保留文件夹
+    #[serde(rename = "folders_only")]
+    FoldersOnly,
+}
+
+/// 复制路径时的目标 shell
+///
+/// 决定 `format_path_for_shell` 使用的引号和转义规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShellKind {
+    /// Windows 命令提示符（cmd.exe）
+    #[serde(rename = "cmd")]
+    Cmd,
+    /// PowerShell
+    #[serde(rename = "powershell")]
+    PowerShell,
+    /// POSIX shell（sh、bash、zsh 等）
+    #[serde(rename = "posix_sh")]
+    PosixSh,
+}
+
+/// 目标路径冲突处理策略
+///
+/// 移动或复制时目标目录中已存在同名项的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConflictPolicy {
+    /// 返回错误，终止操作
+    #[serde(rename = "error")]
+    Error,
+    /// 跳过该项，继续处理其他项
+    #[serde(rename = "skip")]
+    Skip,
+    /// 覆盖已存在的项
+    #[serde(rename = "overwrite")]
+    Overwrite,
+    /// 自动重命名，如 `file (1).txt`
+    #[serde(rename = "rename")]
+    Rename,
+}
+
+/// 文件操作类型
+///
+/// 记录在操作日志中，用于撤销最近一次操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationKind {
+    /// 重命名（可撤销）
+    #[serde(rename = "rename")]
+    Rename,
+    /// 移动（可撤销）
+    #[serde(rename = "move")]
+    Move,
+    /// 永久删除（不可撤销）
+    #[serde(rename = "delete")]
+    Delete,
+}
+
+impl OperationKind {
+    /// 数据库中存储的操作类型名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationKind::Rename => "rename",
+            OperationKind::Move => "move",
+            OperationKind::Delete => "delete",
+        }
+    }
+
+    /// 从数据库中存储的名称解析操作类型
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rename" => Some(OperationKind::Rename),
+            "move" => Some(OperationKind::Move),
+            "delete" => Some(OperationKind::Delete),
+            _ => None,
+        }
+    }
 }
 
 /// 目录信息数据结构
@@ -38,6 +179,9 @@ pub struct DirectoryInfo {
     pub total_files: usize,
     /// 总文件夹数
     pub total_folders: usize,
+    /// 无法读取而被跳过的目录项路径
+    #[serde(default)]
+    pub skipped: Vec<String>,
 }
 
 /// 搜索结果数据结构
@@ -53,4 +197,149 @@ pub struct SearchResult {
     pub page_size: usize,
     /// 是否有更多数据
     pub has_more: bool,
-}
\ No newline at end of file
+}
+
+/// 可取消的批量文件操作（复制、移动）结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status")]
+pub enum BatchOutcome {
+    /// 全部完成
+    #[serde(rename = "completed")]
+    Completed,
+    /// 中途被取消，`completed` 为取消前已处理完成的源路径
+    #[serde(rename = "cancelled")]
+    Cancelled { completed: Vec<String> },
+}
+
+/// 复制进度（每复制完一个文件或大文件的一块时报告一次）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CopyProgress {
+    /// 正在复制的源文件路径
+    pub path: String,
+    /// 该文件已复制的字节数
+    pub copied: u64,
+    /// 该文件的总字节数
+    pub total: u64,
+}
+
+/// 复制前的预检结果
+///
+/// 汇总源文件总大小、目标磁盘可用空间和名称冲突，供前端一次性展示确认信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightReport {
+    /// 待复制内容的总大小（字节）
+    pub total_size: u64,
+    /// 目标磁盘可用空间（字节）
+    pub available_space: u64,
+    /// 可用空间是否足够
+    pub enough_space: bool,
+    /// 目标目录中已存在的同名路径
+    pub conflicts: Vec<String>,
+}
+
+/// 文件记录与磁盘对账结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconcileReport {
+    /// 检查的文件记录数
+    pub checked: usize,
+    /// 因磁盘上已不存在而被软删除的记录数
+    pub removed: usize,
+}
+
+/// 按扩展名整理目录的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizeReport {
+    /// 移动的文件数
+    pub moved: usize,
+    /// 新建的子文件夹路径
+    pub created_folders: Vec<String>,
+}
+
+/// 文件名清理结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SanitizedName {
+    /// 清理后的名称
+    pub name: String,
+    /// 名称是否被修改
+    pub changed: bool,
+}
+
+/// 目录树快照中的节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeNode {
+    /// 文件/文件夹名称
+    pub name: String,
+    /// 类型："file" 或 "folder"
+    pub file_type: String,
+    /// 大小（字节），文件夹为其下所有文件的总大小
+    pub size: u64,
+    /// 子节点（按名称排序），文件和超过导出深度的文件夹没有子节点
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeNode>,
+}
+
+/// 导出的目录树快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeSnapshot {
+    /// 根目录节点
+    pub root: TreeNode,
+    /// 快照是否被截断（节点数量超过 `max_results`，遍历已提前停止，部分文件夹缺少子节点）
+    pub truncated: bool,
+}
+
+/// 文件系统服务在当前平台上支持的功能
+///
+/// 前端据此隐藏当前平台不可用的操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// 是否有驱动盘列表（仅 Windows，Unix 上 "drives:" 为挂载点列表）
+    pub supports_drives: bool,
+    /// 是否支持移到回收站（尚未实现，删除均为永久删除）
+    pub supports_trash: bool,
+    /// 是否能识别和移动符号链接
+    pub supports_symlinks: bool,
+    /// 操作系统名称，取值同 `std::env::consts::OS`（如 "windows"、"linux"、"macos"）
+    pub os: String,
+}
+
+/// 文本预览数据结构
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextPreview {
+    /// 预览内容
+    pub content: String,
+    /// 内容是否被截断（文件大小超过预览上限）
+    pub truncated: bool,
+}
+
+/// 按内容搜索的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentSearchResult {
+    /// 匹配的文件列表（按路径排序）
+    pub items: Vec<FileItem>,
+    /// 结果是否被截断（匹配数量超过 `max_results`，遍历已提前停止）
+    pub truncated: bool,
+}
+
+/// 创建文件/文件夹失败时的错误信息
+///
+/// 名称冲突时附带一个可用的建议名称，前端可以据此提示"使用 Foo (1)？"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateError {
+    /// 错误信息
+    pub message: String,
+    /// 建议使用的名称（仅名称冲突时有值）
+    pub suggestion: Option<String>,
+}
+
+impl CreateError {
+    /// 创建不带建议名称的错误
+    pub fn new(message: String) -> Self {
+        Self { message, suggestion: None }
+    }
+}
+
+impl std::fmt::Display for CreateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
@@ -24,3 +24,47 @@ pub struct Tag {
     /// 更新时间
     pub updated_at: String,
 }
+
+/// 标签搜索模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TagSearchMode {
+    /// 包含匹配：`name LIKE '%kw%'`（默认）
+    #[serde(rename = "contains")]
+    Contains,
+    /// 前缀匹配：`name LIKE 'kw%'`，标签数量很多时可以利用 `tags(name)` 索引
+    #[serde(rename = "prefix")]
+    Prefix,
+}
+
+/// 批量创建标签的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkTagResult {
+    /// 新创建或恢复的标签（按输入顺序）
+    pub created: Vec<Tag>,
+    /// 已存在同名标签而跳过的名称
+    pub skipped: Vec<String>,
+    /// 未通过名称校验的输入
+    pub invalid: Vec<InvalidTagName>,
+}
+
+/// 未通过校验的标签名称
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvalidTagName {
+    /// 原始输入
+    pub name: String,
+    /// 校验失败原因
+    pub reason: String,
+}
+
+/// 标签统计摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagStats {
+    /// 标签总数
+    pub total_tags: i64,
+    /// 至少有一个标签的文件数
+    pub tagged_files: i64,
+    /// 有标签的文件平均拥有的标签数
+    pub average_tags_per_file: f64,
+    /// 使用次数最多的标签（最多5个）
+    pub top_tags: Vec<Tag>,
+}
@@ -0,0 +1,106 @@
+//! 数据库服务
+//!
+//! 提供数据库连接维护相关的业务逻辑实现
+
+use crate::database::{DatabaseConfig, GlobalDatabase};
+
+/// 数据库服务
+pub struct DatabaseService;
+
+impl DatabaseService {
+    /// 检查数据库健康状态
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    ///
+    /// # 返回
+    /// - `Ok(true)`: 数据库可用
+    /// - `Err(String)`: 错误信息
+    pub async fn check_health(db: &GlobalDatabase) -> Result<bool, String> {
+        db.check_health()
+            .await
+            .map_err(|e| format!("数据库健康检查失败: {}", e))
+    }
+
+    /// 重新建立数据库连接
+    ///
+    /// 健康检查失败（例如数据库服务重启导致连接失效）时调用
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    ///
+    /// # 返回
+    /// - `Ok(())`: 重新连接成功
+    /// - `Err(String)`: 错误信息
+    pub async fn reconnect(db: &GlobalDatabase) -> Result<(), String> {
+        db.reconnect()
+            .await
+            .map_err(|e| format!("重新连接数据库失败: {}", e))
+    }
+
+    /// 获取最近一次数据库错误
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    ///
+    /// # 返回
+    /// 错误信息，尚未出错时为 None
+    pub fn last_error(db: &GlobalDatabase) -> Option<String> {
+        db.last_error()
+    }
+
+    /// 获取当前数据库配置
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    ///
+    /// # 返回
+    /// 隐藏密码后的数据库配置
+    pub fn current_config(db: &GlobalDatabase) -> DatabaseConfig {
+        db.config().redacted()
+    }
+
+    /// 清理过期的软删除记录
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `older_than_days`: 保留天数，软删除超过该天数的文件和标签记录会被永久删除
+    ///
+    /// # 返回
+    /// - `Ok(u64)`: 删除的记录数
+    /// - `Err(String)`: 错误信息
+    pub async fn purge_deleted(db: &GlobalDatabase, older_than_days: i64) -> Result<u64, String> {
+        db.purge_deleted(older_than_days).await
+    }
+
+    /// 切换到新的数据库
+    ///
+    /// 先校验配置、建立新连接并执行迁移，全部成功后才替换当前数据库并关闭原连接，
+    /// 任一步骤失败时继续使用原数据库
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `config`: 新的数据库配置
+    ///
+    /// # 返回
+    /// - `Ok(())`: 切换成功
+    /// - `Err(String)`: 错误信息
+    pub async fn switch_database(db: &GlobalDatabase, config: DatabaseConfig) -> Result<(), String> {
+        config.validate()
+            .map_err(|e| format!("数据库配置无效: {}", e))?;
+
+        let new_db = GlobalDatabase::new(config);
+        new_db.init()
+            .await
+            .map_err(|e| format!("连接新数据库失败: {}", e))?;
+
+        if let Err(e) = new_db.migrate().await {
+            let _ = new_db.close().await;
+            return Err(format!("新数据库迁移失败: {}", e));
+        }
+
+        db.replace(new_db)
+            .await
+            .map_err(|e| format!("关闭原数据库连接失败: {}", e))
+    }
+}
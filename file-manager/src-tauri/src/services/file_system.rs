@@ -2,28 +2,185 @@
 //!
 //! 提供文件系统相关的业务逻辑实现
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
-
-use crate::models::file_system::{FileItem, DirectoryInfo};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::models::file_system::{BatchOutcome, ConflictPolicy, ContentSearchResult, CopyProgress, CreateError, Capabilities, FileItem, DirectoryInfo, EntryFilter, FollowSymlinks, OperationKind, OrganizeReport, PreflightReport, ReconcileReport, SearchResult, ShellKind, SortBy, TextPreview, TreeNode, TreeSnapshot};
+use crate::config::global::{DisplayOptions, HiddenDetection};
+#[cfg(windows)]
+use crate::config::global::DriveType;
 use crate::config::GlobalConfigManager;
 use crate::database::{DatabaseConnectionRef, GlobalDatabase};
-use crate::utils;
+use crate::services::operation_log::{OperationBatch, OperationLog};
+use crate::services::tag::TagService;
+use crate::utils::{self, CancellationToken};
 use sqlx::{Pool, Postgres, Sqlite, Row};
+use tokio::sync::Semaphore;
 
 /// 文件系统服务
-pub struct FileSystemService;
+///
+/// 大部分方法是无状态的关联函数；实例持有文件操作并发限制器和进行中操作的取消令牌，
+/// 在应用启动时创建并注册为 Tauri 状态
+pub struct FileSystemService {
+    /// 文件操作并发限制器
+    limiter: Arc<Semaphore>,
+    /// 进行中的可取消操作（操作ID -> 取消令牌）
+    cancellations: Arc<Mutex<HashMap<String, CancellationToken>>>,
+}
+
+/// 批量更新文件路径时单条语句包含的最大路径数（每个路径占两个绑定参数）
+const PATH_UPDATE_BATCH_SIZE: usize = 500;
+
+/// 支持生成缩略图的图片扩展名
+const THUMBNAIL_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp"];
+
+/// 复制流程内部的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CopyError {
+    /// 操作被取消，不完整的目标已清理
+    Cancelled,
+    /// 复制失败，附带错误信息
+    Failed(String),
+}
+
+impl From<String> for CopyError {
+    fn from(message: String) -> Self {
+        CopyError::Failed(message)
+    }
+}
+
+/// 单个文件的复制选项
+#[derive(Clone, Copy)]
+struct FileCopyOptions<'a> {
+    /// 复制后是否校验 SHA-256
+    verify: bool,
+    /// 流式复制的缓冲区大小（字节）
+    buffer_size: usize,
+    /// 超过该大小（字节）的文件使用流式复制
+    stream_threshold: u64,
+    /// 取消令牌
+    cancel: Option<&'a CancellationToken>,
+    /// 进度回调
+    progress: Option<&'a (dyn Fn(&CopyProgress) + Sync)>,
+}
+
+impl FileCopyOptions<'_> {
+    /// 是否已请求取消
+    fn is_cancelled(&self) -> bool {
+        self.cancel.is_some_and(CancellationToken::is_cancelled)
+    }
+}
 
 impl FileSystemService {
+    /// 创建指定并发上限的文件系统服务
+    ///
+    /// # 参数
+    /// - `limit`: 同时执行的文件操作数量上限（最小为1）
+    pub fn with_concurrency(limit: usize) -> Self {
+        Self {
+            limiter: Arc::new(Semaphore::new(limit.max(1))),
+            cancellations: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 根据全局配置创建文件系统服务
+    pub fn from_config(global_config: &GlobalConfigManager) -> Self {
+        Self::with_concurrency(global_config.get_max_concurrent_file_ops())
+    }
+
+    /// 在并发限制下执行文件操作
+    ///
+    /// 同时执行的操作数达到上限时等待，直到有其他操作完成
+    ///
+    /// # 参数
+    /// - `operation`: 要执行的文件操作
+    ///
+    /// # 返回
+    /// 文件操作的结果
+    pub async fn run_limited<T>(&self, operation: impl Future<Output = T>) -> T {
+        // 限制器不会被关闭，获取许可只会等待不会失败
+        let _permit = self.limiter.acquire().await.unwrap();
+        operation.await
+    }
+
+    /// 登记可取消的操作
+    ///
+    /// # 参数
+    /// - `operation_id`: 前端生成的操作ID
+    ///
+    /// # 返回
+    /// 该操作的取消令牌，操作结束后需调用 `finish_operation` 移除
+    pub fn register_operation(&self, operation_id: &str) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.cancellations
+            .lock()
+            .unwrap()
+            .insert(operation_id.to_string(), token.clone());
+        token
+    }
+
+    /// 移除已结束的操作
+    pub fn finish_operation(&self, operation_id: &str) {
+        self.cancellations.lock().unwrap().remove(operation_id);
+    }
+
+    /// 请求取消操作
+    ///
+    /// # 返回
+    /// - `true`: 已通知操作取消
+    /// - `false`: 操作不存在或已结束
+    pub fn cancel_operation(&self, operation_id: &str) -> bool {
+        match self.cancellations.lock().unwrap().get(operation_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 获取当前平台支持的功能
+    ///
+    /// # 返回
+    /// 编译目标平台对应的功能标志
+    pub fn capabilities() -> Capabilities {
+        Capabilities {
+            supports_drives: cfg!(windows),
+            supports_trash: false,
+            supports_symlinks: cfg!(any(unix, windows)),
+            os: std::env::consts::OS.to_string(),
+        }
+    }
+
     /// 获取目录内容
     ///
+    /// 特殊路径 `"drives:"` 表示驱动盘列表（Unix 为挂载点列表），委托给 `list_drives` 处理。
+    /// 无法读取的目录项（如没有权限获取元数据）不会中断列出，记录在 `skipped` 中
+    ///
     /// # 参数
     /// - `path`: 目录路径
+    /// - `filter`: 条目类型过滤，被过滤掉的条目不计入 `total_files` / `total_folders`
+    /// - `extensions`: 只保留这些扩展名的文件（不区分大小写，可带前导 `.`），文件夹不受影响
+    /// - `with_child_count`: 是否统计每个文件夹的直接子项数量（每个文件夹多一次 `read_dir`）
+    /// - `display`: 文件项的展示选项
     ///
     /// # 返回
     /// - `Ok(DirectoryInfo)`: 目录信息
-    /// - `Err(String)`: 错误信息
-    pub fn list_directory(path: &str) -> Result<DirectoryInfo, String> {
+    /// - `Err(String)`: 错误信息（目录本身不存在或无法打开）
+    pub fn list_directory(
+        path: &str,
+        filter: EntryFilter,
+        extensions: Option<&[String]>,
+        with_child_count: bool,
+        display: &DisplayOptions,
+    ) -> Result<DirectoryInfo, String> {
+        if path == "drives:" {
+            return Self::list_drives(display);
+        }
+
         let dir_path = Path::new(path);
 
         // 检查路径是否存在
@@ -40,16 +197,25 @@ impl FileSystemService {
         let entries = fs::read_dir(dir_path)
             .map_err(|e| format!("读取目录失败: {}", e))?;
 
+        let extensions: Option<Vec<String>> = extensions.map(|exts| {
+            exts.iter()
+                .map(|ext| ext.trim_start_matches('.').to_lowercase())
+                .collect()
+        });
+
         let mut items = Vec::new();
+        let mut skipped = Vec::new();
         let mut total_files = 0;
         let mut total_folders = 0;
 
         for entry in entries {
-            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
-            let metadata = entry.metadata()
-                .map_err(|e| format!("获取文件元数据失败: {}", e))?;
-
+            // 读取目录项本身失败时拿不到路径，记录目录路径
+            let Ok(entry) = entry else {
+                skipped.push(path.to_string());
+                continue;
+            };
             let file_path = entry.path();
+
             let file_name = file_path.file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("")
@@ -60,8 +226,33 @@ impl FileSystemService {
                 continue;
             }
 
-            let is_dir = metadata.is_dir();
-            let file_type = if is_dir { "folder" } else { "file" };
+            let Ok(link_metadata) = fs::symlink_metadata(&file_path) else {
+                skipped.push(file_path.to_string_lossy().to_string());
+                continue;
+            };
+            let Ok(mut item) = Self::file_item_from_metadata(&file_path, link_metadata, display) else {
+                skipped.push(file_path.to_string_lossy().to_string());
+                continue;
+            };
+
+            let is_dir = item.file_type == "folder";
+            let keep = match filter {
+                EntryFilter::All => true,
+                EntryFilter::FilesOnly => !is_dir,
+                EntryFilter::FoldersOnly => is_dir,
+            };
+            if !keep {
+                continue;
+            }
+
+            // 扩展名过滤只作用于文件，文件夹始终保留用于导航
+            let excluded = !is_dir && extensions.as_ref().is_some_and(|exts| {
+                !item.extension.as_deref()
+                    .is_some_and(|ext| exts.contains(&ext.to_lowercase()))
+            });
+            if excluded {
+                continue;
+            }
 
             if is_dir {
                 total_folders += 1;
@@ -69,66 +260,18 @@ impl FileSystemService {
                 total_files += 1;
             }
 
-            // 获取文件扩展名
-            let extension = file_path.extension()
-                .and_then(|ext| ext.to_str())
-                .map(|s| s.to_string());
-
-            // 获取修改时间和创建时间
-            let modified = metadata.modified()
-                .map_err(|e| format!("获取修改时间失败: {}", e))?;
-            let created = metadata.created()
-                .unwrap_or(modified);
-
-            // 转换为 ISO 8601 格式
-            let modified_date = utils::format_iso8601(&modified);
-            let created_date = utils::format_iso8601(&created);
-
-            let is_hidden = file_name.starts_with('.');
-
-            let item = FileItem {
-                id: file_path.to_string_lossy().to_string(),
-                name: file_name,
-                path: file_path.to_string_lossy().to_string(),
-                file_type: file_type.to_string(),
-                size: metadata.len(),
-                modified_date,
-                created_date,
-                extension,
-                is_hidden,
-            };
+            if is_dir && with_child_count {
+                item.child_count = Self::count_visible_children(&file_path, display.hidden_detection);
+            }
 
             items.push(item);
         }
 
         // 排序：文件夹在前，然后按名称排序
-        items.sort_by(|a, b| {
-            match (a.file_type.as_str(), b.file_type.as_str()) {
-                ("folder", "file") => std::cmp::Ordering::Less,
-                ("file", "folder") => std::cmp::Ordering::Greater,
-                _ => a.name.cmp(&b.name),
-            }
-        });
+        Self::sort_items(&mut items, SortBy::Name);
 
         // 规范化当前路径（统一驱动盘格式为 X:\）
-        let normalized_path = if Self::is_drive_root(path) {
-            #[cfg(windows)]
-            {
-                let path_trimmed = path.trim();
-                let normalized = path_trimmed.replace('/', "\\").to_uppercase();
-                if normalized.len() == 2 && normalized.ends_with(':') {
-                    format!("{}\\", normalized)
-                } else {
-                    normalized
-                }
-            }
-            #[cfg(not(windows))]
-            {
-                path.to_string()
-            }
-        } else {
-            path.to_string()
-        };
+        let normalized_path = Self::normalize_drive_root(path);
 
         // 获取父路径
         let parent_path = if Self::is_drive_root(path) {
@@ -136,28 +279,7 @@ impl FileSystemService {
             Some("drives:".to_string())
         } else {
             dir_path.parent()
-                .map(|p| {
-                    let parent = p.to_string_lossy().to_string();
-                    // 规范化父路径（如果是驱动盘根目录）
-                    if Self::is_drive_root(&parent) {
-                        #[cfg(windows)]
-                        {
-                            let parent_trimmed = parent.trim();
-                            let parent_normalized = parent_trimmed.replace('/', "\\").to_uppercase();
-                            if parent_normalized.len() == 2 && parent_normalized.ends_with(':') {
-                                format!("{}\\", parent_normalized)
-                            } else {
-                                parent_normalized
-                            }
-                        }
-                        #[cfg(not(windows))]
-                        {
-                            parent
-                        }
-                    } else {
-                        parent
-                    }
-                })
+                .map(|p| Self::normalize_drive_root(&p.to_string_lossy()))
         };
 
         Ok(DirectoryInfo {
@@ -166,41 +288,195 @@ impl FileSystemService {
             items,
             total_files,
             total_folders,
+            skipped,
         })
     }
 
-    /// 获取用户主目录
+    /// 在阻塞线程池中获取目录内容
+    ///
+    /// 参数与 [`Self::list_directory`] 相同。列出网络驱动器等慢速目录时不会占住异步工作线程
     ///
     /// # 返回
-    /// - `Ok(String)`: 用户主目录路径
+    /// - `Ok(DirectoryInfo)`: 目录信息
     /// - `Err(String)`: 错误信息
-    pub fn get_home_directory(global_config: &GlobalConfigManager) -> Result<String, String> {
-        // Windows 上使用环境变量
-        #[cfg(windows)]
-        {
-            use std::env;
-                // 尝试从全局配置获取主目录路径
-            if let Some(home_path) = global_config.get_home_path() {
-                // 如果配置中的路径不为空，则使用配置的路径
-                if !home_path.is_empty() {
-                    return Ok(home_path);
-                }
+    pub async fn list_directory_async(
+        path: String,
+        filter: EntryFilter,
+        extensions: Option<Vec<String>>,
+        with_child_count: bool,
+        display: DisplayOptions,
+    ) -> Result<DirectoryInfo, String> {
+        tokio::task::spawn_blocking(move || {
+            Self::list_directory(&path, filter, extensions.as_deref(), with_child_count, &display)
+        })
+        .await
+        .map_err(|e| format!("读取目录失败: {}", e))?
+    }
+
+    /// 分页获取目录内容
+    ///
+    /// 读取完整的目录项并排序后只返回请求的一页，避免一次性向前端序列化超大目录的全部文件项
+    ///
+    /// # 参数
+    /// - `path`: 目录路径
+    /// - `page`: 页码（从1开始）
+    /// - `page_size`: 每页数量，必须大于0
+    /// - `sort`: 排序方式，默认为按名称
+    /// - `display`: 文件项的展示选项
+    ///
+    /// # 返回
+    /// - `Ok(SearchResult)`: 当前页的文件列表及总数
+    /// - `Err(String)`: 错误信息
+    pub fn list_directory_paged(
+        path: &str,
+        page: usize,
+        page_size: usize,
+        sort: Option<SortBy>,
+        display: &DisplayOptions,
+    ) -> Result<SearchResult, String> {
+        if page_size == 0 {
+            return Err("每页数量必须大于0".to_string());
+        }
+
+        let mut info = Self::list_directory(path, EntryFilter::All, None, false, display)?;
+
+        // list_directory 已按名称排序，其他排序方式需要重新排序
+        let sort = sort.unwrap_or(SortBy::Name);
+        if sort != SortBy::Name {
+            Self::sort_items(&mut info.items, sort);
+        }
+
+        let page = page.max(1);
+        let total = info.items.len();
+        let offset = (page - 1).saturating_mul(page_size);
+        let items: Vec<FileItem> = info.items.into_iter().skip(offset).take(page_size).collect();
+        let has_more = offset.saturating_add(items.len()) < total;
+
+        Ok(SearchResult {
+            items,
+            total,
+            page,
+            page_size,
+            has_more,
+        })
+    }
+
+    /// 统计文件夹中未隐藏的直接子项数量
+    ///
+    /// 与文件项的 `is_hidden` 使用同一判断（[`utils::is_hidden`]），无法读取元数据的子项不计入
+    ///
+    /// # 参数
+    /// - `dir`: 文件夹路径
+    /// - `detection`: 隐藏文件判断策略，取自全局配置的 `hidden_detection`
+    ///
+    /// # 返回
+    /// 子项数量，文件夹无法读取时返回 None
+    fn count_visible_children(dir: &Path, detection: HiddenDetection) -> Option<usize> {
+        let entries = fs::read_dir(dir).ok()?;
+        Some(
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| {
+                    entry.metadata().is_ok_and(|metadata| {
+                        !utils::is_hidden(&entry.file_name().to_string_lossy(), &metadata, detection)
+                    })
+                })
+                .count(),
+        )
+    }
+
+    /// 对文件项排序，文件夹始终在文件之前
+    fn sort_items(items: &mut [FileItem], sort: SortBy) {
+        items.sort_by(|a, b| {
+            match (a.file_type.as_str(), b.file_type.as_str()) {
+                ("folder", "file") => std::cmp::Ordering::Less,
+                ("file", "folder") => std::cmp::Ordering::Greater,
+                _ => match sort {
+                    SortBy::Name => a.name.cmp(&b.name),
+                    SortBy::Size => a.size.cmp(&b.size).then_with(|| a.name.cmp(&b.name)),
+                    // 按时间点比较，不受展示时区偏移（如夏令时切换）影响
+                    SortBy::Modified => {
+                        let parse = |item: &FileItem| chrono::DateTime::parse_from_rfc3339(&item.modified_date).ok();
+                        parse(b).cmp(&parse(a)).then_with(|| a.name.cmp(&b.name))
+                    }
+                },
             }
-            if let Ok(home) = env::var("USERPROFILE") {
-                return Ok(home);
+        });
+    }
+
+    /// 统计目录下的文件和文件夹数量
+    ///
+    /// 只读取目录项类型，不构建 FileItem，适用于只需要数量的场景（如提示信息）
+    ///
+    /// # 参数
+    /// - `path`: 目录路径
+    /// - `include_hidden`: 是否统计隐藏文件（以.开头）
+    ///
+    /// # 返回
+    /// - `Ok((usize, usize))`: (文件数, 文件夹数)
+    /// - `Err(String)`: 错误信息
+    pub fn count_entries(path: &str, include_hidden: bool) -> Result<(usize, usize), String> {
+        let dir_path = Path::new(path);
+
+        if !dir_path.exists() {
+            return Err(format!("路径不存在: {}", path));
+        }
+
+        if !dir_path.is_dir() {
+            return Err(format!("路径不是目录: {}", path));
+        }
+
+        let entries = fs::read_dir(dir_path)
+            .map_err(|e| format!("读取目录失败: {}", e))?;
+
+        let mut total_files = 0;
+        let mut total_folders = 0;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+
+            if !include_hidden && entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
             }
-            if let Ok(home) = env::var("HOMEDRIVE") {
-                if let Ok(path) = env::var("HOMEPATH") {
-                    return Ok(format!("{}{}", home, path));
-                }
+
+            let file_type = entry.file_type()
+                .map_err(|e| format!("获取文件类型失败: {}", e))?;
+
+            // 与 list_directory 保持一致：符号链接按其目标类型统计
+            let is_dir = if file_type.is_symlink() {
+                fs::metadata(entry.path()).map(|m| m.is_dir()).unwrap_or(false)
+            } else {
+                file_type.is_dir()
+            };
+
+            if is_dir {
+                total_folders += 1;
+            } else {
+                total_files += 1;
             }
         }
 
-        // Unix 系统
-        #[cfg(unix)]
-        {
-            use std::env;
-            if let Ok(home) = env::var("HOME") {
+        Ok((total_files, total_folders))
+    }
+
+    /// 获取用户主目录
+    ///
+    /// 按全局配置 `home_resolution` 的顺序依次尝试各个来源，返回第一个非空的结果
+    ///
+    /// # 返回
+    /// - `Ok(String)`: 用户主目录路径
+    /// - `Err(String)`: 错误信息（所有来源都没有值，或来源格式无效）
+    pub fn get_home_directory(global_config: &GlobalConfigManager) -> Result<String, String> {
+        Self::get_home_directory_with(global_config, &|name| std::env::var(name).ok())
+    }
+
+    /// 使用指定的环境变量读取函数获取用户主目录（测试时可替换环境变量）
+    pub(crate) fn get_home_directory_with(
+        global_config: &GlobalConfigManager,
+        env_var: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<String, String> {
+        for source in global_config.get_home_resolution() {
+            if let Some(home) = Self::resolve_home_source(global_config, &source, env_var)? {
                 return Ok(home);
             }
         }
@@ -208,6 +484,63 @@ impl FileSystemService {
         Err("无法获取用户主目录".to_string())
     }
 
+    /// 获取用户主目录的目录列表（应用启动时使用，省去一次额外的调用）
+    ///
+    /// 主目录无法解析或无法列出时回退为驱动盘列表（Windows）或根目录（Unix）
+    ///
+    /// # 返回
+    /// - `Ok(DirectoryInfo)`: 主目录（或回退目录）的内容
+    /// - `Err(String)`: 回退目录同样无法列出
+    pub fn get_home_listing(global_config: &GlobalConfigManager) -> Result<DirectoryInfo, String> {
+        Self::get_home_listing_with(global_config, &|name| std::env::var(name).ok())
+    }
+
+    /// 使用指定的环境变量读取函数获取主目录列表（测试时可替换环境变量）
+    pub(crate) fn get_home_listing_with(
+        global_config: &GlobalConfigManager,
+        env_var: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<DirectoryInfo, String> {
+        let display = global_config.get_display_options();
+        let home_listing = Self::get_home_directory_with(global_config, env_var)
+            .and_then(|home| Self::list_directory(&home, EntryFilter::All, None, false, &display));
+        match home_listing {
+            Ok(info) => Ok(info),
+            Err(_) => {
+                let fallback = if cfg!(windows) { "drives:" } else { "/" };
+                Self::list_directory(fallback, EntryFilter::All, None, false, &display)
+            }
+        }
+    }
+
+    /// 解析单个主目录来源
+    ///
+    /// # 参数
+    /// - `source`: `config`、`env:NAME` 或 `env:A+B`（拼接多个环境变量，任一未设置时视为无值）
+    /// - `env_var`: 读取环境变量的函数
+    ///
+    /// # 返回
+    /// - `Ok(Some(String))`: 该来源的主目录路径
+    /// - `Ok(None)`: 该来源没有值
+    /// - `Err(String)`: 未知的来源
+    fn resolve_home_source(
+        global_config: &GlobalConfigManager,
+        source: &str,
+        env_var: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Option<String>, String> {
+        if source == "config" {
+            return Ok(global_config.get_home_path().filter(|home| !home.is_empty()));
+        }
+
+        let Some(names) = source.strip_prefix("env:") else {
+            return Err(format!("未知的主目录来源: {}", source));
+        };
+        let parts: Option<Vec<String>> = names
+            .split('+')
+            .map(env_var)
+            .collect();
+        Ok(parts.map(|parts| parts.concat()).filter(|home| !home.is_empty()))
+    }
+
     /// 检查路径是否为 Windows 驱动盘根目录
     ///
     /// # 参数
@@ -235,294 +568,2416 @@ impl FileSystemService {
         false
     }
 
-    /// 获取所有 Windows 驱动盘列表
+    /// 规范化驱动盘根目录路径
+    ///
+    /// # 参数
+    /// - `path`: 路径字符串
     ///
     /// # 返回
-    /// - `Ok(DirectoryInfo)`: 包含所有驱动盘的目录信息
-    /// - `Err(String)`: 错误信息
-    pub fn list_drives() -> Result<DirectoryInfo, String> {
+    /// 如果路径是驱动盘根目录，返回统一的 "X:\" 格式；否则原样返回
+    fn normalize_drive_root(path: &str) -> String {
         #[cfg(windows)]
         {
-            let mut items = Vec::new();
-
-            // 遍历 A-Z 驱动盘
-            for drive_letter in b'A'..=b'Z' {
-                let drive = format!("{}:\\", drive_letter as char);
-                let drive_path = Path::new(&drive);
-
-                // 检查驱动盘是否存在
-                if drive_path.exists() {
-                    // 获取驱动盘的元数据
-                    let metadata = match fs::metadata(drive_path) {
-                        Ok(m) => m,
-                        Err(_) => continue,
-                    };
-
-                    // 获取修改时间和创建时间
-                    let modified = metadata.modified()
-                        .unwrap_or_else(|_| std::time::SystemTime::UNIX_EPOCH);
-                    let created = metadata.created()
-                        .unwrap_or(modified);
-
-                    let modified_date = utils::format_iso8601(&modified);
-                    let created_date = utils::format_iso8601(&created);
-
-                    let item = FileItem {
-                        id: drive.clone(),
-                        name: format!("{}:", drive_letter as char),
-                        path: drive.clone(),
-                        file_type: "folder".to_string(),
-                        size: 0,
-                        modified_date,
-                        created_date,
-                        extension: None,
-                        is_hidden: false,
-                    };
-
-                    items.push(item);
+            if Self::is_drive_root(path) {
+                let normalized = path.trim().replace('/', "\\").to_uppercase();
+                if normalized.len() == 2 && normalized.ends_with(':') {
+                    return format!("{}\\", normalized);
                 }
+                return normalized;
             }
-
-            // 按驱动盘字母排序
-            items.sort_by(|a, b| a.name.cmp(&b.name));
-
-            let total_folders = items.len();
-
-            Ok(DirectoryInfo {
-                path: "drives:".to_string(),
-                parent_path: None,
-                items,
-                total_files: 0,
-                total_folders,
-            })
-        }
-
-        #[cfg(not(windows))]
-        {
-            // 非 Windows 系统返回根目录
-            Err("此功能仅支持 Windows 系统".to_string())
         }
+        path.to_string()
     }
 
-    /// 检查路径是否存在且为目录
+    /// 获取路径的父级链（用于面包屑导航）
+    ///
+    /// 返回从驱动盘根目录（Unix 为 `/`）到指定路径的所有文件夹，按从上到下排序。
+    /// 驱动盘列表 `"drives:"` 位于最顶层，没有父级，因此返回空列表。
     ///
     /// # 参数
-    /// - `path`: 路径字符串
+    /// - `path`: 目录路径
+    /// - `display`: 文件项的展示选项
     ///
     /// # 返回
-    /// - `Ok(true)`: 路径存在且为目录
-    /// - `Ok(false)`: 路径不存在或不是目录
+    /// - `Ok(Vec<FileItem>)`: 父级链（包含路径自身）
     /// - `Err(String)`: 错误信息
-    pub fn check_path_exists(path: &str) -> Result<bool, String> {
+    pub fn get_parent_chain(path: &str, display: &DisplayOptions) -> Result<Vec<FileItem>, String> {
+        if path == "drives:" {
+            return Ok(Vec::new());
+        }
+
         let dir_path = Path::new(path);
 
-        // 检查路径是否存在
         if !dir_path.exists() {
-            return Ok(false);
+            return Err(format!("路径不存在: {}", path));
         }
 
-        // 检查是否为目录
         if !dir_path.is_dir() {
-            return Ok(false);
+            return Err(format!("路径不是目录: {}", path));
+        }
+
+        let mut chain = Vec::new();
+        for ancestor in dir_path.ancestors() {
+            // 相对路径的最后一个祖先为空路径
+            if ancestor.as_os_str().is_empty() {
+                continue;
+            }
+
+            let ancestor_path = Self::normalize_drive_root(&ancestor.to_string_lossy());
+
+            // 驱动盘根目录与 list_drives 保持一致显示为 "X:"，Unix 根目录显示为 "/"
+            let name = if Self::is_drive_root(&ancestor_path) {
+                ancestor_path.trim_end_matches('\\').to_string()
+            } else {
+                ancestor.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| ancestor_path.clone())
+            };
+
+            // 路径和名称统一为导航使用的格式，其余字段与目录列表一致
+            let mut item = Self::build_file_item(ancestor, display)?;
+            item.id = ancestor_path.clone();
+            item.path = ancestor_path;
+            item.name = name;
+            chain.push(item);
+        }
+
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// 获取 Windows 驱动盘列表
+    ///
+    /// 只包含 `display.drive_types` 中的驱动盘类型（取自全局配置的 `drive_types`），
+    /// 无法识别类型的驱动盘始终显示。Unix 系统上忽略驱动盘类型，返回挂载点列表
+    ///
+    /// # 参数
+    /// - `display`: 文件项的展示选项
+    ///
+    /// # 返回
+    /// - `Ok(DirectoryInfo)`: 包含驱动盘的目录信息
+    /// - `Err(String)`: 错误信息
+    pub fn list_drives(display: &DisplayOptions) -> Result<DirectoryInfo, String> {
+        #[cfg(windows)]
+        {
+            let mut items = Vec::new();
+
+            // 遍历 A-Z 驱动盘
+            for drive_letter in b'A'..=b'Z' {
+                let drive = format!("{}:\\", drive_letter as char);
+                let drive_path = Path::new(&drive);
+
+                let hidden_type = Self::drive_type(&drive)
+                    .is_some_and(|drive_type| !display.drive_types.contains(&drive_type));
+                if hidden_type {
+                    continue;
+                }
+
+                // 驱动盘不存在或无法读取时跳过
+                let Ok(mut item) = Self::build_file_item(drive_path, display) else {
+                    continue;
+                };
+                item.id = drive.clone();
+                item.path = drive.clone();
+                item.name = format!("{}:", drive_letter as char);
+                items.push(item);
+            }
+
+            // 按驱动盘字母排序
+            items.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let total_folders = items.len();
+
+            Ok(DirectoryInfo {
+                path: "drives:".to_string(),
+                parent_path: None,
+                items,
+                total_files: 0,
+                total_folders,
+                skipped: Vec::new(),
+            })
+        }
+
+        // Unix 系统返回挂载点列表
+        #[cfg(unix)]
+        {
+            let mut items = Vec::new();
+
+            for mount_point in Self::list_mount_points() {
+                let mount_path = Path::new(&mount_point);
+                let mut item = match Self::build_file_item(mount_path, display) {
+                    Ok(item) if item.file_type == "folder" => item,
+                    _ => continue,
+                };
+                item.id = mount_point.clone();
+                item.name = mount_point.clone();
+                item.path = mount_point;
+                items.push(item);
+            }
+
+            items.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let total_folders = items.len();
+
+            Ok(DirectoryInfo {
+                path: "drives:".to_string(),
+                parent_path: None,
+                items,
+                total_files: 0,
+                total_folders,
+                skipped: Vec::new(),
+            })
+        }
+
+        #[cfg(not(any(windows, unix)))]
+        {
+            let _ = display;
+            Err("此功能仅支持 Windows 和 Unix 系统".to_string())
+        }
+    }
+
+    /// 查询驱动盘类型
+    ///
+    /// # 参数
+    /// - `root`: 驱动盘根目录，如 `C:\`
+    ///
+    /// # 返回
+    /// 驱动盘类型，驱动盘不存在或类型无法识别时返回 None
+    #[cfg(windows)]
+    pub(crate) fn drive_type(root: &str) -> Option<DriveType> {
+        use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+
+        // GetDriveTypeW 的返回值（定义于 winbase.h）
+        const DRIVE_REMOVABLE: u32 = 2;
+        const DRIVE_FIXED: u32 = 3;
+        const DRIVE_REMOTE: u32 = 4;
+        const DRIVE_CDROM: u32 = 5;
+        const DRIVE_RAMDISK: u32 = 6;
+
+        let wide: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+        // SAFETY: wide 以 0 结尾且在调用期间有效
+        match unsafe { GetDriveTypeW(wide.as_ptr()) } {
+            DRIVE_REMOVABLE => Some(DriveType::Removable),
+            DRIVE_FIXED => Some(DriveType::Fixed),
+            DRIVE_REMOTE => Some(DriveType::Network),
+            DRIVE_CDROM => Some(DriveType::Cdrom),
+            DRIVE_RAMDISK => Some(DriveType::Ramdisk),
+            _ => None,
+        }
+    }
+
+    /// 获取 Unix 系统的挂载点列表
+    ///
+    /// 从 `/proc/mounts` 读取挂载点并过滤掉虚拟文件系统；
+    /// 无法读取时（如 macOS）仅返回根目录 `/`
+    ///
+    /// # 返回
+    /// 去重后的挂载点路径列表，始终包含 `/`
+    #[cfg(unix)]
+    fn list_mount_points() -> Vec<String> {
+        // 不对应实际存储的虚拟文件系统类型
+        const PSEUDO_FS_TYPES: &[&str] = &[
+            "proc", "sysfs", "devtmpfs", "devpts", "tmpfs", "cgroup", "cgroup2",
+            "securityfs", "pstore", "debugfs", "tracefs", "mqueue", "hugetlbfs",
+            "configfs", "fusectl", "bpf", "autofs", "binfmt_misc", "efivarfs",
+            "nsfs", "rpc_pipefs", "selinuxfs",
+        ];
+
+        let mut mount_points = vec!["/".to_string()];
+
+        if let Ok(content) = fs::read_to_string("/proc/mounts") {
+            for line in content.lines() {
+                let mut fields = line.split_whitespace();
+                let (Some(_device), Some(mount_point), Some(fs_type)) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    continue;
+                };
+
+                if PSEUDO_FS_TYPES.contains(&fs_type) {
+                    continue;
+                }
+
+                // /proc/mounts 中的空格以八进制转义形式 \040 表示
+                let mount_point = mount_point.replace("\\040", " ");
+                if !mount_points.contains(&mount_point) {
+                    mount_points.push(mount_point);
+                }
+            }
+        }
+
+        mount_points
+    }
+
+    /// 检查路径是否存在且为目录
+    ///
+    /// # 参数
+    /// - `path`: 路径字符串
+    ///
+    /// # 返回
+    /// - `Ok(true)`: 路径存在且为目录
+    /// - `Ok(false)`: 路径不存在或不是目录
+    /// - `Err(String)`: 错误信息
+    pub fn check_path_exists(path: &str) -> Result<bool, String> {
+        let dir_path = Path::new(path);
+
+        // 检查路径是否存在
+        if !dir_path.exists() {
+            return Ok(false);
+        }
+
+        // 检查是否为目录
+        if !dir_path.is_dir() {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// 批量检查路径是否存在
+    ///
+    /// 文件和文件夹都视为存在，一次调用代替多次 check_path_exists
+    ///
+    /// # 参数
+    /// - `paths`: 路径列表
+    ///
+    /// # 返回
+    /// 与输入顺序一致的 (路径, 是否存在) 列表
+    pub fn check_paths_exist(paths: &[String]) -> Vec<(String, bool)> {
+        paths
+            .iter()
+            .map(|path| (path.clone(), Path::new(path).exists()))
+            .collect()
+    }
+
+    /// 批量获取文件信息
+    ///
+    /// 每个路径单独返回结果，某个文件不存在不影响其他文件
+    ///
+    /// # 参数
+    /// - `paths`: 路径列表
+    /// - `display`: 文件项的展示选项
+    ///
+    /// # 返回
+    /// 与输入顺序一致的结果列表
+    pub fn get_file_infos(paths: &[String], display: &DisplayOptions) -> Vec<Result<FileItem, String>> {
+        paths
+            .iter()
+            .map(|path| Self::build_file_item(Path::new(path), display))
+            .collect()
+    }
+
+    /// 预览文本文件内容
+    ///
+    /// 最多读取 `max_bytes` 字节；样本中包含 NUL 字节时视为二进制文件并返回错误
+    ///
+    /// # 参数
+    /// - `path`: 文件路径
+    /// - `max_bytes`: 最多读取的字节数
+    ///
+    /// # 返回
+    /// - `Ok(TextPreview)`: 预览内容及是否截断
+    /// - `Err(String)`: 错误信息（文件不存在、不是文本文件等）
+    pub fn preview_text(path: &str, max_bytes: usize) -> Result<TextPreview, String> {
+        use std::io::Read;
+
+        let file_path = Path::new(path);
+
+        if !file_path.exists() {
+            return Err(format!("路径不存在: {}", path));
+        }
+
+        if file_path.is_dir() {
+            return Err(format!("路径不是文件: {}", path));
+        }
+
+        let file = fs::File::open(file_path)
+            .map_err(|e| format!("打开文件失败 {}: {}", path, e))?;
+
+        // 多读取一个字节，用于判断文件是否超过预览上限
+        let mut buffer = Vec::with_capacity(max_bytes.saturating_add(1).min(1024 * 1024));
+        file.take(max_bytes as u64 + 1)
+            .read_to_end(&mut buffer)
+            .map_err(|e| format!("读取文件失败 {}: {}", path, e))?;
+
+        let truncated = buffer.len() > max_bytes;
+        buffer.truncate(max_bytes);
+
+        if buffer.contains(&0) {
+            return Err("不是文本文件".to_string());
+        }
+
+        let content = match std::str::from_utf8(&buffer) {
+            Ok(text) => text.to_string(),
+            // 截断位置恰好落在多字节字符中间时，丢弃末尾不完整的字符
+            Err(e) if e.error_len().is_none() => {
+                String::from_utf8_lossy(&buffer[..e.valid_up_to()]).to_string()
+            }
+            Err(_) => String::from_utf8_lossy(&buffer).to_string(),
+        };
+
+        Ok(TextPreview { content, truncated })
+    }
+
+    /// 生成图片缩略图
+    ///
+    /// 按比例缩小到宽高都不超过 `max_dim`（比 `max_dim` 小的图片保持原尺寸），编码为 PNG。
+    /// 结果按路径、修改时间和尺寸缓存在系统临时目录中，文件修改后自动重新生成
+    ///
+    /// # 参数
+    /// - `path`: 图片文件路径
+    /// - `max_dim`: 缩略图最长边的像素数
+    ///
+    /// # 返回
+    /// - `Ok(Vec<u8>)`: PNG 格式的缩略图数据
+    /// - `Err(String)`: 错误信息（非图片文件返回 "不支持的预览类型"）
+    pub fn generate_thumbnail(path: &str, max_dim: u32) -> Result<Vec<u8>, String> {
+        use std::io::Write;
+
+        let file_path = Path::new(path);
+        let metadata = fs::metadata(file_path)
+            .map_err(|_| format!("路径不存在: {}", path))?;
+        if metadata.is_dir() {
+            return Err(format!("路径不是文件: {}", path));
+        }
+
+        let supported = file_path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| THUMBNAIL_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+        if !supported {
+            return Err("不支持的预览类型".to_string());
+        }
+        if max_dim == 0 {
+            return Err("缩略图尺寸必须大于0".to_string());
+        }
+
+        let cache_path = Self::thumbnail_cache_path(file_path, &metadata, max_dim);
+        if let Ok(bytes) = fs::read(&cache_path) {
+            return Ok(bytes);
+        }
+
+        let image = image::open(file_path)
+            .map_err(|e| format!("解码图片失败 {}: {}", path, e))?;
+        let thumbnail = if image.width() <= max_dim && image.height() <= max_dim {
+            image
+        } else {
+            image.thumbnail(max_dim, max_dim)
+        };
+
+        let mut bytes = Vec::new();
+        thumbnail.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .map_err(|e| format!("编码缩略图失败: {}", e))?;
+
+        // 先写入临时文件再改名，避免并发读取到写了一半的缓存；缓存失败不影响返回结果
+        if let Some(cache_dir) = cache_path.parent() {
+            let _ = fs::create_dir_all(cache_dir)
+                .and_then(|_| tempfile::NamedTempFile::new_in(cache_dir))
+                .and_then(|mut temp_file| {
+                    temp_file.write_all(&bytes)?;
+                    temp_file.persist(&cache_path).map_err(|e| e.error)?;
+                    Ok(())
+                });
+        }
+
+        Ok(bytes)
+    }
+
+    /// 缩略图缓存文件路径：以路径、修改时间和尺寸的 SHA-256 作为文件名
+    fn thumbnail_cache_path(path: &Path, metadata: &fs::Metadata, max_dim: u32) -> PathBuf {
+        let modified_nanos = metadata.modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or(0);
+
+        let mut key_data = path.to_string_lossy().as_bytes().to_vec();
+        key_data.extend_from_slice(&modified_nanos.to_le_bytes());
+        key_data.extend_from_slice(&max_dim.to_le_bytes());
+        let key = utils::sha256_hex(&key_data);
+
+        std::env::temp_dir()
+            .join("file-manager-thumbnails")
+            .join(format!("{}.png", key))
+    }
+
+    /// 按文件内容搜索
+    ///
+    /// 递归遍历目录（不跟随符号链接，跳过以 `.` 开头的文件和文件夹），返回文本内容包含 `query`
+    /// 的文件（不区分大小写）。超过 `max_file_size` 的文件、开头含 NUL 字节的二进制文件
+    /// 以及无法读取的文件和目录会被跳过。匹配数量超过 `max_results` 时停止遍历并标记结果被截断
+    ///
+    /// # 参数
+    /// - `root`: 搜索的根目录
+    /// - `query`: 要查找的文本
+    /// - `max_file_size`: 参与搜索的文件大小上限（字节）
+    /// - `max_results`: 最多返回的匹配数量
+    /// - `display`: 文件项的展示选项
+    ///
+    /// # 返回
+    /// - `Ok(ContentSearchResult)`: 匹配的文件列表（按路径排序）及是否被截断
+    /// - `Err(String)`: 错误信息（根目录不存在、搜索内容为空等）
+    pub fn search_content(
+        root: &str,
+        query: &str,
+        max_file_size: u64,
+        max_results: usize,
+        display: &DisplayOptions,
+    ) -> Result<ContentSearchResult, String> {
+        let root_path = Path::new(root);
+
+        if !root_path.exists() {
+            return Err(format!("路径不存在: {}", root));
+        }
+
+        if !root_path.is_dir() {
+            return Err(format!("路径不是目录: {}", root));
+        }
+
+        if query.is_empty() {
+            return Err("搜索内容不能为空".to_string());
+        }
+
+        let query = query.to_lowercase();
+        let mut matches = Vec::new();
+        let mut truncated = false;
+        let mut pending = vec![root_path.to_path_buf()];
+
+        'walk: while let Some(dir) = pending.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+
+                let entry_path = entry.path();
+                let Ok(metadata) = fs::symlink_metadata(&entry_path) else {
+                    continue;
+                };
+
+                if metadata.is_dir() {
+                    pending.push(entry_path);
+                } else if metadata.is_file()
+                    && metadata.len() <= max_file_size
+                    && Self::file_contains_text(&entry_path, &query)
+                {
+                    if matches.len() >= max_results {
+                        truncated = true;
+                        break 'walk;
+                    }
+                    matches.push(entry_path);
+                }
+            }
+        }
+
+        matches.sort();
+        let items = matches
+            .iter()
+            .filter_map(|path| Self::build_file_item(path, display).ok())
+            .collect();
+        Ok(ContentSearchResult { items, truncated })
+    }
+
+    /// 文本文件的内容是否包含 `query`（`query` 需已转为小写）
+    ///
+    /// 开头 8 KiB 内含 NUL 字节的文件视为二进制文件，读取失败的文件视为不匹配
+    fn file_contains_text(path: &Path, query: &str) -> bool {
+        const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+        let Ok(bytes) = fs::read(path) else {
+            return false;
+        };
+
+        if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+            return false;
+        }
+
+        String::from_utf8_lossy(&bytes).to_lowercase().contains(query)
+    }
+
+    /// 创建文件夹
+    ///
+    /// # 参数
+    /// - `parent_path`: 父目录路径
+    /// - `name`: 新文件夹名称
+    ///
+    /// # 返回
+    /// - `Ok(String)`: 新文件夹的完整路径
+    /// - `Err(CreateError)`: 错误信息，名称冲突时附带建议名称
+    pub fn create_directory(parent_path: &str, name: &str) -> Result<String, CreateError> {
+        let new_path = Self::prepare_create_path(parent_path, name)?;
+
+        fs::create_dir(&new_path)
+            .map_err(|e| CreateError::new(format!("创建文件夹失败 {}: {}", new_path.display(), e)))?;
+
+        Ok(new_path.to_string_lossy().to_string())
+    }
+
+    /// 创建空文件
+    ///
+    /// # 参数
+    /// - `parent_path`: 父目录路径
+    /// - `name`: 新文件名称
+    ///
+    /// # 返回
+    /// - `Ok(String)`: 新文件的完整路径
+    /// - `Err(CreateError)`: 错误信息，名称冲突时附带建议名称
+    pub fn create_file(parent_path: &str, name: &str) -> Result<String, CreateError> {
+        let new_path = Self::prepare_create_path(parent_path, name)?;
+
+        // create_new 保证检查与创建之间不会覆盖其他进程刚创建的文件
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&new_path)
+            .map_err(|e| CreateError::new(format!("创建文件失败 {}: {}", new_path.display(), e)))?;
+
+        Ok(new_path.to_string_lossy().to_string())
+    }
+
+    /// 清理文件名中当前系统不允许的字符
+    ///
+    /// 非法字符替换为 `_`：所有系统都替换路径分隔符 `/`、`\` 和空字符；
+    /// Windows 上额外替换 `<>:"|?*` 和控制字符，去掉末尾的点和空格，
+    /// 并为 `CON`、`NUL`、`COM1` 等保留名称追加 `_`；清理后为空（如 `..`）时返回 `_`
+    ///
+    /// # 参数
+    /// - `name`: 原始文件名
+    ///
+    /// # 返回
+    /// 清理后的文件名，未包含非法字符时与原名称相同
+    pub fn sanitize_name(name: &str) -> String {
+        Self::sanitize_name_for(name, cfg!(windows))
+    }
+
+    /// 按指定平台规则清理文件名，`windows` 为 true 时使用 Windows 规则
+    pub(crate) fn sanitize_name_for(name: &str, windows: bool) -> String {
+        let is_illegal = |c: char| {
+            matches!(c, '/' | '\\' | '\0')
+                || (windows && (matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*') || c.is_control()))
+        };
+        let mut sanitized: String = name
+            .chars()
+            .map(|c| if is_illegal(c) { '_' } else { c })
+            .collect();
+
+        if windows {
+            sanitized.truncate(sanitized.trim_end_matches(['.', ' ']).len());
+            if sanitized.is_empty() {
+                return "_".to_string();
+            }
+
+            if Self::is_reserved_device_name(&sanitized) {
+                let stem_len = sanitized.split('.').next().unwrap_or_default().len();
+                sanitized.insert(stem_len, '_');
+            }
+        }
+
+        sanitized
+    }
+
+    /// 是否为 Windows 保留的设备名（CON、PRN、AUX、NUL、COM1-9、LPT1-9）
+    ///
+    /// 保留名称即使带扩展名也不可用，例如 NUL.txt
+    pub(crate) fn is_reserved_device_name(name: &str) -> bool {
+        let stem = name.split('.').next().unwrap_or_default().to_ascii_uppercase();
+        matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+            || ((stem.starts_with("COM") || stem.starts_with("LPT"))
+                && stem.len() == 4
+                && stem.as_bytes()[3].is_ascii_digit()
+                && stem.as_bytes()[3] != b'0')
+    }
+
+    /// 校验新建项的父目录和名称，返回待创建的路径
+    ///
+    /// 名称冲突时通过 `unique_destination` 生成建议名称
+    fn prepare_create_path(parent_path: &str, name: &str) -> Result<PathBuf, CreateError> {
+        if name.trim().is_empty() {
+            return Err(CreateError::new("名称不能为空".to_string()));
+        }
+
+        if name.contains('/') || name.contains('\\') {
+            return Err(CreateError::new(format!("名称不能包含路径分隔符: {}", name)));
+        }
+
+        let parent_dir = Path::new(parent_path);
+        if !parent_dir.is_dir() {
+            return Err(CreateError::new(format!("父目录不存在: {}", parent_path)));
+        }
+
+        let new_path = parent_dir.join(name);
+        if fs::symlink_metadata(&new_path).is_ok() {
+            let suggestion = Self::unique_destination(parent_dir, name)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string());
+            return Err(CreateError {
+                message: format!("目标路径已存在: {}", new_path.display()),
+                suggestion,
+            });
+        }
+
+        Ok(new_path)
+    }
+
+    /// 设置文件或文件夹的只读属性
+    ///
+    /// Unix 系统上设置/清除所有者写权限位，Windows 上切换只读属性
+    ///
+    /// # 参数
+    /// - `path`: 文件/文件夹路径
+    /// - `readonly`: 是否设为只读
+    ///
+    /// # 返回
+    /// - `Ok(())`: 操作成功
+    /// - `Err(String)`: 错误信息
+    pub fn set_readonly(path: &str, readonly: bool) -> Result<(), String> {
+        let target_path = Path::new(path);
+
+        if !target_path.exists() {
+            return Err(format!("路径不存在: {}", path));
+        }
+
+        let mut permissions = fs::metadata(target_path)
+            .map_err(|e| format!("获取文件元数据失败 {}: {}", path, e))?
+            .permissions();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = permissions.mode();
+            let mode = if readonly { mode & !0o200 } else { mode | 0o200 };
+            permissions.set_mode(mode);
+        }
+
+        #[cfg(not(unix))]
+        {
+            permissions.set_readonly(readonly);
+        }
+
+        fs::set_permissions(target_path, permissions)
+            .map_err(|e| format!("设置文件权限失败 {}: {}", path, e))
+    }
+
+    /// 写入文件内容
+    ///
+    /// 先写入同目录下的临时文件，再重命名覆盖目标文件（原子替换）。
+    /// 如果目标文件在 files 表中有记录，则同步更新 file_size 和 updated_at
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `path`: 目标文件路径
+    /// - `contents`: 文件内容
+    /// - `create_new`: 为 true 时只允许创建新文件，目标已存在则返回错误
+    ///
+    /// # 返回
+    /// - `Ok(())`: 操作成功
+    /// - `Err(String)`: 错误信息
+    pub async fn write_file(
+        db: &GlobalDatabase,
+        path: &str,
+        contents: &[u8],
+        create_new: bool,
+    ) -> Result<(), String> {
+        Self::write_file_atomic(path, contents, create_new)?;
+
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let file_size = contents.len() as i64;
+        match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                Self::update_file_size_postgres(&pool, path, file_size).await
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                Self::update_file_size_sqlite(&pool, path, file_size).await
+            }
+        }
+    }
+
+    /// 原子写入文件内容（写入临时文件后重命名）
+    ///
+    /// # 参数
+    /// - `path`: 目标文件路径
+    /// - `contents`: 文件内容
+    /// - `create_new`: 为 true 时目标已存在则返回错误
+    ///
+    /// # 返回
+    /// - `Ok(())`: 操作成功
+    /// - `Err(String)`: 错误信息
+    fn write_file_atomic(path: &str, contents: &[u8], create_new: bool) -> Result<(), String> {
+        use std::io::Write;
+
+        let target_path = Path::new(path);
+
+        if target_path.is_dir() {
+            return Err(format!("路径是文件夹: {}", path));
+        }
+
+        if create_new && target_path.exists() {
+            return Err(format!("目标路径已存在: {}", path));
+        }
+
+        let parent_dir = target_path.parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .ok_or_else(|| format!("无法获取父目录: {}", path))?;
+
+        if !parent_dir.is_dir() {
+            return Err(format!("父目录不存在: {}", parent_dir.display()));
+        }
+
+        // 临时文件必须与目标位于同一目录，才能保证重命名是原子操作
+        let mut temp_file = tempfile::NamedTempFile::new_in(parent_dir)
+            .map_err(|e| format!("创建临时文件失败 {}: {}", parent_dir.display(), e))?;
+
+        temp_file.write_all(contents)
+            .map_err(|e| format!("写入临时文件失败: {}", e))?;
+        temp_file.as_file().sync_all()
+            .map_err(|e| format!("写入临时文件失败: {}", e))?;
+
+        // 覆盖已有文件时保留其原有权限
+        if let Ok(metadata) = fs::metadata(target_path) {
+            fs::set_permissions(temp_file.path(), metadata.permissions())
+                .map_err(|e| format!("设置文件权限失败: {}", e))?;
+        }
+
+        if create_new {
+            temp_file.persist_noclobber(target_path)
+                .map_err(|e| format!("目标路径已存在或写入失败 {}: {}", path, e.error))?;
+        } else {
+            temp_file.persist(target_path)
+                .map_err(|e| format!("写入文件失败 {}: {}", path, e.error))?;
+        }
+
+        Ok(())
+    }
+
+    /// 剪切文件（移动文件）
+    ///
+    /// 等价于使用 `ConflictPolicy::Error` 策略调用 [`Self::move_files`]
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `paths`: 要剪切的文件/文件夹路径列表
+    /// - `target_path`: 目标目录路径
+    ///
+    /// # 返回
+    /// - `Ok(())`: 操作成功
+    /// - `Err(String)`: 错误信息
+    pub async fn cut_files(
+        db: &GlobalDatabase,
+        paths: &[String],
+        target_path: &str,
+    ) -> Result<(), String> {
+        Self::move_files(db, paths, target_path, Some(ConflictPolicy::Error), None)
+            .await
+            .map(|_| ())
+    }
+
+    /// 移动文件或文件夹
+    ///
+    /// 将指定的文件/文件夹移动到目标目录，支持跨磁盘移动（复制后删除源）。
+    /// 移动后同步更新数据库：文件记录改写 current_path，文件夹同时改写其下所有子文件记录的路径前缀。
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `paths`: 要移动的文件/文件夹路径列表
+    /// - `target_path`: 目标目录路径
+    /// - `policy`: 目标已存在同名项时的处理策略，默认为 `ConflictPolicy::Error`
+    /// - `cancel`: 取消令牌，取消后处理完当前项即停止
+    ///
+    /// # 返回
+    /// - `Ok(BatchOutcome::Completed)`: 全部移动完成
+    /// - `Ok(BatchOutcome::Cancelled)`: 中途取消，附带已移动的源路径
+    /// - `Err(String)`: 错误信息
+    pub async fn move_files(
+        db: &GlobalDatabase,
+        paths: &[String],
+        target_path: &str,
+        policy: Option<ConflictPolicy>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<BatchOutcome, String> {
+        let mut batch = OperationBatch::new();
+        Self::move_entries(db, paths, target_path, policy.unwrap_or(ConflictPolicy::Error), Some(&mut batch), cancel).await
+    }
+
+    /// 移动文件或文件夹的实现
+    ///
+    /// 传入 `batch` 时每移动一项在该批次中记录一条操作日志；撤销移动时传入 None，避免撤销本身被记录。
+    /// 文件的数据库路径攒到最后用 [`Self::update_file_paths`] 批量更新，文件夹连同子路径逐个更新；
+    /// 操作日志同样攒到最后用 [`OperationBatch::record_all`] 批量写入
+    async fn move_entries(
+        db: &GlobalDatabase,
+        paths: &[String],
+        target_path: &str,
+        policy: ConflictPolicy,
+        batch: Option<&mut OperationBatch>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<BatchOutcome, String> {
+        let target_dir = Path::new(target_path);
+
+        // 检查目标路径是否存在且为目录
+        if !target_dir.exists() {
+            return Err(format!("目标路径不存在: {}", target_path));
+        }
+
+        if !target_dir.is_dir() {
+            return Err(format!("目标路径不是目录: {}", target_path));
+        }
+
+        // 获取数据库连接
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        // 已移动但尚未写入数据库的文件路径（原路径, 新路径）
+        let mut pending: Vec<(String, String)> = Vec::new();
+        // 已移动的全部项，最后批量写入操作日志
+        let mut moved: Vec<(String, Option<String>)> = Vec::new();
+        let outcome: Result<BatchOutcome, String> = async {
+            let mut completed = Vec::new();
+            for path in paths {
+                if cancel.is_some_and(CancellationToken::is_cancelled) {
+                    return Ok(BatchOutcome::Cancelled { completed });
+                }
+
+                let source_path = Path::new(path);
+
+                // 使用 symlink_metadata，符号链接本身也可以被移动
+                let source_metadata = fs::symlink_metadata(source_path)
+                    .map_err(|_| format!("源路径不存在: {}", path))?;
+                let is_dir = source_metadata.is_dir();
+
+                // 获取文件名
+                let file_name = source_path.file_name()
+                    .and_then(|n| n.to_str())
+                    .ok_or_else(|| format!("无法获取文件名: {}", path))?;
+
+                // 不能把文件夹移动到它自身或其子目录中
+                if is_dir && target_dir.starts_with(source_path) {
+                    return Err(format!("不能将文件夹移动到其自身或子目录中: {}", path));
+                }
+
+                let mut dest_path = target_dir.join(file_name);
+
+                // 源和目标相同，无需移动
+                if dest_path == source_path {
+                    continue;
+                }
+
+                // 处理目标路径冲突
+                let mut overwritten = false;
+                if let Ok(dest_metadata) = fs::symlink_metadata(&dest_path) {
+                    match policy {
+                        ConflictPolicy::Error => {
+                            return Err(format!("目标路径已存在: {}", dest_path.display()));
+                        }
+                        ConflictPolicy::Skip => continue,
+                        ConflictPolicy::Rename => {
+                            dest_path = Self::unique_destination(target_dir, file_name);
+                        }
+                        ConflictPolicy::Overwrite => {
+                            overwritten = true;
+                            Self::move_replacing(source_path, &dest_path, is_dir, dest_metadata.is_dir())?;
+                        }
+                    }
+                }
+                let dest_path_str = utils::strip_long_path_prefix(&dest_path.to_string_lossy());
+
+                // 移动文件/文件夹（覆盖时已在上面完成移动）
+                if !overwritten {
+                    Self::move_path(&utils::to_long_path(source_path), &utils::to_long_path(&dest_path), is_dir)?;
+                }
+
+                // 被覆盖的目标可能是本次刚移动过去的文件，文件夹的子路径也可能包含尚未更新的文件，
+                // 这两种情况下先写入已攒下的路径
+                if overwritten || is_dir {
+                    Self::update_file_paths(&connection, &pending).await?;
+                    pending.clear();
+                }
+
+                // 更新数据库中的路径，被覆盖的目标记录先清除以免违反 current_path 唯一约束
+                if overwritten {
+                    match &connection {
+                        DatabaseConnectionRef::Postgres(pool) => {
+                            Self::purge_file_records_postgres(pool, &dest_path_str).await?;
+                        }
+                        DatabaseConnectionRef::Sqlite(pool) => {
+                            Self::purge_file_records_sqlite(pool, &dest_path_str).await?;
+                        }
+                    }
+                }
+                if is_dir {
+                    match &connection {
+                        DatabaseConnectionRef::Postgres(pool) => {
+                            Self::update_file_path_postgres(pool, path, &dest_path_str).await?;
+                            Self::update_child_paths_postgres(pool, path, &dest_path_str).await?;
+                        }
+                        DatabaseConnectionRef::Sqlite(pool) => {
+                            Self::update_file_path_sqlite(pool, path, &dest_path_str).await?;
+                            Self::update_child_paths_sqlite(pool, path, &dest_path_str).await?;
+                        }
+                    }
+                } else {
+                    pending.push((path.clone(), dest_path_str.clone()));
+                }
+
+                moved.push((path.clone(), Some(dest_path_str)));
+                completed.push(path.clone());
+            }
+
+            Ok(BatchOutcome::Completed)
+        }
+        .await;
+
+        // 无论完成、取消还是中途出错，已经移动的文件都要同步数据库中的路径并记录日志
+        Self::update_file_paths(&connection, &pending).await?;
+        if let Some(batch) = batch {
+            batch.record_all(&connection, OperationKind::Move, &moved).await?;
+        }
+        outcome
+    }
+
+    /// 批量更新文件的数据库路径
+    ///
+    /// 每条语句最多更新 `PATH_UPDATE_BATCH_SIZE` 个路径，移动大量已登记文件时
+    /// 不必每个文件单独执行一次 UPDATE
+    ///
+    /// # 参数
+    /// - `connection`: 数据库连接
+    /// - `moves`: (原路径, 新路径) 列表
+    async fn update_file_paths(
+        connection: &DatabaseConnectionRef,
+        moves: &[(String, String)],
+    ) -> Result<(), String> {
+        for chunk in moves.chunks(PATH_UPDATE_BATCH_SIZE) {
+            match connection {
+                DatabaseConnectionRef::Postgres(pool) => Self::update_file_paths_postgres(pool, chunk).await?,
+                DatabaseConnectionRef::Sqlite(pool) => Self::update_file_paths_sqlite(pool, chunk).await?,
+            }
+        }
+        Ok(())
+    }
+
+    /// 按扩展名整理目录
+    ///
+    /// 将目录下直接包含的文件移动到以扩展名（小写）命名的子文件夹中，例如 `a.PNG` -> `png/a.PNG`，
+    /// 没有扩展名的文件移动到 `other/`。子文件夹不存在时自动创建，同名的非文件夹占用该名称时改用
+    /// [`Self::unique_destination`] 生成的新文件夹名（如 `doc (1)/`），同名文件同样使用它重命名。
+    /// 隐藏文件、文件夹和符号链接保持不动，数据库中的路径随移动同步更新
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `dir`: 要整理的目录路径
+    ///
+    /// # 返回
+    /// - `Ok(OrganizeReport)`: 移动的文件数和新建的子文件夹
+    /// - `Err(String)`: 错误信息
+    pub async fn organize_by_extension(db: &GlobalDatabase, dir: &str) -> Result<OrganizeReport, String> {
+        let dir_path = Path::new(dir);
+        if !dir_path.is_dir() {
+            return Err(format!("路径不是目录: {}", dir));
+        }
+
+        let entries = fs::read_dir(dir_path)
+            .map_err(|e| format!("读取目录失败: {}", e))?;
+
+        // 先收集全部文件再移动，避免新建的子文件夹影响遍历
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+            let file_path = entry.path();
+
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name.starts_with('.') {
+                continue;
+            }
+
+            let is_file = entry.file_type()
+                .map(|file_type| file_type.is_file())
+                .unwrap_or(false);
+            if !is_file {
+                continue;
+            }
+
+            let folder = file_path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext.to_lowercase())
+                .unwrap_or_else(|| "other".to_string());
+            groups.entry(folder).or_default().push(file_path.to_string_lossy().to_string());
+        }
+
+        let mut report = OrganizeReport {
+            moved: 0,
+            created_folders: Vec::new(),
+        };
+        // 所有分组的移动记为同一批操作，撤销时整体恢复
+        let mut batch = OperationBatch::new();
+
+        for (folder, paths) in groups {
+            let mut folder_path = dir_path.join(&folder);
+            let is_existing_dir = fs::symlink_metadata(&folder_path)
+                .map(|metadata| metadata.is_dir())
+                .unwrap_or(false);
+            if !is_existing_dir {
+                // 同名文件或符号链接占用了分组名时换用不冲突的文件夹名，避免移动到一半才失败
+                folder_path = Self::unique_destination(dir_path, &folder);
+                fs::create_dir(&folder_path)
+                    .map_err(|e| format!("创建目录失败 {}: {}", folder_path.display(), e))?;
+                report.created_folders.push(folder_path.to_string_lossy().to_string());
+            }
+
+            Self::move_entries(db, &paths, &folder_path.to_string_lossy(), ConflictPolicy::Rename, Some(&mut batch), None).await?;
+            report.moved += paths.len();
+        }
+
+        Ok(report)
+    }
+
+    /// 移动文件或文件夹并覆盖已存在的目标
+    ///
+    /// 先把已存在的目标重命名为同目录下的临时备份再移动：移动成功后删除备份，
+    /// 移动失败时把备份改回原名，避免目标已被删除而源文件没有移过去
+    ///
+    /// # 参数
+    /// - `source`: 源路径
+    /// - `dest`: 目标路径（已存在）
+    /// - `is_dir`: 源是否为文件夹
+    /// - `dest_is_dir`: 已存在的目标是否为文件夹
+    ///
+    /// # 返回
+    /// - `Ok(())`: 移动成功，原目标已删除
+    /// - `Err(String)`: 错误信息，此时原目标保持不变
+    pub(crate) fn move_replacing(source: &Path, dest: &Path, is_dir: bool, dest_is_dir: bool) -> Result<(), String> {
+        let parent = dest.parent()
+            .ok_or_else(|| format!("无法获取父目录: {}", dest.display()))?;
+        let file_name = dest.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| format!("无法获取文件名: {}", dest.display()))?;
+        let backup = Self::unique_destination(parent, &format!(".{}.overwritten", file_name));
+
+        fs::rename(utils::to_long_path(dest), utils::to_long_path(&backup))
+            .map_err(|e| format!("备份已存在的目标失败 {}: {}", dest.display(), e))?;
+
+        if let Err(e) = Self::move_path(&utils::to_long_path(source), &utils::to_long_path(dest), is_dir) {
+            return match fs::rename(utils::to_long_path(&backup), utils::to_long_path(dest)) {
+                Ok(()) => Err(e),
+                Err(restore_error) => Err(format!(
+                    "{}；恢复原目标失败，原目标保留在 {}: {}",
+                    e,
+                    backup.display(),
+                    restore_error
+                )),
+            };
+        }
+
+        // 移动已经完成，备份删除失败只会留下一个隐藏文件，不影响本次移动的结果
+        let _ = if dest_is_dir {
+            fs::remove_dir_all(utils::to_long_path(&backup))
+        } else {
+            fs::remove_file(utils::to_long_path(&backup))
+        };
+        Ok(())
+    }
+
+    /// 在文件系统中移动单个文件或文件夹
+    ///
+    /// 优先使用 `fs::rename`，跨磁盘时回退为完整复制后删除源
+    fn move_path(source: &Path, dest: &Path, is_dir: bool) -> Result<(), String> {
+        match fs::rename(source, dest) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+                let removed = if is_dir {
+                    Self::copy_tree_exact(source, dest)?;
+                    fs::remove_dir_all(source)
+                } else {
+                    Self::copy_entry_exact(source, dest)?;
+                    fs::remove_file(source)
+                };
+                removed.map_err(|e| format!("删除源文件失败 {}: {}", source.display(), e))
+            }
+            Err(e) => Err(format!("移动文件失败 {} -> {}: {}", source.display(), dest.display(), e)),
+        }
+    }
+
+    /// 完整复制目录树（用于跨磁盘移动）
+    ///
+    /// 与 `copy_directory` 不同，隐藏文件同样复制，符号链接按原样重建而不跟随，
+    /// 保证复制结果与源目录一致后才能安全删除源目录
+    fn copy_tree_exact(source: &Path, dest: &Path) -> Result<(), String> {
+        fs::create_dir_all(dest)
+            .map_err(|e| format!("创建目标目录失败 {}: {}", dest.display(), e))?;
+
+        let entries = fs::read_dir(source)
+            .map_err(|e| format!("读取目录失败 {}: {}", source.display(), e))?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+            let entry_path = entry.path();
+            let dest_entry_path = dest.join(entry.file_name());
+
+            let file_type = entry.file_type()
+                .map_err(|e| format!("读取文件类型失败 {}: {}", entry_path.display(), e))?;
+            if file_type.is_dir() {
+                Self::copy_tree_exact(&entry_path, &dest_entry_path)?;
+            } else {
+                Self::copy_entry_exact(&entry_path, &dest_entry_path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 复制单个文件，符号链接按原样重建（Unix）
+    fn copy_entry_exact(source: &Path, dest: &Path) -> Result<(), String> {
+        #[cfg(unix)]
+        {
+            let metadata = fs::symlink_metadata(source)
+                .map_err(|e| format!("读取文件信息失败 {}: {}", source.display(), e))?;
+            if metadata.file_type().is_symlink() {
+                let link_target = fs::read_link(source)
+                    .map_err(|e| format!("读取符号链接失败 {}: {}", source.display(), e))?;
+                return std::os::unix::fs::symlink(&link_target, dest)
+                    .map_err(|e| format!("创建符号链接失败 {}: {}", dest.display(), e));
+            }
+        }
+
+        fs::copy(source, dest)
+            .map(|_| ())
+            .map_err(|e| format!("复制文件失败 {} -> {}: {}", source.display(), dest.display(), e))
+    }
+
+    /// 复制文件
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（提供最大目录层级和流式复制参数）
+    /// - `paths`: 要复制的文件/文件夹路径列表
+    /// - `target_path`: 目标目录路径
+    /// - `follow_symlinks`: 递归复制文件夹时的符号链接跟随策略，默认为始终跟随
+    /// - `verify`: 是否在每个文件复制后校验 SHA-256，默认为 false
+    /// - `cancel`: 取消令牌，取消后停止复制并清理未复制完的目标
+    /// - `progress`: 进度回调，每复制完一个文件（大文件为每一块）调用一次
+    ///
+    /// # 返回
+    /// - `Ok(BatchOutcome::Completed)`: 全部复制完成
+    /// - `Ok(BatchOutcome::Cancelled)`: 中途取消，附带已复制完成的源路径
+    /// - `Err(String)`: 错误信息（校验失败时已删除损坏的目标文件）
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy_files(
+        db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
+        paths: &[String],
+        target_path: &str,
+        follow_symlinks: Option<FollowSymlinks>,
+        verify: Option<bool>,
+        cancel: Option<&CancellationToken>,
+        progress: Option<&(dyn Fn(&CopyProgress) + Sync)>,
+    ) -> Result<BatchOutcome, String> {
+        Self::copy_entries(db, global_config, paths, target_path, follow_symlinks, verify, cancel, progress, |_| {}).await
+    }
+
+    /// 复制文件的实现
+    ///
+    /// 每复制完成一项（含标签）调用一次 `on_copied`，参数为该项的源路径
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn copy_entries(
+        db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
+        paths: &[String],
+        target_path: &str,
+        follow_symlinks: Option<FollowSymlinks>,
+        verify: Option<bool>,
+        cancel: Option<&CancellationToken>,
+        progress: Option<&(dyn Fn(&CopyProgress) + Sync)>,
+        mut on_copied: impl FnMut(&str),
+    ) -> Result<BatchOutcome, String> {
+        let follow_symlinks = follow_symlinks.unwrap_or(FollowSymlinks::Always);
+        let options = FileCopyOptions {
+            verify: verify.unwrap_or(false),
+            buffer_size: global_config.get_copy_buffer_size(),
+            stream_threshold: global_config.get_stream_copy_threshold(),
+            cancel,
+            progress,
+        };
+        let max_depth = global_config.get_max_directory_depth();
+        let target_dir = Path::new(target_path);
+
+        // 检查目标路径是否存在且为目录
+        if !target_dir.exists() {
+            return Err(format!("目标路径不存在: {}", target_path));
+        }
+
+        if !target_dir.is_dir() {
+            return Err(format!("目标路径不是目录: {}", target_path));
+        }
+
+        // 获取数据库连接
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        // 复制每个文件/文件夹
+        let mut completed = Vec::new();
+        for path in paths {
+            if options.is_cancelled() {
+                return Ok(BatchOutcome::Cancelled { completed });
+            }
+
+            let source_path = Path::new(path);
+
+            if !source_path.exists() {
+                return Err(format!("源路径不存在: {}", path));
+            }
+
+            // 获取文件名
+            let file_name = source_path.file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| format!("无法获取文件名: {}", path))?;
+
+            // 构建目标路径
+            let dest_path = target_dir.join(file_name);
+            let dest_path_str = utils::strip_long_path_prefix(&dest_path.to_string_lossy());
+
+            // 如果目标路径已存在，返回错误
+            if dest_path.exists() {
+                return Err(format!("目标路径已存在: {}", dest_path.display()));
+            }
+
+            // 复制文件/文件夹，目录项路径由 read_dir 拼接而来，会沿用长路径前缀
+            let long_source = utils::to_long_path(source_path);
+            let long_dest = utils::to_long_path(&dest_path);
+            let copied = if source_path.is_dir() {
+                // 递归复制目录
+                let mut visited = HashSet::new();
+                Self::copy_directory(&long_source, &long_dest, follow_symlinks, &mut visited, 0, max_depth, options)
+            } else {
+                // 复制文件
+                Self::copy_file(&long_source, &long_dest, options)
+            };
+            match copied {
+                Ok(()) => {}
+                Err(CopyError::Cancelled) => {
+                    // 清理复制到一半的目标
+                    let _ = if long_dest.is_dir() {
+                        fs::remove_dir_all(&long_dest)
+                    } else {
+                        fs::remove_file(&long_dest)
+                    };
+                    return Ok(BatchOutcome::Cancelled { completed });
+                }
+                Err(CopyError::Failed(e)) => return Err(e),
+            }
+
+            // 检查源文件是否有标签，如果有则复制标签到新文件
+            // 复制的是文件夹时，其下有标签的子文件同样复制标签
+            let is_dir = dest_path.is_dir();
+            match &connection {
+                DatabaseConnectionRef::Postgres(pool) => {
+                    Self::copy_file_tags_postgres(pool, path, &dest_path_str).await?;
+                    if is_dir {
+                        Self::copy_child_file_tags_postgres(pool, path, &dest_path_str).await?;
+                    }
+                }
+                DatabaseConnectionRef::Sqlite(pool) => {
+                    Self::copy_file_tags_sqlite(pool, path, &dest_path_str).await?;
+                    if is_dir {
+                        Self::copy_child_file_tags_sqlite(pool, path, &dest_path_str).await?;
+                    }
+                }
+            }
+
+            on_copied(path);
+            completed.push(path.clone());
+        }
+
+        Ok(BatchOutcome::Completed)
+    }
+
+    /// 生成不冲突的目标路径
+    ///
+    /// 如果 `dir/file_name` 已存在，则在扩展名前依次追加 ` (1)`、` (2)`……直到路径可用，
+    /// 例如 `file.txt` -> `file (1).txt`，`README` -> `README (1)`
+    ///
+    /// # 参数
+    /// - `dir`: 目标目录
+    /// - `file_name`: 期望的文件名
+    ///
+    /// # 返回
+    /// 目标目录下尚不存在的路径
+    pub fn unique_destination(dir: &Path, file_name: &str) -> PathBuf {
+        // 使用 symlink_metadata 判断，悬空的符号链接同样视为已占用
+        let is_free = |path: &Path| fs::symlink_metadata(path).is_err();
+
+        let candidate = dir.join(file_name);
+        if is_free(&candidate) {
+            return candidate;
+        }
+
+        let name_path = Path::new(file_name);
+        let stem = name_path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_name.to_string());
+        let extension = name_path.extension()
+            .map(|e| e.to_string_lossy().to_string());
+
+        let mut index = 1;
+        loop {
+            let name = match &extension {
+                Some(ext) => format!("{} ({}).{}", stem, index, ext),
+                None => format!("{} ({})", stem, index),
+            };
+            let candidate = dir.join(name);
+            if is_free(&candidate) {
+                return candidate;
+            }
+            index += 1;
+        }
+    }
+
+    /// 递归复制目录
+    ///
+    /// # 参数
+    /// - `source`: 源目录路径
+    /// - `dest`: 目标目录路径
+    /// - `follow_symlinks`: 符号链接跟随策略
+    /// - `visited`: 当前递归路径上祖先目录的规范路径集合（用于检测符号链接循环）
+    /// - `depth`: 当前递归层级
+    /// - `max_depth`: 最大递归层级，超过时返回错误
+    /// - `options`: 单个文件的复制选项
+    ///
+    /// # 返回
+    /// - `Ok(())`: 操作成功
+    /// - `Err(CopyError)`: 复制失败或被取消
+    fn copy_directory(
+        source: &Path,
+        dest: &Path,
+        follow_symlinks: FollowSymlinks,
+        visited: &mut HashSet<PathBuf>,
+        depth: usize,
+        max_depth: usize,
+        options: FileCopyOptions<'_>,
+    ) -> Result<(), CopyError> {
+        if depth > max_depth {
+            return Err(format!("目录层级过深: {}", source.display()).into());
+        }
+
+        // 跟随符号链接时，目录出现在自身的祖先链中说明存在循环
+        if !Self::mark_visited(source, follow_symlinks, visited)? {
+            return Err(format!("检测到符号链接循环: {}", source.display()).into());
+        }
+
+        let result = Self::copy_directory_entries(source, dest, follow_symlinks, visited, depth, max_depth, options);
+
+        // 离开目录时移出祖先集合，同一目录经由不同链接出现（非循环）时仍会正常复制
+        if follow_symlinks == FollowSymlinks::Always {
+            if let Ok(canonical) = fs::canonicalize(source) {
+                visited.remove(&canonical);
+            }
+        }
+
+        result
+    }
+
+    /// 复制目录下的所有条目（`copy_directory` 的递归主体）
+    fn copy_directory_entries(
+        source: &Path,
+        dest: &Path,
+        follow_symlinks: FollowSymlinks,
+        visited: &mut HashSet<PathBuf>,
+        depth: usize,
+        max_depth: usize,
+        options: FileCopyOptions<'_>,
+    ) -> Result<(), CopyError> {
+        // 创建目标目录
+        fs::create_dir_all(dest)
+            .map_err(|e| format!("创建目标目录失败 {}: {}", dest.display(), e))?;
+
+        // 读取源目录内容
+        let entries = fs::read_dir(source)
+            .map_err(|e| format!("读取目录失败 {}: {}", source.display(), e))?;
+
+        // 复制每个条目
+        for entry in entries {
+            if options.is_cancelled() {
+                return Err(CopyError::Cancelled);
+            }
+
+            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+            let entry_path = entry.path();
+            let entry_name = entry_path.file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| format!("无法获取文件名: {}", entry_path.display()))?;
+
+            // 跳过隐藏文件
+            if entry_name.starts_with('.') {
+                continue;
+            }
+
+            let dest_entry_path = dest.join(entry_name);
+
+            // 按策略解析符号链接，返回 None 表示跳过该项
+            let metadata = match Self::resolve_entry_metadata(&entry_path, follow_symlinks)? {
+                Some(metadata) => metadata,
+                None => continue,
+            };
+
+            if metadata.is_dir() {
+                // 递归复制子目录
+                Self::copy_directory(&entry_path, &dest_entry_path, follow_symlinks, visited, depth + 1, max_depth, options)?;
+            } else {
+                // 复制文件
+                Self::copy_file(&entry_path, &dest_entry_path, options)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 复制单个文件，按需校验复制结果
+    ///
+    /// 超过流式复制阈值的大文件分块读写并逐块报告进度，其余文件直接使用 `fs::copy`，完成后报告一次
+    fn copy_file(source: &Path, dest: &Path, options: FileCopyOptions<'_>) -> Result<(), CopyError> {
+        let size = fs::metadata(source)
+            .map_err(|e| format!("读取文件信息失败 {}: {}", source.display(), e))?
+            .len();
+
+        let report = |copied: u64, total: u64| {
+            if let Some(progress) = options.progress {
+                progress(&CopyProgress {
+                    path: utils::strip_long_path_prefix(&source.to_string_lossy()),
+                    copied,
+                    total,
+                });
+            }
+        };
+
+        if size > options.stream_threshold {
+            Self::copy_file_streamed(source, dest, options.buffer_size, options.cancel, report)?;
+        } else {
+            fs::copy(source, dest)
+                .map_err(|e| format!("复制文件失败 {} -> {}: {}", source.display(), dest.display(), e))?;
+            report(size, size);
         }
 
-        Ok(true)
+        if options.verify {
+            Self::verify_copy(source, dest)?;
+        }
+
+        Ok(())
     }
 
-    /// 剪切文件（移动文件）
+    /// 分块复制文件
+    ///
+    /// 按缓冲区大小逐块读写，每写完一块调用一次进度回调；复制失败或被取消时删除不完整的目标文件。
+    /// 与 `fs::copy` 一样，复制完成后沿用源文件的权限
     ///
     /// # 参数
-    /// - `db`: 全局数据库实例
-    /// - `paths`: 要剪切的文件/文件夹路径列表
+    /// - `source`: 源文件路径
+    /// - `dest`: 目标文件路径
+    /// - `buffer_size`: 缓冲区大小（字节，最小为1）
+    /// - `cancel`: 取消令牌，每块读写前检查
+    /// - `progress`: 进度回调，参数为已复制字节数和文件总字节数
+    ///
+    /// # 返回
+    /// - `Ok(u64)`: 复制的字节数
+    /// - `Err(CopyError::Cancelled)`: 被取消，不完整的目标文件已删除
+    /// - `Err(CopyError::Failed)`: 错误信息
+    pub(crate) fn copy_file_streamed(
+        source: &Path,
+        dest: &Path,
+        buffer_size: usize,
+        cancel: Option<&CancellationToken>,
+        mut progress: impl FnMut(u64, u64),
+    ) -> Result<u64, CopyError> {
+        use std::io::{Read, Write};
+
+        let mut reader = fs::File::open(source)
+            .map_err(|e| format!("打开文件失败 {}: {}", source.display(), e))?;
+        let metadata = reader.metadata()
+            .map_err(|e| format!("读取文件信息失败 {}: {}", source.display(), e))?;
+        let total = metadata.len();
+        let mut writer = fs::File::create(dest)
+            .map_err(|e| format!("创建文件失败 {}: {}", dest.display(), e))?;
+
+        let mut buffer = vec![0u8; buffer_size.max(1)];
+        let mut copied = 0u64;
+        let result = loop {
+            if cancel.is_some_and(CancellationToken::is_cancelled) {
+                drop(writer);
+                let _ = fs::remove_file(dest);
+                return Err(CopyError::Cancelled);
+            }
+
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break writer.flush(),
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => break Err(e),
+            };
+            if let Err(e) = writer.write_all(&buffer[..read]) {
+                break Err(e);
+            }
+            copied += read as u64;
+            progress(copied, total);
+        };
+
+        if let Err(e) = result {
+            drop(writer);
+            let _ = fs::remove_file(dest);
+            return Err(format!("复制文件失败 {} -> {}: {}", source.display(), dest.display(), e).into());
+        }
+
+        let _ = fs::set_permissions(dest, metadata.permissions());
+        Ok(copied)
+    }
+
+    /// 校验复制结果
+    ///
+    /// 比较源文件和目标文件的 SHA-256，不一致时删除目标文件并返回错误
+    ///
+    /// # 参数
+    /// - `source`: 源文件路径
+    /// - `dest`: 目标文件路径
+    ///
+    /// # 返回
+    /// - `Ok(())`: 内容一致
+    /// - `Err(String)`: 错误信息
+    pub(crate) fn verify_copy(source: &Path, dest: &Path) -> Result<(), String> {
+        let source_hash = utils::sha256_file(source)?;
+        let dest_hash = utils::sha256_file(dest)?;
+
+        if source_hash != dest_hash {
+            let _ = fs::remove_file(dest);
+            return Err(format!("文件校验失败，复制结果与源文件不一致: {}", dest.display()));
+        }
+
+        Ok(())
+    }
+
+    /// 计算目录总大小
+    ///
+    /// 递归统计目录下所有文件的字节数
+    ///
+    /// # 参数
+    /// - `path`: 目录路径
+    /// - `follow_symlinks`: 符号链接跟随策略，默认为从不跟随
+    ///
+    /// # 返回
+    /// - `Ok(u64)`: 目录总大小（字节）
+    /// - `Err(String)`: 错误信息
+    pub fn get_directory_size(
+        path: &str,
+        follow_symlinks: Option<FollowSymlinks>,
+    ) -> Result<u64, String> {
+        let follow_symlinks = follow_symlinks.unwrap_or(FollowSymlinks::Never);
+        let dir_path = Path::new(path);
+
+        if !dir_path.exists() {
+            return Err(format!("路径不存在: {}", path));
+        }
+
+        if !dir_path.is_dir() {
+            return Err(format!("路径不是目录: {}", path));
+        }
+
+        let mut visited = HashSet::new();
+        Self::directory_size(dir_path, follow_symlinks, &mut visited)
+    }
+
+    /// 导出目录树快照
+    ///
+    /// 递归遍历目录，生成包含名称、大小和类型的嵌套 JSON，可用于生成清单或备份前对比。
+    /// 不跟随符号链接，避免循环。导出的节点数（不含根目录）达到 `max_results` 后停止遍历，
+    /// 此时已导出的文件夹大小只包含已列出的子节点
+    ///
+    /// # 参数
+    /// - `root`: 根目录路径
+    /// - `max_depth`: 导出的最大层级，0 表示只导出根目录本身；更深的文件夹只统计大小
+    /// - `max_results`: 最多导出的节点数量
+    ///
+    /// # 返回
+    /// - `Ok(String)`: 目录树快照（`TreeSnapshot`）的 JSON 字符串
+    /// - `Err(String)`: 错误信息
+    pub fn export_tree(root: &str, max_depth: usize, max_results: usize) -> Result<String, String> {
+        let root_path = Path::new(root);
+
+        if !root_path.exists() {
+            return Err(format!("路径不存在: {}", root));
+        }
+
+        if !root_path.is_dir() {
+            return Err(format!("路径不是目录: {}", root));
+        }
+
+        let mut remaining = max_results;
+        let mut truncated = false;
+        let root = Self::tree_node(root_path, max_depth, &mut remaining, &mut truncated)?;
+        serde_json::to_string_pretty(&TreeSnapshot { root, truncated })
+            .map_err(|e| format!("序列化目录树失败: {}", e))
+    }
+
+    /// 递归构建目录树节点
+    ///
+    /// `depth` 为剩余可展开的层级，为 0 时文件夹不再列出子节点；
+    /// `remaining` 为还能导出的节点数，用完时停止列出并设置 `truncated`
+    fn tree_node(path: &Path, depth: usize, remaining: &mut usize, truncated: &mut bool) -> Result<TreeNode, String> {
+        let name = path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string());
+
+        let metadata = fs::symlink_metadata(path)
+            .map_err(|e| format!("获取文件元数据失败 {}: {}", path.display(), e))?;
+
+        if !metadata.is_dir() {
+            return Ok(TreeNode {
+                name,
+                file_type: "file".to_string(),
+                size: metadata.len(),
+                children: Vec::new(),
+            });
+        }
+
+        if depth == 0 {
+            let mut visited = HashSet::new();
+            return Ok(TreeNode {
+                name,
+                file_type: "folder".to_string(),
+                size: Self::directory_size(path, FollowSymlinks::Never, &mut visited)?,
+                children: Vec::new(),
+            });
+        }
+
+        let entries = fs::read_dir(path)
+            .map_err(|e| format!("读取目录失败 {}: {}", path.display(), e))?;
+
+        let mut children = Vec::new();
+        for entry in entries {
+            if *remaining == 0 {
+                *truncated = true;
+                break;
+            }
+            *remaining -= 1;
+
+            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+            children.push(Self::tree_node(&entry.path(), depth - 1, remaining, truncated)?);
+        }
+        children.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(TreeNode {
+            name,
+            file_type: "folder".to_string(),
+            size: children.iter().map(|child| child.size).sum(),
+            children,
+        })
+    }
+
+    /// 复制前预检
+    ///
+    /// 统计待复制内容的总大小、检查目标磁盘可用空间并列出名称冲突，不执行任何复制
+    ///
+    /// # 参数
+    /// - `paths`: 要复制的文件/文件夹路径列表
     /// - `target_path`: 目标目录路径
     ///
     /// # 返回
-    /// - `Ok(())`: 操作成功
+    /// - `Ok(PreflightReport)`: 预检结果
     /// - `Err(String)`: 错误信息
-    pub async fn cut_files(
-        db: &GlobalDatabase,
+    pub fn preflight_copy(paths: &[String], target_path: &str) -> Result<PreflightReport, String> {
+        let available = Self::available_space(target_path)?;
+        Self::preflight_copy_with_space(paths, target_path, available)
+    }
+
+    /// 根据给定的可用空间生成预检结果（`preflight_copy` 的实现）
+    pub(crate) fn preflight_copy_with_space(
         paths: &[String],
         target_path: &str,
-    ) -> Result<(), String> {
+        available_space: u64,
+    ) -> Result<PreflightReport, String> {
         let target_dir = Path::new(target_path);
+        if !target_dir.is_dir() {
+            return Err(format!("目标路径不是目录: {}", target_path));
+        }
 
-        // 检查目标路径是否存在且为目录
-        if !target_dir.exists() {
-            return Err(format!("目标路径不存在: {}", target_path));
+        let mut total_size = 0u64;
+        let mut conflicts = Vec::new();
+        for path in paths {
+            let source_path = Path::new(path);
+            let metadata = fs::metadata(source_path)
+                .map_err(|_| format!("源路径不存在: {}", path))?;
+
+            // 复制默认跟随符号链接，统计时保持一致
+            total_size += if metadata.is_dir() {
+                let mut visited = HashSet::new();
+                Self::directory_size(source_path, FollowSymlinks::Always, &mut visited)?
+            } else {
+                metadata.len()
+            };
+
+            let file_name = source_path.file_name()
+                .ok_or_else(|| format!("无法获取文件名: {}", path))?;
+            let dest_path = target_dir.join(file_name);
+            if fs::symlink_metadata(&dest_path).is_ok() {
+                conflicts.push(dest_path.to_string_lossy().to_string());
+            }
         }
 
-        if !target_dir.is_dir() {
-            return Err(format!("目标路径不是目录: {}", target_path));
+        Ok(PreflightReport {
+            total_size,
+            available_space,
+            enough_space: total_size <= available_space,
+            conflicts,
+        })
+    }
+
+    /// 获取路径所在磁盘的可用空间
+    ///
+    /// # 参数
+    /// - `path`: 文件或目录路径
+    ///
+    /// # 返回
+    /// - `Ok(u64)`: 可用空间（字节）
+    /// - `Err(String)`: 错误信息
+    pub fn available_space(path: &str) -> Result<u64, String> {
+        let target = Path::new(path);
+
+        if !target.exists() {
+            return Err(format!("路径不存在: {}", path));
+        }
+
+        utils::available_space(&utils::to_long_path(target))
+            .map_err(|e| format!("获取可用空间失败 {}: {}", path, e))
+    }
+
+    /// 递归计算目录大小
+    fn directory_size(
+        dir: &Path,
+        follow_symlinks: FollowSymlinks,
+        visited: &mut HashSet<PathBuf>,
+    ) -> Result<u64, String> {
+        if !Self::mark_visited(dir, follow_symlinks, visited)? {
+            return Ok(0);
+        }
+
+        let entries = fs::read_dir(dir)
+            .map_err(|e| format!("读取目录失败 {}: {}", dir.display(), e))?;
+
+        let mut total = 0;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+            let entry_path = entry.path();
+
+            let metadata = match Self::resolve_entry_metadata(&entry_path, follow_symlinks)? {
+                Some(metadata) => metadata,
+                None => continue,
+            };
+
+            if metadata.is_dir() {
+                total += Self::directory_size(&entry_path, follow_symlinks, visited)?;
+            } else {
+                total += metadata.len();
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// 按符号链接策略获取目录项的元数据
+    ///
+    /// # 返回
+    /// - `Ok(Some(Metadata))`: 需要处理的目录项元数据（符号链接时为目标的元数据）
+    /// - `Ok(None)`: 按策略应跳过该项（包括悬空链接）
+    /// - `Err(String)`: 错误信息
+    fn resolve_entry_metadata(
+        path: &Path,
+        follow_symlinks: FollowSymlinks,
+    ) -> Result<Option<fs::Metadata>, String> {
+        let link_metadata = fs::symlink_metadata(path)
+            .map_err(|e| format!("获取文件元数据失败 {}: {}", path.display(), e))?;
+
+        if !link_metadata.file_type().is_symlink() {
+            return Ok(Some(link_metadata));
+        }
+
+        // 悬空链接没有可读取的目标，直接跳过
+        let target_metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(_) => return Ok(None),
+        };
+
+        match follow_symlinks {
+            FollowSymlinks::Never => Ok(None),
+            FollowSymlinks::FilesOnly if target_metadata.is_dir() => Ok(None),
+            _ => Ok(Some(target_metadata)),
+        }
+    }
+
+    /// 记录已访问的目录
+    ///
+    /// 仅在始终跟随符号链接时才可能出现循环，此时以规范路径判重
+    ///
+    /// # 返回
+    /// - `Ok(true)`: 首次访问，应继续遍历
+    /// - `Ok(false)`: 已访问过，应跳过
+    /// - `Err(String)`: 错误信息
+    fn mark_visited(
+        dir: &Path,
+        follow_symlinks: FollowSymlinks,
+        visited: &mut HashSet<PathBuf>,
+    ) -> Result<bool, String> {
+        if follow_symlinks != FollowSymlinks::Always {
+            return Ok(true);
+        }
+
+        let canonical = fs::canonicalize(dir)
+            .map_err(|e| format!("解析规范路径失败 {}: {}", dir.display(), e))?;
+
+        Ok(visited.insert(canonical))
+    }
+
+    /// 按文件名查找已登记的文件
+    ///
+    /// 在 files 表中查找最后一级路径与 `name` 完全一致的记录（不访问文件系统遍历），
+    /// 已不存在于磁盘上的记录会被跳过
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `name`: 文件/文件夹名称
+    /// - `display`: 文件项的展示选项
+    ///
+    /// # 返回
+    /// - `Ok(Vec<FileItem>)`: 匹配的文件列表（按路径排序）
+    /// - `Err(String)`: 错误信息
+    pub async fn find_by_name(
+        db: &GlobalDatabase,
+        name: &str,
+        display: &DisplayOptions,
+    ) -> Result<Vec<FileItem>, String> {
+        if name.trim().is_empty() {
+            return Err("文件名不能为空".to_string());
+        }
+
+        if name.contains('/') || name.contains('\\') {
+            return Err(format!("文件名不能包含路径分隔符: {}", name));
         }
 
-        // 获取数据库连接
         let connection = db
             .get_connection()
             .await
             .map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
-        // 移动每个文件/文件夹
+        let paths = match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                Self::find_paths_by_name_postgres(&pool, name).await?
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                Self::find_paths_by_name_sqlite(&pool, name).await?
+            }
+        };
+
+        let mut items = Vec::new();
         for path in paths {
-            let source_path = Path::new(path);
+            let path_obj = Path::new(&path);
+            if fs::symlink_metadata(path_obj).is_err() {
+                continue;
+            }
+            items.push(Self::build_file_item(path_obj, display)?);
+        }
 
-            if !source_path.exists() {
-                return Err(format!("源路径不存在: {}", path));
+        Ok(items)
+    }
+
+    /// 对账文件记录与磁盘
+    ///
+    /// 扫描所有未删除的文件记录，软删除磁盘上已不存在的路径（如在应用外被删除的文件），
+    /// 并重新统计受影响标签的使用次数，保证按标签浏览的结果准确
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    ///
+    /// # 返回
+    /// - `Ok(ReconcileReport)`: 检查和软删除的记录数
+    /// - `Err(String)`: 错误信息
+    pub async fn reconcile(db: &GlobalDatabase) -> Result<ReconcileReport, String> {
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let paths: Vec<String> = match &connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                sqlx::query_scalar("SELECT current_path FROM files WHERE deleted_at IS NULL")
+                    .fetch_all(pool)
+                    .await
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                sqlx::query_scalar("SELECT current_path FROM files WHERE deleted_at IS NULL")
+                    .fetch_all(pool)
+                    .await
             }
+        }
+        .map_err(|e| format!("查询文件记录失败: {}", e))?;
 
-            // 获取文件名
-            let file_name = source_path.file_name()
-                .and_then(|n| n.to_str())
-                .ok_or_else(|| format!("无法获取文件名: {}", path))?;
+        // 悬空的符号链接本身仍存在，不视为缺失
+        let missing: Vec<String> = paths
+            .iter()
+            .filter(|path| fs::symlink_metadata(path).is_err())
+            .cloned()
+            .collect();
+
+        if !missing.is_empty() {
+            // 缺失文件夹下的子文件记录同样会被单独检出，这里按普通路径逐条软删除即可
+            match &connection {
+                DatabaseConnectionRef::Postgres(pool) => {
+                    Self::soft_delete_files_postgres(pool, &missing, &[]).await?
+                }
+                DatabaseConnectionRef::Sqlite(pool) => {
+                    Self::soft_delete_files_sqlite(pool, &missing, &[]).await?
+                }
+            }
+        }
+
+        Ok(ReconcileReport {
+            checked: paths.len(),
+            removed: missing.len(),
+        })
+    }
+
+    /// 列出未添加任何标签的已登记文件
+    ///
+    /// 只统计未删除标签的关联；已不存在于磁盘上的记录不出现在结果中
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `limit`: 最多返回的记录数
+    /// - `offset`: 跳过的记录数
+    /// - `display`: 文件项的展示选项
+    ///
+    /// # 返回
+    /// - `Ok(Vec<FileItem>)`: 文件列表（按路径排序）
+    /// - `Err(String)`: 错误信息
+    pub async fn list_untagged(
+        db: &GlobalDatabase,
+        limit: usize,
+        offset: usize,
+        display: &DisplayOptions,
+    ) -> Result<Vec<FileItem>, String> {
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let rows: Vec<(String, Option<String>, Option<String>)> = match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                sqlx::query_as(
+                    r#"
+                    SELECT
+                        f.current_path,
+                        TO_CHAR(f.created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS registered_at,
+                        TO_CHAR(f.updated_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS db_updated_at
+                    FROM files f
+                    WHERE f.deleted_at IS NULL
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM file_tags ft
+                        INNER JOIN tags t ON t.id = ft.tag_id
+                        WHERE ft.file_id = f.id AND t.deleted_at IS NULL
+                    )
+                    ORDER BY f.current_path
+                    LIMIT $1 OFFSET $2
+                    "#,
+                )
+                .bind(limit as i64)
+                .bind(offset as i64)
+                .fetch_all(&pool)
+                .await
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                sqlx::query_as(
+                    r#"
+                    SELECT
+                        f.current_path,
+                        datetime(f.created_at) AS registered_at,
+                        datetime(f.updated_at) AS db_updated_at
+                    FROM files f
+                    WHERE f.deleted_at IS NULL
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM file_tags ft
+                        INNER JOIN tags t ON t.id = ft.tag_id
+                        WHERE ft.file_id = f.id AND t.deleted_at IS NULL
+                    )
+                    ORDER BY f.current_path
+                    LIMIT ?1 OFFSET ?2
+                    "#,
+                )
+                .bind(limit as i64)
+                .bind(offset as i64)
+                .fetch_all(&pool)
+                .await
+            }
+        }
+        .map_err(|e| format!("查询未添加标签的文件失败: {}", e))?;
+
+        let mut items = Vec::new();
+        for (path, registered_at, db_updated_at) in rows {
+            let path_obj = Path::new(&path);
+            if fs::symlink_metadata(path_obj).is_err() {
+                continue;
+            }
+            let mut item = Self::build_file_item(path_obj, display)?;
+            item.registered_at = registered_at;
+            item.db_updated_at = db_updated_at;
+            items.push(item);
+        }
+
+        Ok(items)
+    }
+
+    /// 计算相对于基准目录的路径
+    ///
+    /// 按路径组件比较（Windows 上不区分大小写），`path` 位于 `base` 之下时返回剩余部分，
+    /// 与 `base` 相同时返回 `.`，否则原样返回 `path`
+    ///
+    /// # 参数
+    /// - `path`: 要显示的路径
+    /// - `base`: 基准目录（如当前目录或主目录）
+    ///
+    /// # 返回
+    /// 用于显示的路径
+    pub fn relativize(path: &str, base: &str) -> String {
+        let path_components: Vec<_> = Path::new(path).components().collect();
+        let base_components: Vec<_> = Path::new(base).components().collect();
 
-            // 构建目标路径
-            let dest_path = target_dir.join(file_name);
-            let dest_path_str = dest_path.to_string_lossy().to_string();
+        if base_components.is_empty() || base_components.len() > path_components.len() {
+            return path.to_string();
+        }
 
-            // 如果目标路径已存在，返回错误
-            if dest_path.exists() {
-                return Err(format!("目标路径已存在: {}", dest_path.display()));
+        let same = |a: &std::path::Component, b: &std::path::Component| {
+            if cfg!(windows) {
+                a.as_os_str().to_string_lossy().to_lowercase() == b.as_os_str().to_string_lossy().to_lowercase()
+            } else {
+                a == b
             }
+        };
+        if !base_components.iter().zip(&path_components).all(|(b, p)| same(b, p)) {
+            return path.to_string();
+        }
 
-            // 移动文件/文件夹
-            fs::rename(source_path, &dest_path)
-                .map_err(|e| format!("移动文件失败 {} -> {}: {}", path, dest_path.display(), e))?;
+        let rest: PathBuf = path_components[base_components.len()..].iter().collect();
+        if rest.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            rest.to_string_lossy().to_string()
+        }
+    }
 
-            // 如果源文件在 files 表中有记录，更新 current_path 字段
-            match &connection {
-                DatabaseConnectionRef::Postgres(pool) => {
-                    Self::update_file_path_postgres(pool, path, &dest_path_str).await?;
-                }
-                DatabaseConnectionRef::Sqlite(pool) => {
-                    Self::update_file_path_sqlite(pool, path, &dest_path_str).await?;
-                }
-            }
+    /// 将路径格式化为可直接粘贴到终端的形式
+    ///
+    /// 只包含字母、数字和 `/._-:+` 的路径原样返回（`Cmd`、`PowerShell` 还允许 `\`），
+    /// 否则按 shell 规则加引号：
+    /// - `Cmd`: 双引号包裹
+    /// - `PowerShell`: 单引号包裹，内部的 `'` 写作 `''`
+    /// - `PosixSh`: 单引号包裹，内部的 `'` 写作 `'\''`
+    ///
+    /// # 参数
+    /// - `path`: 文件路径
+    /// - `shell`: 目标 shell
+    ///
+    /// # 返回
+    /// 加引号或转义后的路径
+    pub fn format_path_for_shell(path: &str, shell: ShellKind) -> String {
+        // 反斜杠在 POSIX shell 中是转义符，需要加引号
+        let is_safe = |c: char| {
+            c.is_alphanumeric()
+                || matches!(c, '/' | '.' | '_' | '-' | ':' | '+')
+                || (c == '\\' && shell != ShellKind::PosixSh)
+        };
+        if !path.is_empty() && path.chars().all(is_safe) {
+            return path.to_string();
         }
 
-        Ok(())
+        match shell {
+            ShellKind::Cmd => format!("\"{}\"", path),
+            ShellKind::PowerShell => format!("'{}'", path.replace('\'', "''")),
+            ShellKind::PosixSh => format!("'{}'", path.replace('\'', "'\\''")),
+        }
     }
 
-    /// 复制文件
+    /// 为搜索结果中的每一项填充相对于基准目录的路径
+    ///
+    /// # 参数
+    /// - `result`: 搜索结果
+    /// - `base`: 基准目录，为 None 时不做处理
+    pub fn relativize_result(result: &mut SearchResult, base: Option<&str>) {
+        if let Some(base) = base {
+            for item in &mut result.items {
+                item.relative_path = Some(Self::relativize(&item.path, base));
+            }
+        }
+    }
+
+    /// 在已登记的文件中按路径搜索
+    ///
+    /// 在 files 表中对 current_path 做不区分大小写的包含匹配（不访问文件系统遍历），
+    /// 先查询匹配总数再取当前页。已不存在于磁盘上的记录不出现在结果中，但仍计入总数
     ///
     /// # 参数
     /// - `db`: 全局数据库实例
-    /// - `paths`: 要复制的文件/文件夹路径列表
-    /// - `target_path`: 目标目录路径
+    /// - `query`: 路径关键词
+    /// - `page`: 页码（从1开始）
+    /// - `page_size`: 每页数量
+    /// - `display`: 文件项的展示选项
     ///
     /// # 返回
-    /// - `Ok(())`: 操作成功
+    /// - `Ok(SearchResult)`: 搜索结果（按路径排序）
     /// - `Err(String)`: 错误信息
-    pub async fn copy_files(
+    pub async fn search_registered_files(
         db: &GlobalDatabase,
-        paths: &[String],
-        target_path: &str,
-    ) -> Result<(), String> {
-        let target_dir = Path::new(target_path);
-
-        // 检查目标路径是否存在且为目录
-        if !target_dir.exists() {
-            return Err(format!("目标路径不存在: {}", target_path));
-        }
+        query: &str,
+        page: usize,
+        page_size: usize,
+        display: &DisplayOptions,
+    ) -> Result<SearchResult, String> {
+        let page = page.max(1);
+        let offset = (page - 1) * page_size;
+        let pattern = format!("%{}%", TagService::escape_like(query));
 
-        if !target_dir.is_dir() {
-            return Err(format!("目标路径不是目录: {}", target_path));
-        }
-
-        // 获取数据库连接
         let connection = db
             .get_connection()
             .await
             .map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
-        // 复制每个文件/文件夹
-        for path in paths {
-            let source_path = Path::new(path);
+        let (total, rows) = match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                Self::search_registered_paths_postgres(&pool, &pattern, page_size, offset).await?
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                Self::search_registered_paths_sqlite(&pool, &pattern, page_size, offset).await?
+            }
+        };
 
-            if !source_path.exists() {
-                return Err(format!("源路径不存在: {}", path));
+        // has_more 按数据库返回的行数计算，不受跳过的缺失文件影响
+        let has_more = offset + rows.len() < total;
+
+        let mut items = Vec::new();
+        for (path, registered_at, db_updated_at) in rows {
+            let path_obj = Path::new(&path);
+            if fs::symlink_metadata(path_obj).is_err() {
+                continue;
             }
+            let mut item = Self::build_file_item(path_obj, display)?;
+            item.registered_at = registered_at;
+            item.db_updated_at = db_updated_at;
+            items.push(item);
+        }
 
-            // 获取文件名
-            let file_name = source_path.file_name()
-                .and_then(|n| n.to_str())
-                .ok_or_else(|| format!("无法获取文件名: {}", path))?;
+        Ok(SearchResult {
+            items,
+            total,
+            page,
+            page_size,
+            has_more,
+        })
+    }
 
-            // 构建目标路径
-            let dest_path = target_dir.join(file_name);
-            let dest_path_str = dest_path.to_string_lossy().to_string();
+    /// PostgreSQL 实现：按路径关键词分页查询已登记的文件路径及登记时间
+    async fn search_registered_paths_postgres(
+        pool: &Pool<Postgres>,
+        pattern: &str,
+        page_size: usize,
+        offset: usize,
+    ) -> Result<(usize, Vec<(String, Option<String>, Option<String>)>), String> {
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM files
+            WHERE deleted_at IS NULL AND current_path ILIKE $1 ESCAPE '\'
+            "#,
+        )
+        .bind(pattern)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("查询文件总数失败: {}", e))?;
 
-            // 如果目标路径已存在，返回错误
-            if dest_path.exists() {
-                return Err(format!("目标路径已存在: {}", dest_path.display()));
-            }
+        let rows = sqlx::query_as(
+            r#"
+            SELECT
+                current_path,
+                TO_CHAR(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS registered_at,
+                TO_CHAR(updated_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS db_updated_at
+            FROM files
+            WHERE deleted_at IS NULL AND current_path ILIKE $1 ESCAPE '\'
+            ORDER BY current_path
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(pattern)
+        .bind(page_size as i64)
+        .bind(offset as i64)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查询文件列表失败: {}", e))?;
 
-            // 复制文件/文件夹
-            if source_path.is_dir() {
-                // 递归复制目录
-                Self::copy_directory(source_path, &dest_path)?;
-            } else {
-                // 复制文件
-                fs::copy(source_path, &dest_path)
-                    .map_err(|e| format!("复制文件失败 {} -> {}: {}", path, dest_path.display(), e))?;
-            }
+        Ok((total as usize, rows))
+    }
 
-            // 检查源文件是否有标签，如果有则复制标签到新文件
-            match &connection {
-                DatabaseConnectionRef::Postgres(pool) => {
-                    Self::copy_file_tags_postgres(pool, path, &dest_path_str).await?;
-                }
-                DatabaseConnectionRef::Sqlite(pool) => {
-                    Self::copy_file_tags_sqlite(pool, path, &dest_path_str).await?;
-                }
-            }
-        }
+    /// SQLite 实现：按路径关键词分页查询已登记的文件路径及登记时间
+    async fn search_registered_paths_sqlite(
+        pool: &Pool<Sqlite>,
+        pattern: &str,
+        page_size: usize,
+        offset: usize,
+    ) -> Result<(usize, Vec<(String, Option<String>, Option<String>)>), String> {
+        // SQLite 的 LIKE 对 ASCII 字符不区分大小写
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM files
+            WHERE deleted_at IS NULL AND current_path LIKE ?1 ESCAPE '\'
+            "#,
+        )
+        .bind(pattern)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("查询文件总数失败: {}", e))?;
 
-        Ok(())
+        let rows = sqlx::query_as(
+            r#"
+            SELECT
+                current_path,
+                datetime(created_at) AS registered_at,
+                datetime(updated_at) AS db_updated_at
+            FROM files
+            WHERE deleted_at IS NULL AND current_path LIKE ?1 ESCAPE '\'
+            ORDER BY current_path
+            LIMIT ?2 OFFSET ?3
+            "#,
+        )
+        .bind(pattern)
+        .bind(page_size as i64)
+        .bind(offset as i64)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查询文件列表失败: {}", e))?;
+
+        Ok((total as usize, rows))
     }
 
-    /// 递归复制目录
-    ///
-    /// # 参数
-    /// - `source`: 源目录路径
-    /// - `dest`: 目标目录路径
-    ///
-    /// # 返回
-    /// - `Ok(())`: 操作成功
-    /// - `Err(String)`: 错误信息
-    fn copy_directory(source: &Path, dest: &Path) -> Result<(), String> {
-        // 创建目标目录
-        fs::create_dir_all(dest)
-            .map_err(|e| format!("创建目标目录失败 {}: {}", dest.display(), e))?;
+    /// PostgreSQL 实现：按最后一级路径名查找文件路径
+    async fn find_paths_by_name_postgres(pool: &Pool<Postgres>, name: &str) -> Result<Vec<String>, String> {
+        // 比较路径末尾的 "分隔符 + 名称"，避免 LIKE 通配符问题
+        let suffix_len = name.chars().count() as i32 + 1;
+        let rows = sqlx::query(
+            r#"
+            SELECT current_path
+            FROM files
+            WHERE deleted_at IS NULL
+            AND (current_path = $1 OR right(current_path, $2) IN ('/' || $1, '\' || $1))
+            ORDER BY current_path
+            "#,
+        )
+        .bind(name)
+        .bind(suffix_len)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("按名称查找文件失败: {}", e))?;
 
-        // 读取源目录内容
-        let entries = fs::read_dir(source)
-            .map_err(|e| format!("读取目录失败 {}: {}", source.display(), e))?;
+        Ok(rows.into_iter().map(|row| row.get("current_path")).collect())
+    }
 
-        // 复制每个条目
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
-            let entry_path = entry.path();
-            let entry_name = entry_path.file_name()
-                .and_then(|n| n.to_str())
-                .ok_or_else(|| format!("无法获取文件名: {}", entry_path.display()))?;
+    /// SQLite 实现：按最后一级路径名查找文件路径
+    async fn find_paths_by_name_sqlite(pool: &Pool<Sqlite>, name: &str) -> Result<Vec<String>, String> {
+        // 比较路径末尾的 "分隔符 + 名称"，避免 LIKE 通配符问题
+        let suffix_len = name.chars().count() as i32 + 1;
+        let rows = sqlx::query(
+            r#"
+            SELECT current_path
+            FROM files
+            WHERE deleted_at IS NULL
+            AND (current_path = ?1 OR substr(current_path, -?2) IN ('/' || ?1, '\' || ?1))
+            ORDER BY current_path
+            "#,
+        )
+        .bind(name)
+        .bind(suffix_len)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("按名称查找文件失败: {}", e))?;
 
-            // 跳过隐藏文件
-            if entry_name.starts_with('.') {
-                continue;
-            }
+        Ok(rows.into_iter().map(|row| row.get("current_path")).collect())
+    }
 
-            let dest_entry_path = dest.join(entry_name);
+    /// 根据路径构建 FileItem
+    ///
+    /// 符号链接展示其目标的类型和大小，悬空链接保留链接自身的信息
+    pub(crate) fn build_file_item(file_path: &Path, display: &DisplayOptions) -> Result<FileItem, String> {
+        let link_metadata = fs::symlink_metadata(file_path)
+            .map_err(|e| format!("获取文件元数据失败 {}: {}", file_path.display(), e))?;
+        Self::file_item_from_metadata(file_path, link_metadata, display)
+    }
 
-            if entry_path.is_dir() {
-                // 递归复制子目录
-                Self::copy_directory(&entry_path, &dest_entry_path)?;
-            } else {
-                // 复制文件
-                fs::copy(&entry_path, &dest_entry_path)
-                    .map_err(|e| format!("复制文件失败 {} -> {}: {}", entry_path.display(), dest_entry_path.display(), e))?;
-            }
-        }
+    /// 根据路径及其 `symlink_metadata` 构建 FileItem
+    ///
+    /// `list_directory` 与 `build_file_item` 共用的构建逻辑，FileItem 新增字段时只需修改这里。
+    /// 数据库相关字段（`registered_at` 等）和 `child_count` 留空，由调用方按需填写
+    fn file_item_from_metadata(
+        file_path: &Path,
+        link_metadata: fs::Metadata,
+        display: &DisplayOptions,
+    ) -> Result<FileItem, String> {
+        let is_symlink = link_metadata.file_type().is_symlink();
+        let metadata = if is_symlink {
+            fs::metadata(file_path).unwrap_or(link_metadata)
+        } else {
+            link_metadata
+        };
 
-        Ok(())
+        let file_name = file_path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+        let is_dir = metadata.is_dir();
+        let extension = file_path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|s| s.to_string());
+
+        let modified = metadata.modified()
+            .map_err(|e| format!("获取修改时间失败: {}", e))?;
+        let created = utils::created_time(file_path, &metadata)
+            .unwrap_or(modified);
+
+        let category = if is_dir {
+            "other"
+        } else {
+            utils::detect_category(extension.as_deref())
+        };
+
+        Ok(FileItem {
+            id: file_path.to_string_lossy().to_string(),
+            is_hidden: utils::is_hidden(&file_name, &metadata, display.hidden_detection),
+            name: file_name,
+            path: file_path.to_string_lossy().to_string(),
+            file_type: if is_dir { "folder".to_string() } else { "file".to_string() },
+            size: metadata.len(),
+            size_display: utils::format_size_with(metadata.len(), display.size_units),
+            modified_date: utils::format_iso8601(&modified, display.timezone),
+            created_date: utils::format_iso8601(&created, display.timezone),
+            extension,
+            category: category.to_string(),
+            is_symlink,
+            readonly: metadata.permissions().readonly(),
+            mode: utils::unix_mode(&metadata),
+            registered_at: None,
+            db_updated_at: None,
+            relative_path: None,
+            child_count: None,
+        })
     }
 
     /// 重命名文件或文件夹
@@ -539,6 +2994,18 @@ impl FileSystemService {
         db: &GlobalDatabase,
         old_path: &str,
         new_name: &str,
+    ) -> Result<(), String> {
+        Self::rename_path(db, old_path, new_name, true).await
+    }
+
+    /// 重命名文件或文件夹的实现
+    ///
+    /// `record` 为 true 时记录操作日志；撤销重命名时传入 false，避免撤销本身被记录
+    async fn rename_path(
+        db: &GlobalDatabase,
+        old_path: &str,
+        new_name: &str,
+        record: bool,
     ) -> Result<(), String> {
         let source_path = Path::new(old_path);
 
@@ -557,85 +3024,316 @@ impl FileSystemService {
             return Err("新名称不能为空".to_string());
         }
 
+        // Windows 下保留设备名和以点或空格结尾的名称会导致难以理解的失败
+        #[cfg(windows)]
+        if Self::is_reserved_device_name(new_name) || new_name.ends_with(['.', ' ']) {
+            return Err(format!("名称为系统保留名: {}", new_name));
+        }
+
         // 获取父目录
         let parent_dir = source_path.parent()
             .ok_or_else(|| format!("无法获取父目录: {}", old_path))?;
 
         // 构建新路径
         let new_path = parent_dir.join(new_name);
-        let new_path_str = new_path.to_string_lossy().to_string();
+        let new_path_str = utils::strip_long_path_prefix(&new_path.to_string_lossy());
+
+        // 如果目标路径已存在，返回错误
+        if new_path.exists() {
+            return Err(format!("目标路径已存在: {}", new_path.display()));
+        }
+
+        // 重命名文件/文件夹
+        fs::rename(utils::to_long_path(source_path), utils::to_long_path(&new_path))
+            .map_err(|e| format!("重命名失败 {} -> {}: {}", old_path, new_path.display(), e))?;
+
+        // 更新数据库中的路径
+        // 如果重命名的是文件夹，其下所有子文件记录的路径前缀也需要一并更新
+        let is_dir = new_path.is_dir();
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        match &connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                Self::update_file_path_postgres(pool, old_path, &new_path_str).await?;
+                if is_dir {
+                    Self::update_child_paths_postgres(pool, old_path, &new_path_str).await?;
+                }
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                Self::update_file_path_sqlite(pool, old_path, &new_path_str).await?;
+                if is_dir {
+                    Self::update_child_paths_sqlite(pool, old_path, &new_path_str).await?;
+                }
+            }
+        }
+
+        if record {
+            OperationLog::record(&connection, OperationKind::Rename, None, old_path, Some(&new_path_str)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 撤销最近一次文件操作
+    ///
+    /// 同一次调用产生的操作（如批量移动、按扩展名整理）整批撤销。
+    /// 重命名撤销为反向重命名，移动撤销为移回原目录（自动重命名过的项同时恢复原名称）。
+    /// 最近一次操作为永久删除时无法撤销
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    ///
+    /// # 返回
+    /// - `Ok(())`: 撤销成功
+    /// - `Err(String)`: 错误信息（没有可撤销的操作、操作不可撤销等）
+    pub async fn undo_last(db: &GlobalDatabase) -> Result<(), String> {
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let operations = OperationLog::latest_batch(&connection).await?;
+        let Some(first) = operations.first() else {
+            return Err("没有可撤销的操作".to_string());
+        };
+        let (batch_id, kind) = (first.batch_id, first.kind);
+
+        // 整批中任一项不可撤销时直接拒绝，不做部分撤销
+        if operations.iter().any(|operation| operation.kind != kind || operation.dest_path.is_none()) {
+            return Err("无法撤销该操作".to_string());
+        }
+
+        match kind {
+            OperationKind::Rename => {
+                // 按执行顺序的倒序逐个改回原名称
+                for operation in &operations {
+                    let original_name = Self::original_name(&operation.source_path)?;
+                    let dest_path = operation.dest_path.as_deref().unwrap_or_default();
+                    Self::rename_path(db, dest_path, original_name, false).await?;
+                }
+            }
+            OperationKind::Move => {
+                // 按原目录分组，每个原目录一次移回
+                let mut groups: BTreeMap<PathBuf, Vec<(&str, &str)>> = BTreeMap::new();
+                for operation in &operations {
+                    let source_path = Path::new(&operation.source_path);
+                    let original_dir = source_path.parent()
+                        .ok_or_else(|| format!("无法获取父目录: {}", operation.source_path))?;
+                    let original_name = Self::original_name(&operation.source_path)?;
+                    let dest_path = operation.dest_path.as_deref().unwrap_or_default();
+                    groups.entry(original_dir.to_path_buf()).or_default().push((dest_path, original_name));
+                }
+
+                for (original_dir, entries) in groups {
+                    let dest_paths: Vec<String> = entries.iter().map(|(dest_path, _)| dest_path.to_string()).collect();
+                    Self::move_entries(
+                        db,
+                        &dest_paths,
+                        &original_dir.to_string_lossy(),
+                        ConflictPolicy::Error,
+                        None,
+                        None,
+                    )
+                    .await?;
+
+                    // 移动时因冲突被自动重命名的项，移回后恢复原名称
+                    for (dest_path, original_name) in entries {
+                        let moved_name = Path::new(dest_path).file_name().and_then(|n| n.to_str());
+                        if moved_name != Some(original_name) {
+                            let moved_back = original_dir.join(moved_name.unwrap_or_default());
+                            Self::rename_path(db, &moved_back.to_string_lossy(), original_name, false).await?;
+                        }
+                    }
+                }
+            }
+            OperationKind::Delete => return Err("无法撤销该操作".to_string()),
+        }
+
+        OperationLog::mark_undone(&connection, batch_id).await
+    }
+
+    /// 操作日志中原路径的文件名
+    fn original_name(source_path: &str) -> Result<&str, String> {
+        Path::new(source_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| format!("无法获取文件名: {}", source_path))
+    }
+
+    /// 重命名文件并保留原扩展名
+    ///
+    /// 将原文件的扩展名追加到 `new_base_name` 后再执行重命名，
+    /// 原文件没有扩展名（或是文件夹）时等同于普通重命名
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `old_path`: 原文件路径
+    /// - `new_base_name`: 新的文件名（不含扩展名）
+    ///
+    /// # 返回
+    /// - `Ok(())`: 操作成功
+    /// - `Err(String)`: 错误信息
+    pub async fn rename_keep_extension(
+        db: &GlobalDatabase,
+        old_path: &str,
+        new_base_name: &str,
+    ) -> Result<(), String> {
+        let source_path = Path::new(old_path);
 
-        // 如果目标路径已存在，返回错误
-        if new_path.exists() {
-            return Err(format!("目标路径已存在: {}", new_path.display()));
-        }
+        let extension = if source_path.is_dir() {
+            None
+        } else {
+            source_path.extension().and_then(|ext| ext.to_str())
+        };
 
-        // 重命名文件/文件夹
-        fs::rename(source_path, &new_path)
-            .map_err(|e| format!("重命名失败 {} -> {}: {}", old_path, new_path.display(), e))?;
+        let new_name = match extension {
+            Some(ext) if !new_base_name.trim().is_empty() => format!("{}.{}", new_base_name, ext),
+            _ => new_base_name.to_string(),
+        };
 
-        // 更新数据库中的路径
-        let connection = db
-            .get_connection()
-            .await
-            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
-        match connection {
-            DatabaseConnectionRef::Postgres(pool) => {
-                Self::update_file_path_postgres(&pool, old_path, &new_path_str).await
-            }
-            DatabaseConnectionRef::Sqlite(pool) => {
-                Self::update_file_path_sqlite(&pool, old_path, &new_path_str).await
-            }
-        }
+        Self::rename_file(db, old_path, &new_name).await
     }
 
     /// 删除文件或文件夹
     ///
-    /// 删除指定的文件/文件夹列表，支持递归删除文件夹
+    /// 删除指定的文件/文件夹列表，支持递归删除文件夹。
+    /// 受保护路径（系统目录、用户主目录等）及其上级目录会被拒绝删除
     ///
     /// # 参数
     /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（提供受保护路径列表）
     /// - `paths`: 要删除的文件/文件夹路径列表
     ///
     /// # 返回
     /// - `Ok(())`: 操作成功
     /// - `Err(String)`: 错误信息
-    pub async fn delete_files(db: &GlobalDatabase, paths: &[String]) -> Result<(), String> {
+    pub async fn delete_files(
+        db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
+        paths: &[String],
+    ) -> Result<(), String> {
+        // 任一路径受保护时整体拒绝，避免只删除了一部分
+        let protected_paths = global_config.get_protected_paths();
+        if let Some(path) = paths
+            .iter()
+            .find(|path| utils::is_protected_path(Path::new(path), &protected_paths))
+        {
+            return Err(format!("拒绝删除受保护路径: {}", path));
+        }
+
+        // 删除前记录哪些路径是文件夹，用于级联软删除其子文件记录
+        let folder_paths: Vec<String> = paths
+            .iter()
+            .filter(|path| Path::new(path).is_dir())
+            .cloned()
+            .collect();
+
         // 先删除文件系统中的文件
         for path in paths {
-            let target_path = Path::new(path);
+            Self::remove_path(path)?;
+        }
+
+        Self::record_deleted(db, paths, &folder_paths).await
+    }
 
-            // 检查路径是否存在
-            if !target_path.exists() {
-                return Err(format!("路径不存在: {}", path));
+    /// 逐个删除文件或文件夹，返回每个路径的结果
+    ///
+    /// 与 `delete_files` 不同，单个路径失败（不存在、被占用、受保护）不会中断其他路径，
+    /// 只对删除成功的路径执行数据库软删除
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（提供受保护路径列表）
+    /// - `paths`: 要删除的文件/文件夹路径列表
+    ///
+    /// # 返回
+    /// - `Ok(Vec<(String, Result<(), String>)>)`: 按输入顺序排列的每个路径及其删除结果
+    /// - `Err(String)`: 数据库错误
+    pub async fn delete_files_each(
+        db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
+        paths: &[String],
+    ) -> Result<Vec<(String, Result<(), String>)>, String> {
+        let protected_paths = global_config.get_protected_paths();
+        let mut results = Vec::with_capacity(paths.len());
+        let mut deleted = Vec::new();
+        let mut folder_paths = Vec::new();
+
+        for path in paths {
+            if utils::is_protected_path(Path::new(path), &protected_paths) {
+                results.push((path.clone(), Err(format!("拒绝删除受保护路径: {}", path))));
+                continue;
             }
 
-            // 删除文件或文件夹
-            if target_path.is_dir() {
-                // 递归删除目录
-                fs::remove_dir_all(target_path)
-                    .map_err(|e| format!("删除文件夹失败 {}: {}", path, e))?;
-            } else {
-                // 删除文件
-                fs::remove_file(target_path)
-                    .map_err(|e| format!("删除文件失败 {}: {}", path, e))?;
+            let is_dir = Path::new(path).is_dir();
+            let result = Self::remove_path(path);
+            if result.is_ok() {
+                if is_dir {
+                    folder_paths.push(path.clone());
+                }
+                deleted.push(path.clone());
             }
+            results.push((path.clone(), result));
+        }
+
+        if !deleted.is_empty() {
+            Self::record_deleted(db, &deleted, &folder_paths).await?;
         }
 
+        Ok(results)
+    }
+
+    /// 从文件系统中删除单个文件或文件夹（文件夹递归删除）
+    fn remove_path(path: &str) -> Result<(), String> {
+        let target_path = Path::new(path);
+
+        // 检查路径是否存在
+        if !target_path.exists() {
+            return Err(format!("路径不存在: {}", path));
+        }
+
+        // 删除文件或文件夹
+        if target_path.is_dir() {
+            // 递归删除目录
+            fs::remove_dir_all(utils::to_long_path(target_path))
+                .map_err(|e| format!("删除文件夹失败 {}: {}", path, e))
+        } else {
+            // 删除文件
+            fs::remove_file(utils::to_long_path(target_path))
+                .map_err(|e| format!("删除文件失败 {}: {}", path, e))
+        }
+    }
+
+    /// 在数据库中软删除已删除路径的文件记录，并记录操作日志
+    ///
+    /// `folder_paths` 为其中的文件夹路径，用于级联软删除其子文件记录
+    async fn record_deleted(
+        db: &GlobalDatabase,
+        paths: &[String],
+        folder_paths: &[String],
+    ) -> Result<(), String> {
         // 更新数据库：软删除文件记录（设置 deleted_at）
         let connection = db
             .get_connection()
             .await
             .map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
-        match connection {
+        match &connection {
             DatabaseConnectionRef::Postgres(pool) => {
-                Self::soft_delete_files_postgres(&pool, paths).await
+                Self::soft_delete_files_postgres(pool, paths, folder_paths).await?
             }
             DatabaseConnectionRef::Sqlite(pool) => {
-                Self::soft_delete_files_sqlite(&pool, paths).await
+                Self::soft_delete_files_sqlite(pool, paths, folder_paths).await?
             }
         }
+
+        // 永久删除不可撤销，记录下来使撤销时能给出明确提示
+        let entries: Vec<(String, Option<String>)> = paths.iter().map(|path| (path.clone(), None)).collect();
+        OperationBatch::new().record_all(&connection, OperationKind::Delete, &entries).await?;
+
+        Ok(())
     }
 
     /// PostgreSQL 实现：更新文件路径
@@ -660,6 +3358,63 @@ impl FileSystemService {
         Ok(())
     }
 
+    /// 生成批量更新路径用的 VALUES 列表，如 `($1, $2), ($3, $4)`
+    fn path_pairs_placeholders(count: usize, prefix: char) -> String {
+        (0..count)
+            .map(|i| format!("({}{}, {}{})", prefix, i * 2 + 1, prefix, i * 2 + 2))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// PostgreSQL 实现：用一条 UPDATE 批量更新文件路径
+    async fn update_file_paths_postgres(pool: &Pool<Postgres>, moves: &[(String, String)]) -> Result<(), String> {
+        let sql = format!(
+            r#"
+            UPDATE files AS f
+            SET current_path = m.new_path, updated_at = CURRENT_TIMESTAMP
+            FROM (VALUES {}) AS m(old_path, new_path)
+            WHERE f.current_path = m.old_path AND f.deleted_at IS NULL
+            "#,
+            Self::path_pairs_placeholders(moves.len(), '$')
+        );
+
+        let mut query = sqlx::query(&sql);
+        for (old_path, new_path) in moves {
+            query = query.bind(old_path).bind(new_path);
+        }
+        query
+            .execute(pool)
+            .await
+            .map_err(|e| format!("批量更新文件路径失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// SQLite 实现：用一条 UPDATE 批量更新文件路径
+    async fn update_file_paths_sqlite(pool: &Pool<Sqlite>, moves: &[(String, String)]) -> Result<(), String> {
+        let sql = format!(
+            r#"
+            WITH moves(old_path, new_path) AS (VALUES {})
+            UPDATE files
+            SET current_path = (SELECT new_path FROM moves WHERE moves.old_path = files.current_path),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE deleted_at IS NULL AND current_path IN (SELECT old_path FROM moves)
+            "#,
+            Self::path_pairs_placeholders(moves.len(), '?')
+        );
+
+        let mut query = sqlx::query(&sql);
+        for (old_path, new_path) in moves {
+            query = query.bind(old_path).bind(new_path);
+        }
+        query
+            .execute(pool)
+            .await
+            .map_err(|e| format!("批量更新文件路径失败: {}", e))?;
+
+        Ok(())
+    }
+
     /// SQLite 实现：更新文件路径
     async fn update_file_path_sqlite(
         pool: &Pool<Sqlite>,
@@ -682,47 +3437,401 @@ impl FileSystemService {
         Ok(())
     }
 
+    /// 生成文件夹的子路径前缀（文件夹路径 + 路径分隔符）
+    fn child_path_prefix(dir_path: &str) -> String {
+        let trimmed = dir_path.trim_end_matches(['/', '\\']);
+        format!("{}{}", trimmed, std::path::MAIN_SEPARATOR)
+    }
+
+    /// PostgreSQL 实现：批量更新文件夹下子文件的路径前缀
+    async fn update_child_paths_postgres(
+        pool: &Pool<Postgres>,
+        old_dir: &str,
+        new_dir: &str,
+    ) -> Result<(), String> {
+        let old_prefix = Self::child_path_prefix(old_dir);
+        let new_prefix = Self::child_path_prefix(new_dir);
+        let prefix_len = old_prefix.chars().count() as i32;
+
+        // 使用 substr 比较前缀，避免路径中的 % 和 _ 被当作 LIKE 通配符
+        sqlx::query(
+            r#"
+            UPDATE files
+            SET current_path = $1 || substr(current_path, $3 + 1),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE substr(current_path, 1, $3) = $2 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(&new_prefix)
+        .bind(&old_prefix)
+        .bind(prefix_len)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("更新子文件路径失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// SQLite 实现：批量更新文件夹下子文件的路径前缀
+    async fn update_child_paths_sqlite(
+        pool: &Pool<Sqlite>,
+        old_dir: &str,
+        new_dir: &str,
+    ) -> Result<(), String> {
+        let old_prefix = Self::child_path_prefix(old_dir);
+        let new_prefix = Self::child_path_prefix(new_dir);
+        let prefix_len = old_prefix.chars().count() as i32;
+
+        // 使用 substr 比较前缀，避免路径中的 % 和 _ 被当作 LIKE 通配符
+        sqlx::query(
+            r#"
+            UPDATE files
+            SET current_path = ?1 || substr(current_path, ?3 + 1),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE substr(current_path, 1, ?3) = ?2 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(&new_prefix)
+        .bind(&old_prefix)
+        .bind(prefix_len)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("更新子文件路径失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// PostgreSQL 实现：清除路径（及其子路径）下的所有文件记录
+    ///
+    /// 用于覆盖目标时释放 current_path，包括已软删除的记录，并重新统计受影响标签的使用次数
+    async fn purge_file_records_postgres(pool: &Pool<Postgres>, path: &str) -> Result<(), String> {
+        let prefix = Self::child_path_prefix(path);
+        let prefix_len = prefix.chars().count() as i32;
+
+        let tag_rows = sqlx::query(
+            r#"
+            SELECT DISTINCT ft.tag_id
+            FROM file_tags ft
+            INNER JOIN files f ON f.id = ft.file_id
+            WHERE f.current_path = $1 OR substr(f.current_path, 1, $3) = $2
+            "#,
+        )
+        .bind(path)
+        .bind(&prefix)
+        .bind(prefix_len)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查询文件标签失败: {}", e))?;
+
+        sqlx::query("DELETE FROM files WHERE current_path = $1 OR substr(current_path, 1, $3) = $2")
+            .bind(path)
+            .bind(&prefix)
+            .bind(prefix_len)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("清除文件记录失败: {}", e))?;
+
+        for row in tag_rows {
+            TagService::recount_usage_postgres(pool, row.get("tag_id")).await?;
+        }
+
+        Ok(())
+    }
+
+    /// SQLite 实现：清除路径（及其子路径）下的所有文件记录
+    ///
+    /// 用于覆盖目标时释放 current_path，包括已软删除的记录，并重新统计受影响标签的使用次数
+    async fn purge_file_records_sqlite(pool: &Pool<Sqlite>, path: &str) -> Result<(), String> {
+        let prefix = Self::child_path_prefix(path);
+        let prefix_len = prefix.chars().count() as i32;
+
+        let tag_rows = sqlx::query(
+            r#"
+            SELECT DISTINCT ft.tag_id
+            FROM file_tags ft
+            INNER JOIN files f ON f.id = ft.file_id
+            WHERE f.current_path = ?1 OR substr(f.current_path, 1, ?3) = ?2
+            "#,
+        )
+        .bind(path)
+        .bind(&prefix)
+        .bind(prefix_len)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查询文件标签失败: {}", e))?;
+
+        // SQLite 默认不启用外键约束，需要手动删除关联记录
+        sqlx::query(
+            r#"
+            DELETE FROM file_tags
+            WHERE file_id IN (
+                SELECT id FROM files WHERE current_path = ?1 OR substr(current_path, 1, ?3) = ?2
+            )
+            "#,
+        )
+        .bind(path)
+        .bind(&prefix)
+        .bind(prefix_len)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("清除文件标签失败: {}", e))?;
+
+        sqlx::query("DELETE FROM files WHERE current_path = ?1 OR substr(current_path, 1, ?3) = ?2")
+            .bind(path)
+            .bind(&prefix)
+            .bind(prefix_len)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("清除文件记录失败: {}", e))?;
+
+        for row in tag_rows {
+            TagService::recount_usage_sqlite(pool, row.get("tag_id")).await?;
+        }
+
+        Ok(())
+    }
+
+    /// PostgreSQL 实现：更新文件大小
+    async fn update_file_size_postgres(
+        pool: &Pool<Postgres>,
+        path: &str,
+        file_size: i64,
+    ) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            UPDATE files
+            SET file_size = $1, updated_at = CURRENT_TIMESTAMP
+            WHERE current_path = $2 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(file_size)
+        .bind(path)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("更新文件大小失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// SQLite 实现：更新文件大小
+    async fn update_file_size_sqlite(
+        pool: &Pool<Sqlite>,
+        path: &str,
+        file_size: i64,
+    ) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            UPDATE files
+            SET file_size = ?1, updated_at = CURRENT_TIMESTAMP
+            WHERE current_path = ?2 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(file_size)
+        .bind(path)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("更新文件大小失败: {}", e))?;
+
+        Ok(())
+    }
+
     /// PostgreSQL 实现：软删除文件记录
+    ///
+    /// 文件夹路径会级联软删除其下所有子文件记录，并重新统计受影响标签的使用次数
     async fn soft_delete_files_postgres(
         pool: &Pool<Postgres>,
         paths: &[String],
+        folder_paths: &[String],
     ) -> Result<(), String> {
+        let mut affected_tag_ids: Vec<i32> = Vec::new();
+
         for path in paths {
+            let is_folder = folder_paths.contains(path);
+            let prefix = Self::child_path_prefix(path);
+            let prefix_len = prefix.chars().count() as i32;
+
+            // 记录受影响的标签，软删除后需要重新统计使用次数
+            let tag_rows = sqlx::query(
+                r#"
+                SELECT DISTINCT ft.tag_id
+                FROM file_tags ft
+                INNER JOIN files f ON f.id = ft.file_id
+                WHERE f.deleted_at IS NULL
+                AND (f.current_path = $1 OR ($2 AND substr(f.current_path, 1, $4) = $3))
+                "#,
+            )
+            .bind(path)
+            .bind(is_folder)
+            .bind(&prefix)
+            .bind(prefix_len)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("查询文件标签失败: {}", e))?;
+
+            for row in tag_rows {
+                let tag_id: i32 = row.get("tag_id");
+                if !affected_tag_ids.contains(&tag_id) {
+                    affected_tag_ids.push(tag_id);
+                }
+            }
+
             sqlx::query(
                 r#"
                 UPDATE files
                 SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
-                WHERE current_path = $1 AND deleted_at IS NULL
+                WHERE deleted_at IS NULL
+                AND (current_path = $1 OR ($2 AND substr(current_path, 1, $4) = $3))
                 "#,
             )
             .bind(path)
+            .bind(is_folder)
+            .bind(&prefix)
+            .bind(prefix_len)
             .execute(pool)
             .await
             .map_err(|e| format!("软删除文件记录失败: {}", e))?;
         }
 
+        for tag_id in affected_tag_ids {
+            TagService::recount_usage_postgres(pool, tag_id).await?;
+        }
+
         Ok(())
     }
 
     /// SQLite 实现：软删除文件记录
+    ///
+    /// 文件夹路径会级联软删除其下所有子文件记录，并重新统计受影响标签的使用次数
     async fn soft_delete_files_sqlite(
         pool: &Pool<Sqlite>,
         paths: &[String],
+        folder_paths: &[String],
     ) -> Result<(), String> {
+        let mut affected_tag_ids: Vec<i32> = Vec::new();
+
         for path in paths {
+            let is_folder = folder_paths.contains(path);
+            let prefix = Self::child_path_prefix(path);
+            let prefix_len = prefix.chars().count() as i32;
+
+            // 记录受影响的标签，软删除后需要重新统计使用次数
+            let tag_rows = sqlx::query(
+                r#"
+                SELECT DISTINCT ft.tag_id
+                FROM file_tags ft
+                INNER JOIN files f ON f.id = ft.file_id
+                WHERE f.deleted_at IS NULL
+                AND (f.current_path = ?1 OR (?2 AND substr(f.current_path, 1, ?4) = ?3))
+                "#,
+            )
+            .bind(path)
+            .bind(is_folder)
+            .bind(&prefix)
+            .bind(prefix_len)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("查询文件标签失败: {}", e))?;
+
+            for row in tag_rows {
+                let tag_id: i32 = row.get("tag_id");
+                if !affected_tag_ids.contains(&tag_id) {
+                    affected_tag_ids.push(tag_id);
+                }
+            }
+
             sqlx::query(
                 r#"
                 UPDATE files
                 SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
-                WHERE current_path = ?1 AND deleted_at IS NULL
+                WHERE deleted_at IS NULL
+                AND (current_path = ?1 OR (?2 AND substr(current_path, 1, ?4) = ?3))
                 "#,
             )
             .bind(path)
+            .bind(is_folder)
+            .bind(&prefix)
+            .bind(prefix_len)
             .execute(pool)
             .await
             .map_err(|e| format!("软删除文件记录失败: {}", e))?;
         }
 
+        for tag_id in affected_tag_ids {
+            TagService::recount_usage_sqlite(pool, tag_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// PostgreSQL 实现：复制文件夹下所有子文件的标签
+    ///
+    /// 查找源文件夹下有标签的子文件记录，按相对路径为复制后的对应文件复制标签
+    async fn copy_child_file_tags_postgres(
+        pool: &Pool<Postgres>,
+        source_dir: &str,
+        dest_dir: &str,
+    ) -> Result<(), String> {
+        let source_prefix = Self::child_path_prefix(source_dir);
+        let dest_prefix = Self::child_path_prefix(dest_dir);
+        let prefix_len = source_prefix.chars().count() as i32;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT f.current_path
+            FROM files f
+            INNER JOIN file_tags ft ON ft.file_id = f.id
+            WHERE f.deleted_at IS NULL AND substr(f.current_path, 1, $2) = $1
+            "#,
+        )
+        .bind(&source_prefix)
+        .bind(prefix_len)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查询子文件记录失败: {}", e))?;
+
+        for row in rows {
+            let child_path: String = row.get("current_path");
+            let relative: String = child_path.chars().skip(source_prefix.chars().count()).collect();
+            let dest_child_path = format!("{}{}", dest_prefix, relative);
+            Self::copy_file_tags_postgres(pool, &child_path, &dest_child_path).await?;
+        }
+
+        Ok(())
+    }
+
+    /// SQLite 实现：复制文件夹下所有子文件的标签
+    ///
+    /// 查找源文件夹下有标签的子文件记录，按相对路径为复制后的对应文件复制标签
+    async fn copy_child_file_tags_sqlite(
+        pool: &Pool<Sqlite>,
+        source_dir: &str,
+        dest_dir: &str,
+    ) -> Result<(), String> {
+        let source_prefix = Self::child_path_prefix(source_dir);
+        let dest_prefix = Self::child_path_prefix(dest_dir);
+        let prefix_len = source_prefix.chars().count() as i32;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT f.current_path
+            FROM files f
+            INNER JOIN file_tags ft ON ft.file_id = f.id
+            WHERE f.deleted_at IS NULL AND substr(f.current_path, 1, ?2) = ?1
+            "#,
+        )
+        .bind(&source_prefix)
+        .bind(prefix_len)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查询子文件记录失败: {}", e))?;
+
+        for row in rows {
+            let child_path: String = row.get("current_path");
+            let relative: String = child_path.chars().skip(source_prefix.chars().count()).collect();
+            let dest_child_path = format!("{}{}", dest_prefix, relative);
+            Self::copy_file_tags_sqlite(pool, &child_path, &dest_child_path).await?;
+        }
+
         Ok(())
     }
 
@@ -814,7 +3923,7 @@ impl FileSystemService {
         let dest_file_id: i32 = dest_file_row.get("id");
 
         // 复制所有标签关联
-        for tag_row in tag_rows {
+        for tag_row in &tag_rows {
             let tag_id: i32 = tag_row.get("tag_id");
             sqlx::query(
                 r#"
@@ -830,6 +3939,11 @@ impl FileSystemService {
             .map_err(|e| format!("复制标签关联失败: {}", e))?;
         }
 
+        // 新文件记录增加了标签引用，重新统计使用次数
+        for tag_row in &tag_rows {
+            TagService::recount_usage_postgres(pool, tag_row.get("tag_id")).await?;
+        }
+
         Ok(())
     }
 
@@ -952,7 +4066,7 @@ impl FileSystemService {
         };
 
         // 复制所有标签关联
-        for tag_row in tag_rows {
+        for tag_row in &tag_rows {
             let tag_id: i32 = tag_row.get("tag_id");
             sqlx::query(
                 r#"
@@ -967,6 +4081,11 @@ impl FileSystemService {
             .map_err(|e| format!("复制标签关联失败: {}", e))?;
         }
 
+        // 新文件记录增加了标签引用，重新统计使用次数
+        for tag_row in &tag_rows {
+            TagService::recount_usage_sqlite(pool, tag_row.get("tag_id")).await?;
+        }
+
         Ok(())
     }
 
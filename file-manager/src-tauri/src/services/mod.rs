@@ -2,9 +2,15 @@
 //!
 //! 包含所有业务逻辑的实现
 
+pub mod database;
 pub mod file_system;
+pub mod operation_log;
 pub mod tag;
 
+#[cfg(test)]
+mod tests;
+
+pub use database::DatabaseService;
 pub use file_system::FileSystemService;
 pub use tag::TagService;
 
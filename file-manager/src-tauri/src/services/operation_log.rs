@@ -0,0 +1,250 @@
+//! 文件操作日志
+//!
+//! 记录重命名、移动和永久删除操作，供撤销最近一次操作使用。
+//! 同一次调用产生的记录属于同一批次，撤销时整批撤销
+
+use crate::database::{retry_sqlite_busy, DatabaseConnectionRef};
+use crate::models::file_system::OperationKind;
+use sqlx::Row;
+
+/// 批量记录操作时每条 INSERT 最多包含的记录数
+const LOG_INSERT_BATCH_SIZE: usize = 500;
+
+/// 操作日志中的一条记录
+#[derive(Debug, Clone)]
+pub struct OperationRecord {
+    /// 记录ID
+    pub id: i32,
+    /// 所属批次（同一次调用产生的记录共享批次号，即该批第一条记录的ID）
+    pub batch_id: i32,
+    /// 操作类型
+    pub kind: OperationKind,
+    /// 操作前的路径
+    pub source_path: String,
+    /// 操作后的路径（永久删除时为 None）
+    pub dest_path: Option<String>,
+}
+
+/// 一次调用内的操作批次
+///
+/// 批量移动、整理目录等一次调用会产生多条记录，第一条记录的ID作为整批的批次号，
+/// 撤销时整批一起撤销
+#[derive(Debug, Default)]
+pub struct OperationBatch {
+    id: Option<i32>,
+}
+
+impl OperationBatch {
+    /// 创建尚未记录任何操作的批次
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 在本批次中批量记录同类操作
+    ///
+    /// 批次的第一条记录单独插入以取得批次号，其余记录每 `LOG_INSERT_BATCH_SIZE` 条合并为一条 INSERT
+    ///
+    /// # 参数
+    /// - `connection`: 数据库连接
+    /// - `kind`: 操作类型
+    /// - `entries`: (操作前的路径, 操作后的路径) 列表
+    ///
+    /// # 返回
+    /// - `Ok(usize)`: 执行的 INSERT 语句数
+    /// - `Err(String)`: 错误信息
+    pub async fn record_all(
+        &mut self,
+        connection: &DatabaseConnectionRef,
+        kind: OperationKind,
+        entries: &[(String, Option<String>)],
+    ) -> Result<usize, String> {
+        let mut statements = 0;
+        let mut rest = entries;
+
+        let batch_id = match self.id {
+            Some(id) => id,
+            None => {
+                let Some(((source_path, dest_path), tail)) = entries.split_first() else {
+                    return Ok(0);
+                };
+                let id = OperationLog::record(connection, kind, None, source_path, dest_path.as_deref()).await?;
+                self.id = Some(id);
+                rest = tail;
+                statements += 1;
+                id
+            }
+        };
+
+        for chunk in rest.chunks(LOG_INSERT_BATCH_SIZE) {
+            OperationLog::record_many(connection, kind, batch_id, chunk).await?;
+            statements += 1;
+        }
+
+        Ok(statements)
+    }
+}
+
+/// 文件操作日志
+pub struct OperationLog;
+
+impl OperationLog {
+    /// 记录一次文件操作
+    ///
+    /// # 参数
+    /// - `connection`: 数据库连接
+    /// - `kind`: 操作类型
+    /// - `batch_id`: 所属批次号，为 None 时该记录自成一批
+    /// - `source_path`: 操作前的路径
+    /// - `dest_path`: 操作后的路径（永久删除时为 None）
+    ///
+    /// # 返回
+    /// - `Ok(i32)`: 新记录的ID
+    /// - `Err(String)`: 错误信息
+    pub async fn record(
+        connection: &DatabaseConnectionRef,
+        kind: OperationKind,
+        batch_id: Option<i32>,
+        source_path: &str,
+        dest_path: Option<&str>,
+    ) -> Result<i32, String> {
+        match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                sqlx::query_scalar::<_, i32>(
+                    "INSERT INTO file_operations (operation, batch_id, source_path, dest_path) VALUES ($1, $2, $3, $4) RETURNING id",
+                )
+                .bind(kind.as_str())
+                .bind(batch_id)
+                .bind(source_path)
+                .bind(dest_path)
+                .fetch_one(pool)
+                .await
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                retry_sqlite_busy(|| sqlx::query_scalar::<_, i32>(
+                    "INSERT INTO file_operations (operation, batch_id, source_path, dest_path) VALUES (?1, ?2, ?3, ?4) RETURNING id",
+                )
+                .bind(kind.as_str())
+                .bind(batch_id)
+                .bind(source_path)
+                .bind(dest_path)
+                .fetch_one(pool))
+                .await
+            }
+        }
+        .map_err(|e| format!("记录文件操作失败: {}", e))
+    }
+
+    /// 用一条 INSERT 记录同一批次的多条操作
+    async fn record_many(
+        connection: &DatabaseConnectionRef,
+        kind: OperationKind,
+        batch_id: i32,
+        entries: &[(String, Option<String>)],
+    ) -> Result<(), String> {
+        let prefix = match connection {
+            DatabaseConnectionRef::Postgres(_) => '$',
+            DatabaseConnectionRef::Sqlite(_) => '?',
+        };
+        // 操作类型和批次号在所有行中共用参数 1、2
+        let values = (0..entries.len())
+            .map(|i| format!("({p}1, {p}2, {p}{}, {p}{})", i * 2 + 3, i * 2 + 4, p = prefix))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "INSERT INTO file_operations (operation, batch_id, source_path, dest_path) VALUES {}",
+            values
+        );
+
+        match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                let mut query = sqlx::query(&sql).bind(kind.as_str()).bind(batch_id);
+                for (source_path, dest_path) in entries {
+                    query = query.bind(source_path).bind(dest_path);
+                }
+                query.execute(pool).await.map(|_| ())
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                retry_sqlite_busy(|| {
+                    let mut query = sqlx::query(&sql).bind(kind.as_str()).bind(batch_id);
+                    for (source_path, dest_path) in entries {
+                        query = query.bind(source_path).bind(dest_path);
+                    }
+                    query.execute(pool)
+                })
+                .await
+                .map(|_| ())
+            }
+        }
+        .map_err(|e| format!("记录文件操作失败: {}", e))
+    }
+
+    /// 获取最近一批未撤销的操作
+    ///
+    /// 批次号为空的旧记录（以及单条操作）各自视为一批
+    ///
+    /// # 返回
+    /// - `Ok(Vec<OperationRecord>)`: 最近一批操作（按执行顺序倒序），没有可撤销的操作时为空
+    /// - `Err(String)`: 错误信息
+    pub async fn latest_batch(connection: &DatabaseConnectionRef) -> Result<Vec<OperationRecord>, String> {
+        let query = r#"
+            SELECT id, COALESCE(batch_id, id) AS batch_id, operation, source_path, dest_path
+            FROM file_operations
+            WHERE undone_at IS NULL
+              AND COALESCE(batch_id, id) = (
+                  SELECT COALESCE(batch_id, id)
+                  FROM file_operations
+                  WHERE undone_at IS NULL
+                  ORDER BY id DESC
+                  LIMIT 1
+              )
+            ORDER BY id DESC
+        "#;
+
+        let rows = match connection {
+            DatabaseConnectionRef::Postgres(pool) => sqlx::query(query).fetch_all(pool).await,
+            DatabaseConnectionRef::Sqlite(pool) => sqlx::query(query).fetch_all(pool).await,
+        }
+        .map_err(|e| format!("查询文件操作记录失败: {}", e))?;
+
+        rows.iter()
+            .map(|row| {
+                let operation: String = row.get("operation");
+                let kind = OperationKind::from_name(&operation)
+                    .ok_or_else(|| format!("未知的操作类型: {}", operation))?;
+
+                Ok(OperationRecord {
+                    id: row.get("id"),
+                    batch_id: row.get("batch_id"),
+                    kind,
+                    source_path: row.get("source_path"),
+                    dest_path: row.get("dest_path"),
+                })
+            })
+            .collect()
+    }
+
+    /// 将整批操作标记为已撤销
+    pub async fn mark_undone(connection: &DatabaseConnectionRef, batch_id: i32) -> Result<(), String> {
+        match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                sqlx::query(
+                    "UPDATE file_operations SET undone_at = CURRENT_TIMESTAMP WHERE COALESCE(batch_id, id) = $1 AND undone_at IS NULL",
+                )
+                .bind(batch_id)
+                .execute(pool)
+                .await
+                .map(|_| ())
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                retry_sqlite_busy(|| sqlx::query(
+                    "UPDATE file_operations SET undone_at = CURRENT_TIMESTAMP WHERE COALESCE(batch_id, id) = ?1 AND undone_at IS NULL",
+                )
+                .bind(batch_id)
+                .execute(pool))
+                .await
+                .map(|_| ())
+            }
+        }
+        .map_err(|e| format!("更新文件操作记录失败: {}", e))
+    }
+}
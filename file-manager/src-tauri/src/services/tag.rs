@@ -2,10 +2,28 @@
 //!
 //! 提供标签相关的业务逻辑实现
 
-use crate::database::{DatabaseConnectionRef, GlobalDatabase};
-use crate::models::tag::Tag;
+use crate::config::global::DisplayOptions;
+use crate::config::GlobalConfigManager;
+use crate::database::{log_slow, retry_sqlite_busy, DatabaseConnectionRef, GlobalDatabase};
+use crate::models::tag::{BulkTagResult, InvalidTagName, Tag, TagSearchMode, TagStats};
+use crate::services::FileSystemService;
 use crate::utils;
-use sqlx::{Pool, Postgres, Sqlite, Row};
+use sqlx::{Executor, Pool, Postgres, Sqlite, Row, Transaction};
+use std::collections::HashSet;
+
+/// 标签名称的最大字符数
+const MAX_TAG_NAME_LENGTH: usize = 100;
+
+/// 按扩展名推荐标签时返回的最大数量
+const MAX_TAG_SUGGESTIONS: i64 = 10;
+
+/// 按标签搜索时查询到的文件记录
+struct RegisteredRecord {
+    current_path: String,
+    file_size: i64,
+    registered_at: Option<String>,
+    db_updated_at: Option<String>,
+}
 
 /// 标签服务
 pub struct TagService;
@@ -15,9 +33,10 @@ impl TagService {
     ///
     /// # 参数
     /// - `db`: 全局数据库实例
-    /// - `limit`: 返回的标签数量限制，默认为 10
-    /// - `mode`: 排序模式：
-    ///   - "most_used"：按使用次数降序排列（默认）
+    /// - `global_config`: 全局配置管理器（提供查询默认值）
+    /// - `limit`: 返回的标签数量限制，默认使用配置中的 tag_list_limit（10）
+    /// - `mode`: 排序模式，默认使用配置中的 tag_list_mode（"most_used"）：
+    ///   - "most_used"：按使用次数降序排列
     ///   - "recent_used"：按更新时间降序排列
     ///
     /// # 返回
@@ -25,6 +44,7 @@ impl TagService {
     /// - `Err(String)`: 错误信息
     pub async fn get_tag_list(
         db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
         limit: Option<i32>,
         mode: Option<String>,
     ) -> Result<Vec<Tag>, String> {
@@ -33,67 +53,147 @@ impl TagService {
             .await
             .map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
-        let limit = limit.unwrap_or(10);
-        let mode = mode.unwrap_or_else(|| "most_used".to_string());
-
-        match connection {
-            DatabaseConnectionRef::Postgres(pool) => {
-                Self::get_tag_list_postgres(&pool, limit, &mode).await
-            }
-            DatabaseConnectionRef::Sqlite(pool) => {
-                Self::get_tag_list_sqlite(&pool, limit, &mode).await
+        let defaults = global_config.get_query_defaults();
+        let limit = limit.unwrap_or(defaults.tag_list_limit);
+        let mode = mode.unwrap_or(defaults.tag_list_mode);
+
+        log_slow("TagService::get_tag_list", connection.kind(), global_config.get_slow_query_threshold(), async {
+            match connection {
+                DatabaseConnectionRef::Postgres(pool) => {
+                    Self::get_tag_list_postgres(&pool, limit, &mode).await
+                }
+                DatabaseConnectionRef::Sqlite(pool) => {
+                    Self::get_tag_list_sqlite(&pool, limit, &mode).await
+                }
             }
-        }
+        })
+        .await
     }
 
     /// 搜索标签
     ///
-    /// 根据关键词搜索包含该文字的标签名称（模糊匹配）
+    /// 根据关键词搜索标签名称，支持包含匹配和前缀匹配。
+    /// 两种数据库的大小写行为保持一致：PostgreSQL 使用 `LIKE`/`ILIKE`，SQLite 使用 `GLOB`/`LIKE`。
+    ///
+    /// 性能说明：标签数量很多时建议使用前缀模式。PostgreSQL 上区分大小写的前缀匹配可以使用
+    /// `idx_tags_name_pattern` 索引；包含模式需要启用 `pg_trgm` 扩展才能使用 trigram 索引加速。
     ///
     /// # 参数
     /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（提供查询默认值）
     /// - `keyword`: 搜索关键词
-    /// - `limit`: 返回的标签数量限制，默认为 50
+    /// - `limit`: 返回的标签数量限制，默认使用配置中的 search_tags_limit（50）
+    /// - `case_sensitive`: 是否区分大小写，默认为 false
+    /// - `search_mode`: 搜索模式，默认为包含匹配
     ///
     /// # 返回
     /// - `Ok(Vec<Tag>)`: 匹配的标签列表
     /// - `Err(String)`: 错误信息
     pub async fn search_tags(
         db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
         keyword: String,
         limit: Option<i32>,
+        case_sensitive: Option<bool>,
+        search_mode: Option<TagSearchMode>,
     ) -> Result<Vec<Tag>, String> {
         let connection = db
             .get_connection()
             .await
             .map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
-        let limit = limit.unwrap_or(10);
+        let limit = limit.unwrap_or(global_config.get_query_defaults().search_tags_limit);
+        let case_sensitive = case_sensitive.unwrap_or(false);
+        let search_mode = search_mode.unwrap_or(TagSearchMode::Contains);
+
+        log_slow("TagService::search_tags", connection.kind(), global_config.get_slow_query_threshold(), async {
+            match connection {
+                DatabaseConnectionRef::Postgres(pool) => {
+                    Self::search_tags_postgres(&pool, &keyword, limit, case_sensitive, search_mode).await
+                }
+                DatabaseConnectionRef::Sqlite(pool) => {
+                    Self::search_tags_sqlite(&pool, &keyword, limit, case_sensitive, search_mode).await
+                }
+            }
+        })
+        .await
+    }
+
+    /// 创建新标签
+    ///
+    /// 配置了默认颜色列表时，按当前标签数量循环选取背景颜色，字体颜色按背景深浅自动选择。
+    /// `restore_if_deleted` 为 true 且存在同名的已删除标签时，恢复该标签（保留原 ID 和颜色）而不是新建
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（提供默认颜色列表）
+    /// - `name`: 标签名称
+    /// - `restore_if_deleted`: 是否恢复同名的已删除标签
+    ///
+    /// # 返回
+    /// - `Ok(Tag)`: 创建或恢复的标签
+    /// - `Err(String)`: 错误信息
+    pub async fn create_tag(
+        db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
+        name: String,
+        restore_if_deleted: bool,
+    ) -> Result<Tag, String> {
+        let trimmed_name = Self::validate_tag_name(&name)?;
+        let palette = global_config.get_default_tag_colors();
+
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
         match connection {
             DatabaseConnectionRef::Postgres(pool) => {
-                Self::search_tags_postgres(&pool, &keyword, limit).await
+                Self::create_tag_postgres(&pool, trimmed_name, &palette, restore_if_deleted).await
             }
             DatabaseConnectionRef::Sqlite(pool) => {
-                Self::search_tags_sqlite(&pool, &keyword, limit).await
+                Self::create_tag_sqlite(&pool, trimmed_name, &palette, restore_if_deleted).await
             }
         }
     }
 
-    /// 创建新标签
+    /// 批量创建标签
+    ///
+    /// 输入名称去除首尾空白并去重后，在一个事务中创建尚不存在的标签。
+    /// 与 `create_tag` 一致：存在同名的已删除标签时恢复该标签，新标签按默认颜色列表选取颜色。
+    /// 未通过校验的名称和已存在的名称分别记录在结果中，不影响其它名称的创建
     ///
     /// # 参数
     /// - `db`: 全局数据库实例
-    /// - `name`: 标签名称
+    /// - `global_config`: 全局配置管理器（提供默认颜色列表）
+    /// - `names`: 标签名称列表
     ///
     /// # 返回
-    /// - `Ok(Tag)`: 创建成功的标签
-    /// - `Err(String)`: 错误信息
-    pub async fn create_tag(db: &GlobalDatabase, name: String) -> Result<Tag, String> {
-        let trimmed_name = name.trim();
-        if trimmed_name.is_empty() {
-            return Err("标签名称不能为空".to_string());
+    /// - `Ok(BulkTagResult)`: 创建、跳过和校验失败的名称
+    /// - `Err(String)`: 数据库错误（事务回滚，不创建任何标签）
+    pub async fn create_tags_bulk(
+        db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
+        names: Vec<String>,
+    ) -> Result<BulkTagResult, String> {
+        let mut result = BulkTagResult::default();
+        let mut seen = HashSet::new();
+        let mut valid_names = Vec::new();
+        for name in &names {
+            match Self::validate_tag_name(name) {
+                Ok(trimmed_name) => {
+                    if seen.insert(trimmed_name) {
+                        valid_names.push(trimmed_name);
+                    }
+                }
+                Err(reason) => result.invalid.push(InvalidTagName { name: name.clone(), reason }),
+            }
+        }
+
+        if valid_names.is_empty() {
+            return Ok(result);
         }
+        let palette = global_config.get_default_tag_colors();
 
         let connection = db
             .get_connection()
@@ -102,12 +202,104 @@ impl TagService {
 
         match connection {
             DatabaseConnectionRef::Postgres(pool) => {
-                Self::create_tag_postgres(&pool, trimmed_name).await
+                Self::create_tags_bulk_postgres(&pool, &valid_names, &palette, &mut result).await?
             }
             DatabaseConnectionRef::Sqlite(pool) => {
-                Self::create_tag_sqlite(&pool, trimmed_name).await
+                Self::create_tags_bulk_sqlite(&pool, &valid_names, &palette, &mut result).await?
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// PostgreSQL 实现：在事务中批量创建标签
+    async fn create_tags_bulk_postgres(
+        pool: &Pool<Postgres>,
+        names: &[&str],
+        palette: &[String],
+        result: &mut BulkTagResult,
+    ) -> Result<(), String> {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("开启事务失败: {}", e))?;
+
+        for &name in names {
+            let exists_row = sqlx::query("SELECT 1 FROM tags WHERE name = $1 AND deleted_at IS NULL")
+                .bind(name)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| format!("检查标签是否存在失败: {}", e))?;
+
+            if exists_row.is_some() {
+                result.skipped.push(name.to_string());
+                continue;
+            }
+
+            // 与 create_tag 一致：恢复同名的已删除标签，而不是再插入一条同名记录
+            let restored = Self::restore_deleted_tag_postgres(&mut *tx, name)
+                .await
+                .map_err(|e| format!("恢复已删除标签失败 {}: {}", name, e))?;
+            let tag = match restored {
+                Some(tag) => tag,
+                None => {
+                    let colors = Self::default_colors_postgres(&mut *tx, palette).await?;
+                    Self::insert_tag_postgres(&mut *tx, name, colors.as_ref())
+                        .await
+                        .map_err(|e| format!("创建标签失败 {}: {}", name, e))?
+                }
+            };
+            result.created.push(tag);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("提交事务失败: {}", e))
+    }
+
+    /// SQLite 实现：在事务中批量创建标签
+    async fn create_tags_bulk_sqlite(
+        pool: &Pool<Sqlite>,
+        names: &[&str],
+        palette: &[String],
+        result: &mut BulkTagResult,
+    ) -> Result<(), String> {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("开启事务失败: {}", e))?;
+
+        for &name in names {
+            let exists_row = sqlx::query("SELECT 1 FROM tags WHERE name = ?1 AND deleted_at IS NULL")
+                .bind(name)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| format!("检查标签是否存在失败: {}", e))?;
+
+            if exists_row.is_some() {
+                result.skipped.push(name.to_string());
+                continue;
             }
+
+            // 与 create_tag 一致：恢复同名的已删除标签，而不是再插入一条同名记录
+            let restored = Self::restore_deleted_tag_sqlite(&mut *tx, name)
+                .await
+                .map_err(|e| format!("恢复已删除标签失败 {}: {}", name, e))?;
+            let tag = match restored {
+                Some(tag) => tag,
+                None => {
+                    let colors = Self::default_colors_sqlite(&mut *tx, palette).await?;
+                    Self::insert_tag_sqlite(&mut *tx, name, colors.as_ref())
+                        .await
+                        .map_err(|e| format!("创建标签失败 {}: {}", name, e))?
+                }
+            };
+            result.created.push(tag);
         }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("提交事务失败: {}", e))
     }
 
     /// PostgreSQL 实现：获取标签列表
@@ -219,7 +411,10 @@ impl TagService {
         pool: &Pool<Postgres>,
         keyword: &str,
         limit: i32,
+        case_sensitive: bool,
+        search_mode: TagSearchMode,
     ) -> Result<Vec<Tag>, String> {
+        let operator = if case_sensitive { "LIKE" } else { "ILIKE" };
         let query = format!(
             r#"
             SELECT
@@ -233,13 +428,17 @@ impl TagService {
                 TO_CHAR(updated_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as updated_at
             FROM tags
             WHERE deleted_at IS NULL
-            AND name ILIKE $1
+            AND name {} $1 ESCAPE '\'
             ORDER BY usage_count DESC, id ASC
             LIMIT $2
-            "#
+            "#,
+            operator
         );
 
-        let search_pattern = format!("%{}%", keyword);
+        let search_pattern = match search_mode {
+            TagSearchMode::Contains => format!("%{}%", Self::escape_like(keyword)),
+            TagSearchMode::Prefix => format!("{}%", Self::escape_like(keyword)),
+        };
         let rows = sqlx::query(&query)
             .bind(&search_pattern)
             .bind(limit)
@@ -269,7 +468,21 @@ impl TagService {
         pool: &Pool<Sqlite>,
         keyword: &str,
         limit: i32,
+        case_sensitive: bool,
+        search_mode: TagSearchMode,
     ) -> Result<Vec<Tag>, String> {
+        let leading = match search_mode {
+            TagSearchMode::Contains => true,
+            TagSearchMode::Prefix => false,
+        };
+        // SQLite 的 LIKE 对 ASCII 字符不区分大小写，区分大小写时改用 GLOB
+        let (condition, search_pattern) = if case_sensitive {
+            let wildcard = if leading { "*" } else { "" };
+            ("name GLOB ?1", format!("{}{}*", wildcard, Self::escape_glob(keyword)))
+        } else {
+            let wildcard = if leading { "%" } else { "" };
+            ("name LIKE ?1 ESCAPE '\\'", format!("{}{}%", wildcard, Self::escape_like(keyword)))
+        };
         let query = format!(
             r#"
             SELECT
@@ -283,13 +496,13 @@ impl TagService {
                 datetime(updated_at) as updated_at
             FROM tags
             WHERE deleted_at IS NULL
-            AND name LIKE ?1
+            AND {}
             ORDER BY usage_count DESC, id ASC
             LIMIT ?2
-            "#
+            "#,
+            condition
         );
 
-        let search_pattern = format!("%{}%", keyword);
         let rows = sqlx::query(&query)
             .bind(&search_pattern)
             .bind(limit)
@@ -314,8 +527,79 @@ impl TagService {
         Ok(tags)
     }
 
+    /// 校验标签名称
+    ///
+    /// 去除首尾空白后不能为空、不能超过 `MAX_TAG_NAME_LENGTH` 个字符、不能包含换行等控制字符
+    ///
+    /// # 返回
+    /// - `Ok(&str)`: 去除首尾空白后的名称
+    /// - `Err(String)`: 错误信息
+    pub(crate) fn validate_tag_name(name: &str) -> Result<&str, String> {
+        let trimmed_name = name.trim();
+        if trimmed_name.is_empty() {
+            return Err("标签名称不能为空".to_string());
+        }
+        if trimmed_name.chars().count() > MAX_TAG_NAME_LENGTH {
+            return Err(format!("标签名称过长，最多 {} 个字符", MAX_TAG_NAME_LENGTH));
+        }
+        if trimmed_name.chars().any(char::is_control) {
+            return Err("标签名称包含非法字符".to_string());
+        }
+        Ok(trimmed_name)
+    }
+
+    /// 转义 LIKE 通配符（`%`、`_`）及转义符 `\`，配合 `ESCAPE '\'` 使关键词按字面匹配
+    pub(crate) fn escape_like(keyword: &str) -> String {
+        let mut escaped = String::with_capacity(keyword.len());
+        for c in keyword.chars() {
+            if matches!(c, '\\' | '%' | '_') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    /// 转义 GLOB 通配符（`*`、`?`、`[`），使关键词按字面匹配
+    fn escape_glob(keyword: &str) -> String {
+        let mut escaped = String::with_capacity(keyword.len());
+        for c in keyword.chars() {
+            match c {
+                '*' | '?' | '[' => {
+                    escaped.push('[');
+                    escaped.push(c);
+                    escaped.push(']');
+                }
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    /// 从默认颜色列表中选取新标签的颜色
+    ///
+    /// # 参数
+    /// - `palette`: 默认颜色列表
+    /// - `tag_count`: 当前标签数量
+    ///
+    /// # 返回
+    /// (背景颜色, 字体颜色)，颜色列表为空时返回 None
+    fn palette_colors(palette: &[String], tag_count: i64) -> Option<(String, String)> {
+        if palette.is_empty() {
+            return None;
+        }
+        let color = palette[tag_count.max(0) as usize % palette.len()].clone();
+        let font_color = utils::contrast_font_color(&color).unwrap_or_else(|| "#000000".to_string());
+        Some((color, font_color))
+    }
+
     /// PostgreSQL 实现：创建新标签
-    async fn create_tag_postgres(pool: &Pool<Postgres>, name: &str) -> Result<Tag, String> {
+    async fn create_tag_postgres(
+        pool: &Pool<Postgres>,
+        name: &str,
+        palette: &[String],
+        restore_if_deleted: bool,
+    ) -> Result<Tag, String> {
         // 检查是否已存在同名标签
         let exists_row = sqlx::query(
             r#"
@@ -333,11 +617,116 @@ impl TagService {
             return Err(format!("标签 \"{}\" 已存在", name));
         }
 
-        // 使用数据库默认值插入
-        let row = sqlx::query(
+        if restore_if_deleted {
+            let restored = Self::restore_deleted_tag_postgres(pool, name)
+                .await
+                .map_err(|e| format!("恢复已删除标签失败: {}", e))?;
+            if let Some(tag) = restored {
+                return Ok(tag);
+            }
+        }
+
+        let colors = Self::default_colors_postgres(pool, palette).await?;
+        Self::insert_tag_postgres(pool, name, colors.as_ref())
+            .await
+            .map_err(|e| format!("创建标签失败: {}", e))
+    }
+
+    /// SQLite 实现：创建新标签
+    async fn create_tag_sqlite(
+        pool: &Pool<Sqlite>,
+        name: &str,
+        palette: &[String],
+        restore_if_deleted: bool,
+    ) -> Result<Tag, String> {
+        // 检查是否已存在同名标签
+        let exists_row = sqlx::query(
+            r#"
+            SELECT 1
+            FROM tags
+            WHERE name = ?1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("检查标签是否存在失败: {}", e))?;
+
+        if exists_row.is_some() {
+            return Err(format!("标签 \"{}\" 已存在", name));
+        }
+
+        if restore_if_deleted {
+            let restored = retry_sqlite_busy(|| Self::restore_deleted_tag_sqlite(pool, name))
+                .await
+                .map_err(|e| format!("恢复已删除标签失败: {}", e))?;
+            if let Some(tag) = restored {
+                return Ok(tag);
+            }
+        }
+
+        let colors = Self::default_colors_sqlite(pool, palette).await?;
+        retry_sqlite_busy(|| Self::insert_tag_sqlite(pool, name, colors.as_ref()))
+            .await
+            .map_err(|e| format!("创建标签失败: {}", e))
+    }
+
+    /// PostgreSQL 实现：从默认颜色列表中为新标签选取颜色
+    ///
+    /// # 返回
+    /// (背景颜色, 字体颜色)，颜色列表为空时返回 None（使用数据库默认颜色）
+    async fn default_colors_postgres<'e>(
+        executor: impl Executor<'e, Database = Postgres>,
+        palette: &[String],
+    ) -> Result<Option<(String, String)>, String> {
+        if palette.is_empty() {
+            return Ok(None);
+        }
+
+        let tag_count: i64 = sqlx::query("SELECT COUNT(*) AS count FROM tags WHERE deleted_at IS NULL")
+            .fetch_one(executor)
+            .await
+            .map_err(|e| format!("统计标签数量失败: {}", e))?
+            .get("count");
+        Ok(Self::palette_colors(palette, tag_count))
+    }
+
+    /// SQLite 实现：从默认颜色列表中为新标签选取颜色
+    ///
+    /// # 返回
+    /// (背景颜色, 字体颜色)，颜色列表为空时返回 None（使用数据库默认颜色）
+    async fn default_colors_sqlite<'e>(
+        executor: impl Executor<'e, Database = Sqlite>,
+        palette: &[String],
+    ) -> Result<Option<(String, String)>, String> {
+        if palette.is_empty() {
+            return Ok(None);
+        }
+
+        let tag_count: i64 = sqlx::query("SELECT COUNT(*) AS count FROM tags WHERE deleted_at IS NULL")
+            .fetch_one(executor)
+            .await
+            .map_err(|e| format!("统计标签数量失败: {}", e))?
+            .get("count");
+        Ok(Self::palette_colors(palette, tag_count))
+    }
+
+    /// PostgreSQL 实现：插入新标签
+    ///
+    /// `colors` 为 None 时使用数据库默认颜色
+    async fn insert_tag_postgres<'e>(
+        executor: impl Executor<'e, Database = Postgres>,
+        name: &str,
+        colors: Option<&(String, String)>,
+    ) -> Result<Tag, sqlx::Error> {
+        let (columns, values) = match colors {
+            Some(_) => ("name, color, font_color", "$1, $2, $3"),
+            None => ("name", "$1"),
+        };
+        let sql = format!(
             r#"
-            INSERT INTO tags (name)
-            VALUES ($1)
+            INSERT INTO tags ({})
+            VALUES ({})
             RETURNING
                 id,
                 name,
@@ -348,11 +737,14 @@ impl TagService {
                 TO_CHAR(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at,
                 TO_CHAR(updated_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as updated_at
             "#,
-        )
-        .bind(name)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| format!("创建标签失败: {}", e))?;
+            columns, values
+        );
+
+        let mut query = sqlx::query(&sql).bind(name);
+        if let Some((color, font_color)) = colors {
+            query = query.bind(color).bind(font_color);
+        }
+        let row = query.fetch_one(executor).await?;
 
         Ok(Tag {
             id: row.get("id"),
@@ -366,32 +758,121 @@ impl TagService {
         })
     }
 
-    /// SQLite 实现：创建新标签
-    async fn create_tag_sqlite(pool: &Pool<Sqlite>, name: &str) -> Result<Tag, String> {
-        // 检查是否已存在同名标签
-        let exists_row = sqlx::query(
+    /// SQLite 实现：插入新标签
+    ///
+    /// `colors` 为 None 时使用数据库默认颜色
+    async fn insert_tag_sqlite<'e>(
+        executor: impl Executor<'e, Database = Sqlite>,
+        name: &str,
+        colors: Option<&(String, String)>,
+    ) -> Result<Tag, sqlx::Error> {
+        let (columns, values) = match colors {
+            Some(_) => ("name, color, font_color", "?1, ?2, ?3"),
+            None => ("name", "?1"),
+        };
+        let sql = format!(
             r#"
-            SELECT 1
-            FROM tags
-            WHERE name = ?1 AND deleted_at IS NULL
+            INSERT INTO tags ({})
+            VALUES ({})
+            RETURNING
+                id,
+                name,
+                color,
+                font_color,
+                parent_id,
+                usage_count,
+                datetime(created_at) as created_at,
+                datetime(updated_at) as updated_at
             "#,
-        )
-        .bind(name)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| format!("检查标签是否存在失败: {}", e))?;
+            columns, values
+        );
 
-        if exists_row.is_some() {
-            return Err(format!("标签 \"{}\" 已存在", name));
+        let mut query = sqlx::query(&sql).bind(name);
+        if let Some((color, font_color)) = colors {
+            query = query.bind(color).bind(font_color);
         }
+        let row = query.fetch_one(executor).await?;
+
+        Ok(Tag {
+            id: row.get("id"),
+            name: row.get("name"),
+            color: row.get("color"),
+            font_color: row.get("font_color"),
+            parent_id: row.get("parent_id"),
+            usage_count: row.get("usage_count"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
 
-        // 使用数据库默认值插入
+    /// PostgreSQL 实现：恢复最近删除的同名标签
+    ///
+    /// # 返回
+    /// - `Ok(Some(Tag))`: 恢复后的标签
+    /// - `Ok(None)`: 不存在同名的已删除标签
+    async fn restore_deleted_tag_postgres<'e>(
+        executor: impl Executor<'e, Database = Postgres>,
+        name: &str,
+    ) -> Result<Option<Tag>, sqlx::Error> {
         let row = sqlx::query(
             r#"
-            INSERT INTO tags (name)
-            VALUES (?1);
+            UPDATE tags
+            SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP
+            WHERE id = (
+                SELECT id
+                FROM tags
+                WHERE name = $1 AND deleted_at IS NOT NULL
+                ORDER BY deleted_at DESC, id DESC
+                LIMIT 1
+            )
+            RETURNING
+                id,
+                name,
+                color,
+                font_color,
+                parent_id,
+                usage_count,
+                TO_CHAR(created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at,
+                TO_CHAR(updated_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as updated_at
+            "#,
+        )
+        .bind(name)
+        .fetch_optional(executor)
+        .await?;
 
-            SELECT
+        Ok(row.map(|row| Tag {
+            id: row.get("id"),
+            name: row.get("name"),
+            color: row.get("color"),
+            font_color: row.get("font_color"),
+            parent_id: row.get("parent_id"),
+            usage_count: row.get("usage_count"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }))
+    }
+
+    /// SQLite 实现：恢复最近删除的同名标签
+    ///
+    /// # 返回
+    /// - `Ok(Some(Tag))`: 恢复后的标签
+    /// - `Ok(None)`: 不存在同名的已删除标签
+    async fn restore_deleted_tag_sqlite<'e>(
+        executor: impl Executor<'e, Database = Sqlite>,
+        name: &str,
+    ) -> Result<Option<Tag>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            UPDATE tags
+            SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP
+            WHERE id = (
+                SELECT id
+                FROM tags
+                WHERE name = ?1 AND deleted_at IS NOT NULL
+                ORDER BY deleted_at DESC, id DESC
+                LIMIT 1
+            )
+            RETURNING
                 id,
                 name,
                 color,
@@ -400,18 +881,13 @@ impl TagService {
                 usage_count,
                 datetime(created_at) as created_at,
                 datetime(updated_at) as updated_at
-            FROM tags
-            WHERE name = ?1 AND deleted_at IS NULL
-            ORDER BY id DESC
-            LIMIT 1;
             "#,
         )
         .bind(name)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| format!("创建标签失败: {}", e))?;
+        .fetch_optional(executor)
+        .await?;
 
-        Ok(Tag {
+        Ok(row.map(|row| Tag {
             id: row.get("id"),
             name: row.get("name"),
             color: row.get("color"),
@@ -420,18 +896,40 @@ impl TagService {
             usage_count: row.get("usage_count"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
-        })
+        }))
     }
 
-    /// 修改标签
+    /// 根据ID获取单个标签
     ///
     /// # 参数
     /// - `db`: 全局数据库实例
     /// - `id`: 标签ID
-    /// - `name`: 新标签名称（可选）
-    /// - `color`: 新背景颜色（可选，None表示不修改）
+    ///
+    /// # 返回
+    /// - `Ok(Tag)`: 标签信息
+    /// - `Err(String)`: 错误信息（标签不存在或已删除时返回 "标签 ID {id} 不存在"）
+    pub async fn get_tag(db: &GlobalDatabase, id: i32) -> Result<Tag, String> {
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        match connection {
+            DatabaseConnectionRef::Postgres(pool) => Self::get_tag_by_id_postgres(&pool, id).await,
+            DatabaseConnectionRef::Sqlite(pool) => Self::get_tag_by_id_sqlite(&pool, id).await,
+        }
+    }
+
+    /// 修改标签
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `id`: 标签ID
+    /// - `name`: 新标签名称（可选）
+    /// - `color`: 新背景颜色（可选，None表示不修改）
     /// - `font_color`: 新字体颜色（可选，None表示不修改）
     /// - `parent_id`: 新父标签ID（可选，None表示不修改）
+    /// - `auto_contrast`: 只设置背景颜色时是否自动选择黑色或白色字体，默认为 false
     ///
     /// # 返回
     /// - `Ok(Tag)`: 修改后的标签
@@ -443,7 +941,14 @@ impl TagService {
         color: Option<Option<String>>,
         font_color: Option<Option<String>>,
         parent_id: Option<Option<i32>>,
+        auto_contrast: Option<bool>,
     ) -> Result<Tag, String> {
+        let font_color = if auto_contrast.unwrap_or(false) {
+            Self::resolve_font_color(color.as_ref(), font_color)
+        } else {
+            font_color
+        };
+
         let connection = db
             .get_connection()
             .await
@@ -459,6 +964,31 @@ impl TagService {
         }
     }
 
+    /// 确定要写入的字体颜色
+    ///
+    /// 未提供字体颜色但设置了有效的背景颜色时，根据背景亮度选择黑色或白色；
+    /// 其余情况保持调用方传入的值
+    ///
+    /// # 参数
+    /// - `color`: 新背景颜色（None表示不修改）
+    /// - `font_color`: 新字体颜色（None表示不修改）
+    ///
+    /// # 返回
+    /// 实际要写入的字体颜色
+    pub(crate) fn resolve_font_color(
+        color: Option<&Option<String>>,
+        font_color: Option<Option<String>>,
+    ) -> Option<Option<String>> {
+        if font_color.is_some() {
+            return font_color;
+        }
+
+        match color {
+            Some(Some(background)) => utils::contrast_font_color(background).map(Some),
+            _ => None,
+        }
+    }
+
     /// PostgreSQL 实现：修改标签
     async fn modify_tag_postgres(
         pool: &Pool<Postgres>,
@@ -487,10 +1017,7 @@ impl TagService {
 
         // 如果提供了新名称，检查是否与其他标签重复
         if let Some(ref new_name) = name {
-            let trimmed_name = new_name.trim();
-            if trimmed_name.is_empty() {
-                return Err("标签名称不能为空".to_string());
-            }
+            let trimmed_name = Self::validate_tag_name(new_name)?;
 
             let exists_row = sqlx::query(
                 r#"
@@ -626,10 +1153,7 @@ impl TagService {
 
         // 如果提供了新名称，检查是否与其他标签重复
         if let Some(ref new_name) = name {
-            let trimmed_name = new_name.trim();
-            if trimmed_name.is_empty() {
-                return Err("标签名称不能为空".to_string());
-            }
+            let trimmed_name = Self::validate_tag_name(new_name)?;
 
             let exists_row = sqlx::query(
                 r#"
@@ -691,33 +1215,169 @@ impl TagService {
             bind_index
         );
 
-        let mut query_builder = sqlx::query(&query);
+        // 每次重试都需要重新绑定参数
+        retry_sqlite_busy(|| {
+            let mut query_builder = sqlx::query(&query);
 
-        if let Some(ref new_name) = name {
-            query_builder = query_builder.bind(new_name.trim());
+            if let Some(ref new_name) = name {
+                query_builder = query_builder.bind(new_name.trim());
+            }
+
+            if let Some(color_opt) = &color {
+                query_builder = query_builder.bind(color_opt.as_ref().map(|s| s.as_str()));
+            }
+
+            if let Some(font_color_opt) = &font_color {
+                query_builder = query_builder.bind(font_color_opt.as_ref().map(|s| s.as_str()));
+            }
+
+            if let Some(parent_id_opt) = &parent_id {
+                query_builder = query_builder.bind(parent_id_opt.as_ref());
+            }
+
+            query_builder.bind(id).execute(pool)
+        })
+        .await
+        .map_err(|e| format!("修改标签失败: {}", e))?;
+
+        // 返回更新后的标签
+        Self::get_tag_by_id_sqlite(pool, id).await
+    }
+
+    /// 重命名标签
+    ///
+    /// 只修改名称和更新时间，并在同一事务中把修改前后的名称写入 `tag_audit_log`。
+    /// 新名称与当前名称相同时不做修改，也不记录审计日志
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `id`: 标签ID
+    /// - `new_name`: 新名称（去除首尾空白后使用）
+    ///
+    /// # 返回
+    /// - `Ok(Tag)`: 重命名后的标签
+    /// - `Err(String)`: 错误信息（名称无效、标签不存在、名称重复等）
+    pub async fn rename_tag(db: &GlobalDatabase, id: i32, new_name: &str) -> Result<Tag, String> {
+        let new_name = Self::validate_tag_name(new_name)?;
+
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                Self::rename_tag_postgres(&pool, id, new_name).await?;
+                Self::get_tag_by_id_postgres(&pool, id).await
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                Self::rename_tag_sqlite(&pool, id, new_name).await?;
+                Self::get_tag_by_id_sqlite(&pool, id).await
+            }
         }
+    }
 
-        if let Some(color_opt) = &color {
-            query_builder = query_builder.bind(color_opt.as_ref().map(|s| s.as_str()));
+    /// PostgreSQL 实现：在事务中重命名标签并记录审计日志
+    async fn rename_tag_postgres(pool: &Pool<Postgres>, id: i32, new_name: &str) -> Result<(), String> {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("开启事务失败: {}", e))?;
+
+        // 锁定标签行，避免并发重命名记录错误的旧名称
+        let old_name: String = sqlx::query("SELECT name FROM tags WHERE id = $1 AND deleted_at IS NULL FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("检查标签是否存在失败: {}", e))?
+            .ok_or_else(|| format!("标签 ID {} 不存在", id))?
+            .get("name");
+
+        if old_name == new_name {
+            return Ok(());
         }
 
-        if let Some(font_color_opt) = &font_color {
-            query_builder = query_builder.bind(font_color_opt.as_ref().map(|s| s.as_str()));
+        let duplicate = sqlx::query("SELECT 1 FROM tags WHERE name = $1 AND id != $2 AND deleted_at IS NULL")
+            .bind(new_name)
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("检查标签名称是否重复失败: {}", e))?;
+        if duplicate.is_some() {
+            return Err(format!("标签 \"{}\" 已存在", new_name));
         }
 
-        if let Some(parent_id_opt) = &parent_id {
-            query_builder = query_builder.bind(parent_id_opt.as_ref());
+        sqlx::query("UPDATE tags SET name = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2")
+            .bind(new_name)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("重命名标签失败: {}", e))?;
+
+        sqlx::query(
+            "INSERT INTO tag_audit_log (tag_id, action, old_value, new_value) VALUES ($1, 'rename', $2, $3)",
+        )
+        .bind(id)
+        .bind(&old_name)
+        .bind(new_name)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("记录标签审计日志失败: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("提交事务失败: {}", e))
+    }
+
+    /// SQLite 实现：在事务中重命名标签并记录审计日志
+    async fn rename_tag_sqlite(pool: &Pool<Sqlite>, id: i32, new_name: &str) -> Result<(), String> {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("开启事务失败: {}", e))?;
+
+        let old_name: String = sqlx::query("SELECT name FROM tags WHERE id = ?1 AND deleted_at IS NULL")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("检查标签是否存在失败: {}", e))?
+            .ok_or_else(|| format!("标签 ID {} 不存在", id))?
+            .get("name");
+
+        if old_name == new_name {
+            return Ok(());
         }
 
-        query_builder = query_builder.bind(id);
+        let duplicate = sqlx::query("SELECT 1 FROM tags WHERE name = ?1 AND id != ?2 AND deleted_at IS NULL")
+            .bind(new_name)
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("检查标签名称是否重复失败: {}", e))?;
+        if duplicate.is_some() {
+            return Err(format!("标签 \"{}\" 已存在", new_name));
+        }
 
-        query_builder
-            .execute(pool)
+        sqlx::query("UPDATE tags SET name = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2")
+            .bind(new_name)
+            .bind(id)
+            .execute(&mut *tx)
             .await
-            .map_err(|e| format!("修改标签失败: {}", e))?;
+            .map_err(|e| format!("重命名标签失败: {}", e))?;
 
-        // 返回更新后的标签
-        Self::get_tag_by_id_sqlite(pool, id).await
+        sqlx::query(
+            "INSERT INTO tag_audit_log (tag_id, action, old_value, new_value) VALUES (?1, 'rename', ?2, ?3)",
+        )
+        .bind(id)
+        .bind(&old_name)
+        .bind(new_name)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("记录标签审计日志失败: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("提交事务失败: {}", e))
     }
 
     /// PostgreSQL 实现：根据ID获取标签
@@ -798,33 +1458,216 @@ impl TagService {
     ///
     /// # 参数
     /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（决定是否自动登记文件）
     /// - `paths`: 文件/文件夹路径列表
     /// - `tag_id`: 标签ID
     ///
     /// # 返回
     /// - `Ok(())`: 操作成功
-    /// - `Err(String)`: 错误信息
+    /// - `Err(String)`: 错误信息（关闭自动登记时，未登记的文件返回 "文件未注册"；
+    ///   路径数量超过 `max_batch_size` 时直接拒绝，返回 "批量操作数量超过限制"）
+    ///
+    /// 开启 `incremental_usage_count` 时，使用次数在插入关联的同一事务中原子递增，
+    /// 否则在结束后重新统计
     pub async fn add_tags_to_files(
         db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
         paths: Vec<String>,
         tag_id: i32,
     ) -> Result<(), String> {
+        Self::check_batch_size(global_config, paths.len())?;
+
         let connection = db
             .get_connection()
             .await
             .map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
+        let auto_register = global_config.get_auto_register_files();
+        let incremental = global_config.get_incremental_usage_count();
+
         // 验证标签是否存在
         match connection {
             DatabaseConnectionRef::Postgres(pool) => {
                 Self::verify_tag_exists_postgres(&pool, tag_id).await?;
-                Self::add_tags_to_files_postgres(&pool, &paths, tag_id).await
+                Self::add_tags_to_files_postgres(&pool, &paths, tag_id, auto_register, incremental).await
             }
             DatabaseConnectionRef::Sqlite(pool) => {
                 Self::verify_tag_exists_sqlite(&pool, tag_id).await?;
-                Self::add_tags_to_files_sqlite(&pool, &paths, tag_id).await
+                Self::add_tags_to_files_sqlite(&pool, &paths, tag_id, auto_register, incremental).await
+            }
+        }
+    }
+
+    /// 批量为文件/文件夹添加多个标签
+    ///
+    /// 在一个事务中为所有路径添加所有标签，任一路径失败时整体回滚。
+    /// 执行前先验证所有标签存在，结束后每个标签只重新统计一次使用次数；
+    /// 开启 `incremental_usage_count` 时改为在事务内按实际新增的关联递增。
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（决定是否自动登记文件）
+    /// - `paths`: 文件/文件夹路径列表
+    /// - `tag_ids`: 标签ID列表
+    ///
+    /// # 返回
+    /// - `Ok(())`: 操作成功
+    /// - `Err(String)`: 错误信息（路径数量超过 `max_batch_size` 时返回 "批量操作数量超过限制"）
+    pub async fn add_multiple_tags_to_files(
+        db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
+        paths: Vec<String>,
+        tag_ids: Vec<i32>,
+    ) -> Result<(), String> {
+        Self::check_batch_size(global_config, paths.len())?;
+
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let auto_register = global_config.get_auto_register_files();
+        let incremental = global_config.get_incremental_usage_count();
+
+        match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                for tag_id in &tag_ids {
+                    Self::verify_tag_exists_postgres(&pool, *tag_id).await?;
+                }
+                Self::add_multiple_tags_to_files_postgres(&pool, &paths, &tag_ids, auto_register, incremental).await
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                for tag_id in &tag_ids {
+                    Self::verify_tag_exists_sqlite(&pool, *tag_id).await?;
+                }
+                Self::add_multiple_tags_to_files_sqlite(&pool, &paths, &tag_ids, auto_register, incremental).await
+            }
+        }
+    }
+
+    /// 清除文件/文件夹上的所有标签
+    ///
+    /// 删除这些文件的所有标签关联，并更新受影响标签的使用次数（重新统计或事务内递减）。
+    /// 没有文件记录的路径直接跳过。
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（决定使用次数的维护方式）
+    /// - `paths`: 文件/文件夹路径列表
+    ///
+    /// # 返回
+    /// - `Ok(())`: 操作成功
+    /// - `Err(String)`: 错误信息
+    pub async fn clear_tags_from_files(
+        db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
+        paths: Vec<String>,
+    ) -> Result<(), String> {
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let incremental = global_config.get_incremental_usage_count();
+
+        match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                Self::clear_tags_from_files_postgres(&pool, &paths, incremental).await
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                Self::clear_tags_from_files_sqlite(&pool, &paths, incremental).await
+            }
+        }
+    }
+
+    /// 统计标签的使用趋势
+    ///
+    /// 按 file_tags 关联的创建日期分组，统计每天被添加该标签的文件数量，用于使用趋势图表
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（提供慢查询阈值）
+    /// - `tag_id`: 标签ID
+    ///
+    /// # 返回
+    /// - `Ok(Vec<(String, i64)>)`: (日期 YYYY-MM-DD, 文件数量) 列表，按日期升序
+    /// - `Err(String)`: 错误信息
+    pub async fn tag_activity(
+        db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
+        tag_id: i32,
+    ) -> Result<Vec<(String, i64)>, String> {
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        log_slow("TagService::tag_activity", connection.kind(), global_config.get_slow_query_threshold(), async {
+            match connection {
+                DatabaseConnectionRef::Postgres(pool) => {
+                    Self::verify_tag_exists_postgres(&pool, tag_id).await?;
+                    Self::tag_activity_postgres(&pool, tag_id).await
+                }
+                DatabaseConnectionRef::Sqlite(pool) => {
+                    Self::verify_tag_exists_sqlite(&pool, tag_id).await?;
+                    Self::tag_activity_sqlite(&pool, tag_id).await
+                }
+            }
+        })
+        .await
+    }
+
+    /// 根据扩展名推荐标签
+    ///
+    /// 统计与 `path` 扩展名相同（不区分大小写）的已登记文件上各标签出现的次数，
+    /// 按出现次数降序返回，`path` 自身已有的标签不会被推荐
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（提供慢查询阈值）
+    /// - `path`: 待打标签的文件路径（文件可以尚未登记）
+    ///
+    /// # 返回
+    /// - `Ok(Vec<Tag>)`: 推荐的标签列表，最多 `MAX_TAG_SUGGESTIONS` 个；没有扩展名时为空
+    /// - `Err(String)`: 错误信息
+    pub async fn suggest_tags(
+        db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
+        path: &str,
+    ) -> Result<Vec<Tag>, String> {
+        let Some(extension) = std::path::Path::new(path).extension().and_then(|ext| ext.to_str()) else {
+            return Ok(Vec::new());
+        };
+        let pattern = format!("%.{}", Self::escape_like(extension));
+
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        log_slow("TagService::suggest_tags", connection.kind(), global_config.get_slow_query_threshold(), async {
+            match connection {
+                DatabaseConnectionRef::Postgres(pool) => {
+                    Self::suggest_tags_postgres(&pool, path, &pattern).await
+                }
+                DatabaseConnectionRef::Sqlite(pool) => {
+                    Self::suggest_tags_sqlite(&pool, path, &pattern).await
+                }
             }
+        })
+        .await
+    }
+
+    /// 检查批量操作的路径数量是否超过配置的上限
+    ///
+    /// 超过上限时整体拒绝而不是分批执行，避免调用方误以为操作在一个事务中完成
+    fn check_batch_size(global_config: &GlobalConfigManager, count: usize) -> Result<(), String> {
+        let max_batch_size = global_config.get_max_batch_size();
+        if count > max_batch_size {
+            return Err(format!("批量操作数量超过限制: {} > {}", count, max_batch_size));
         }
+
+        Ok(())
     }
 
     /// PostgreSQL 实现：验证标签是否存在
@@ -862,36 +1705,23 @@ impl TagService {
         pool: &Pool<Postgres>,
         paths: &[String],
         tag_id: i32,
+        auto_register: bool,
+        incremental: bool,
     ) -> Result<(), String> {
-        use std::path::Path;
-        use std::fs;
-
         for path in paths {
-            let path_obj = Path::new(path);
-
-            // 检查路径是否存在
-            if !path_obj.exists() {
-                return Err(format!("路径不存在: {}", path));
-            }
-
-            // 判断是文件还是文件夹
-            let is_dir = path_obj.is_dir();
-            let file_type = if is_dir { "folder" } else { "file" };
-
-            // 获取文件大小
-            let file_size = if is_dir {
-                0
-            } else {
-                fs::metadata(path_obj)
-                    .map_err(|e| format!("获取文件元数据失败 {}: {}", path, e))?
-                    .len() as i64
-            };
+            // 判断是文件还是文件夹并获取文件大小（路径不存在时返回错误）
+            let (file_type, file_size) = Self::describe_path(path).await?;
 
             // 获取或创建文件记录
-            let file_id = Self::get_or_create_file_postgres(pool, path, file_type, file_size).await?;
+            let file_id = Self::get_or_create_file_postgres(pool, path, file_type, file_size, auto_register).await?;
+
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| format!("开启事务失败: {}", e))?;
 
             // 添加文件-标签关联（如果已存在则忽略）
-            sqlx::query(
+            let inserted = sqlx::query(
                 r#"
                 INSERT INTO file_tags (file_id, tag_id)
                 VALUES ($1, $2)
@@ -900,29 +1730,25 @@ impl TagService {
             )
             .bind(file_id)
             .bind(tag_id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await
-            .map_err(|e| format!("添加标签关联失败: {}", e))?;
+            .map_err(|e| format!("添加标签关联失败: {}", e))?
+            .rows_affected();
+
+            if incremental && inserted > 0 {
+                Self::adjust_usage_postgres(&mut tx, tag_id, 1).await?;
+            }
+
+            tx.commit()
+                .await
+                .map_err(|e| format!("提交事务失败: {}", e))?;
         }
 
         // 更新标签使用次数
-        sqlx::query(
-            r#"
-            UPDATE tags
-            SET usage_count = (
-                SELECT COUNT(DISTINCT file_id)
-                FROM file_tags
-                WHERE tag_id = $1
-            )
-            WHERE id = $1
-            "#,
-        )
-        .bind(tag_id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("更新标签使用次数失败: {}", e))?;
-
-        Ok(())
+        if incremental {
+            return Ok(());
+        }
+        Self::recount_usage_postgres(pool, tag_id).await
     }
 
     /// SQLite 实现：批量添加标签到文件
@@ -930,36 +1756,39 @@ impl TagService {
         pool: &Pool<Sqlite>,
         paths: &[String],
         tag_id: i32,
+        auto_register: bool,
+        incremental: bool,
     ) -> Result<(), String> {
-        use std::path::Path;
-        use std::fs;
-
         for path in paths {
-            let path_obj = Path::new(path);
+            // 判断是文件还是文件夹并获取文件大小（路径不存在时返回错误）
+            let (file_type, file_size) = Self::describe_path(path).await?;
 
-            // 检查路径是否存在
-            if !path_obj.exists() {
-                return Err(format!("路径不存在: {}", path));
+            // 获取或创建文件记录
+            let file_id = Self::get_or_create_file_sqlite(pool, path, file_type, file_size, auto_register).await?;
+
+            if !incremental {
+                // 添加文件-标签关联（如果已存在则忽略）
+                retry_sqlite_busy(|| sqlx::query(
+                    r#"
+                    INSERT OR IGNORE INTO file_tags (file_id, tag_id)
+                    VALUES (?1, ?2)
+                    "#,
+                )
+                .bind(file_id)
+                .bind(tag_id)
+                .execute(pool))
+                .await
+                .map_err(|e| format!("添加标签关联失败: {}", e))?;
+                continue;
             }
 
-            // 判断是文件还是文件夹
-            let is_dir = path_obj.is_dir();
-            let file_type = if is_dir { "folder" } else { "file" };
+            // 关联和使用次数在同一事务中写入
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| format!("开启事务失败: {}", e))?;
 
-            // 获取文件大小
-            let file_size = if is_dir {
-                0
-            } else {
-                fs::metadata(path_obj)
-                    .map_err(|e| format!("获取文件元数据失败 {}: {}", path, e))?
-                    .len() as i64
-            };
-
-            // 获取或创建文件记录
-            let file_id = Self::get_or_create_file_sqlite(pool, path, file_type, file_size).await?;
-
-            // 添加文件-标签关联（如果已存在则忽略）
-            sqlx::query(
+            let inserted = sqlx::query(
                 r#"
                 INSERT OR IGNORE INTO file_tags (file_id, tag_id)
                 VALUES (?1, ?2)
@@ -967,21 +1796,623 @@ impl TagService {
             )
             .bind(file_id)
             .bind(tag_id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await
-            .map_err(|e| format!("添加标签关联失败: {}", e))?;
+            .map_err(|e| format!("添加标签关联失败: {}", e))?
+            .rows_affected();
+
+            if inserted > 0 {
+                Self::adjust_usage_sqlite(&mut tx, tag_id, 1).await?;
+            }
+
+            tx.commit()
+                .await
+                .map_err(|e| format!("提交事务失败: {}", e))?;
         }
 
         // 更新标签使用次数
+        if incremental {
+            return Ok(());
+        }
+        Self::recount_usage_sqlite(pool, tag_id).await
+    }
+
+    /// 获取路径对应的文件类型和大小（用于登记文件记录）
+    ///
+    /// 文件系统调用放到阻塞线程池中执行，批量打标签时不会占住异步工作线程
+    async fn describe_path(path: &str) -> Result<(&'static str, i64), String> {
+        let path = path.to_string();
+        tokio::task::spawn_blocking(move || {
+            let metadata = std::fs::metadata(&path)
+                .map_err(|_| format!("路径不存在: {}", path))?;
+
+            if metadata.is_dir() {
+                Ok(("folder", 0))
+            } else {
+                Ok(("file", metadata.len() as i64))
+            }
+        })
+        .await
+        .map_err(|e| format!("读取文件元数据任务失败: {}", e))?
+    }
+
+    /// PostgreSQL 实现：在事务中批量为文件添加多个标签
+    async fn add_multiple_tags_to_files_postgres(
+        pool: &Pool<Postgres>,
+        paths: &[String],
+        tag_ids: &[i32],
+        auto_register: bool,
+        incremental: bool,
+    ) -> Result<(), String> {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("开启事务失败: {}", e))?;
+
+        for path in paths {
+            let (file_type, file_size) = Self::describe_path(path).await?;
+
+            // 获取或创建文件记录
+            let row = sqlx::query("SELECT id FROM files WHERE current_path = $1 AND deleted_at IS NULL")
+                .bind(path)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| format!("查询文件记录失败: {}", e))?;
+
+            let file_id: i32 = match row {
+                Some(row) => row.get("id"),
+                None => {
+                    if !auto_register {
+                        return Err(format!("文件未注册: {}", path));
+                    }
+
+                    sqlx::query(
+                        r#"
+                        INSERT INTO files (current_path, file_type, file_size)
+                        VALUES ($1, $2, $3)
+                        ON CONFLICT (current_path) DO UPDATE
+                        SET file_type = EXCLUDED.file_type,
+                            file_size = EXCLUDED.file_size,
+                            updated_at = CURRENT_TIMESTAMP,
+                            deleted_at = NULL
+                        RETURNING id
+                        "#,
+                    )
+                    .bind(path)
+                    .bind(file_type)
+                    .bind(file_size)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(|e| format!("创建文件记录失败: {}", e))?
+                    .get("id")
+                }
+            };
+
+            // 添加文件-标签关联（如果已存在则忽略）
+            for tag_id in tag_ids {
+                let inserted = sqlx::query(
+                    r#"
+                    INSERT INTO file_tags (file_id, tag_id)
+                    VALUES ($1, $2)
+                    ON CONFLICT (file_id, tag_id) DO NOTHING
+                    "#,
+                )
+                .bind(file_id)
+                .bind(tag_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("添加标签关联失败: {}", e))?
+                .rows_affected();
+
+                if incremental && inserted > 0 {
+                    Self::adjust_usage_postgres(&mut tx, *tag_id, 1).await?;
+                }
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("提交事务失败: {}", e))?;
+
+        // 每个标签只重新统计一次使用次数
+        if !incremental {
+            for tag_id in tag_ids {
+                Self::recount_usage_postgres(pool, *tag_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// SQLite 实现：在事务中批量为文件添加多个标签
+    async fn add_multiple_tags_to_files_sqlite(
+        pool: &Pool<Sqlite>,
+        paths: &[String],
+        tag_ids: &[i32],
+        auto_register: bool,
+        incremental: bool,
+    ) -> Result<(), String> {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("开启事务失败: {}", e))?;
+
+        for path in paths {
+            let (file_type, file_size) = Self::describe_path(path).await?;
+
+            // 获取或创建文件记录
+            let row = sqlx::query("SELECT id FROM files WHERE current_path = ?1 AND deleted_at IS NULL")
+                .bind(path)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| format!("查询文件记录失败: {}", e))?;
+
+            let file_id: i32 = match row {
+                Some(row) => row.get("id"),
+                None => {
+                    if !auto_register {
+                        return Err(format!("文件未注册: {}", path));
+                    }
+
+                    // SQLite 不支持 ON CONFLICT DO UPDATE，需要先尝试插入，如果失败则更新
+                    let inserted = sqlx::query(
+                        r#"
+                        INSERT INTO files (current_path, file_type, file_size)
+                        VALUES (?1, ?2, ?3)
+                        "#,
+                    )
+                    .bind(path)
+                    .bind(file_type)
+                    .bind(file_size)
+                    .execute(&mut *tx)
+                    .await;
+
+                    if inserted.is_err() {
+                        sqlx::query(
+                            r#"
+                            UPDATE files
+                            SET file_type = ?2,
+                                file_size = ?3,
+                                updated_at = CURRENT_TIMESTAMP,
+                                deleted_at = NULL
+                            WHERE current_path = ?1
+                            "#,
+                        )
+                        .bind(path)
+                        .bind(file_type)
+                        .bind(file_size)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| format!("更新文件记录失败: {}", e))?;
+                    }
+
+                    sqlx::query("SELECT id FROM files WHERE current_path = ?1")
+                        .bind(path)
+                        .fetch_one(&mut *tx)
+                        .await
+                        .map_err(|e| format!("获取文件ID失败: {}", e))?
+                        .get("id")
+                }
+            };
+
+            // 添加文件-标签关联（如果已存在则忽略）
+            for tag_id in tag_ids {
+                let inserted = sqlx::query(
+                    r#"
+                    INSERT OR IGNORE INTO file_tags (file_id, tag_id)
+                    VALUES (?1, ?2)
+                    "#,
+                )
+                .bind(file_id)
+                .bind(tag_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("添加标签关联失败: {}", e))?
+                .rows_affected();
+
+                if incremental && inserted > 0 {
+                    Self::adjust_usage_sqlite(&mut tx, *tag_id, 1).await?;
+                }
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("提交事务失败: {}", e))?;
+
+        // 每个标签只重新统计一次使用次数
+        if !incremental {
+            for tag_id in tag_ids {
+                Self::recount_usage_sqlite(pool, *tag_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// PostgreSQL 实现：清除文件上的所有标签
+    async fn clear_tags_from_files_postgres(
+        pool: &Pool<Postgres>,
+        paths: &[String],
+        incremental: bool,
+    ) -> Result<(), String> {
+        let mut affected_tag_ids: Vec<i32> = Vec::new();
+
+        for path in paths {
+            let row = sqlx::query("SELECT id FROM files WHERE current_path = $1 AND deleted_at IS NULL")
+                .bind(path)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| format!("查询文件记录失败: {}", e))?;
+
+            // 没有记录的文件不会有标签，直接跳过
+            let file_id: i32 = match row {
+                Some(row) => row.get("id"),
+                None => continue,
+            };
+
+            let mut tx = pool
+                .begin()
+                .await
+                .map_err(|e| format!("开启事务失败: {}", e))?;
+
+            let tag_rows = sqlx::query("DELETE FROM file_tags WHERE file_id = $1 RETURNING tag_id")
+                .bind(file_id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| format!("清除标签关联失败: {}", e))?;
+
+            if incremental {
+                for row in &tag_rows {
+                    Self::adjust_usage_postgres(&mut tx, row.get("tag_id"), -1).await?;
+                }
+            }
+
+            tx.commit()
+                .await
+                .map_err(|e| format!("提交事务失败: {}", e))?;
+
+            for row in tag_rows {
+                let tag_id: i32 = row.get("tag_id");
+                if !affected_tag_ids.contains(&tag_id) {
+                    affected_tag_ids.push(tag_id);
+                }
+            }
+        }
+
+        if incremental {
+            return Ok(());
+        }
+
+        for tag_id in affected_tag_ids {
+            Self::recount_usage_postgres(pool, tag_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// SQLite 实现：清除文件上的所有标签
+    async fn clear_tags_from_files_sqlite(
+        pool: &Pool<Sqlite>,
+        paths: &[String],
+        incremental: bool,
+    ) -> Result<(), String> {
+        let mut affected_tag_ids: Vec<i32> = Vec::new();
+
+        for path in paths {
+            let row = sqlx::query("SELECT id FROM files WHERE current_path = ?1 AND deleted_at IS NULL")
+                .bind(path)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| format!("查询文件记录失败: {}", e))?;
+
+            // 没有记录的文件不会有标签，直接跳过
+            let file_id: i32 = match row {
+                Some(row) => row.get("id"),
+                None => continue,
+            };
+
+            let tag_rows = if incremental {
+                // 删除关联和递减使用次数在同一事务中完成
+                let mut tx = pool
+                    .begin()
+                    .await
+                    .map_err(|e| format!("开启事务失败: {}", e))?;
+
+                let tag_rows = sqlx::query("SELECT tag_id FROM file_tags WHERE file_id = ?1")
+                    .bind(file_id)
+                    .fetch_all(&mut *tx)
+                    .await
+                    .map_err(|e| format!("查询文件标签失败: {}", e))?;
+
+                sqlx::query("DELETE FROM file_tags WHERE file_id = ?1")
+                    .bind(file_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("清除标签关联失败: {}", e))?;
+
+                for row in &tag_rows {
+                    Self::adjust_usage_sqlite(&mut tx, row.get("tag_id"), -1).await?;
+                }
+
+                tx.commit()
+                    .await
+                    .map_err(|e| format!("提交事务失败: {}", e))?;
+                tag_rows
+            } else {
+                let tag_rows = sqlx::query("SELECT tag_id FROM file_tags WHERE file_id = ?1")
+                    .bind(file_id)
+                    .fetch_all(pool)
+                    .await
+                    .map_err(|e| format!("查询文件标签失败: {}", e))?;
+
+                retry_sqlite_busy(|| sqlx::query("DELETE FROM file_tags WHERE file_id = ?1")
+                    .bind(file_id)
+                    .execute(pool))
+                    .await
+                    .map_err(|e| format!("清除标签关联失败: {}", e))?;
+                tag_rows
+            };
+
+            for row in tag_rows {
+                let tag_id: i32 = row.get("tag_id");
+                if !affected_tag_ids.contains(&tag_id) {
+                    affected_tag_ids.push(tag_id);
+                }
+            }
+        }
+
+        if incremental {
+            return Ok(());
+        }
+
+        for tag_id in affected_tag_ids {
+            Self::recount_usage_sqlite(pool, tag_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 统计标签概况
+    ///
+    /// 一次聚合查询得到标签总数、有标签的文件数和标签关联数，再查询使用次数最多的5个标签。
+    /// 已删除的标签和文件不计入统计
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（提供慢查询阈值）
+    ///
+    /// # 返回
+    /// - `Ok(TagStats)`: 统计结果
+    /// - `Err(String)`: 错误信息
+    pub async fn get_statistics(db: &GlobalDatabase, global_config: &GlobalConfigManager) -> Result<TagStats, String> {
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let query = r#"
+            SELECT
+                (SELECT COUNT(*) FROM tags WHERE deleted_at IS NULL) AS total_tags,
+                COUNT(DISTINCT ft.file_id) AS tagged_files,
+                COUNT(*) AS associations
+            FROM file_tags ft
+            INNER JOIN files f ON f.id = ft.file_id
+            INNER JOIN tags t ON t.id = ft.tag_id
+            WHERE f.deleted_at IS NULL AND t.deleted_at IS NULL
+        "#;
+
+        let (counts, top_tags): ((i64, i64, i64), Vec<Tag>) = log_slow("TagService::get_statistics", connection.kind(), global_config.get_slow_query_threshold(), async {
+            Ok::<_, String>(match connection {
+                DatabaseConnectionRef::Postgres(pool) => {
+                    let row = sqlx::query(query)
+                        .fetch_one(&pool)
+                        .await
+                        .map_err(|e| format!("统计标签失败: {}", e))?;
+                    let counts = (row.get("total_tags"), row.get("tagged_files"), row.get("associations"));
+                    (counts, Self::get_tag_list_postgres(&pool, 5, "most_used").await?)
+                }
+                DatabaseConnectionRef::Sqlite(pool) => {
+                    let row = sqlx::query(query)
+                        .fetch_one(&pool)
+                        .await
+                        .map_err(|e| format!("统计标签失败: {}", e))?;
+                    let counts = (row.get("total_tags"), row.get("tagged_files"), row.get("associations"));
+                    (counts, Self::get_tag_list_sqlite(&pool, 5, "most_used").await?)
+                }
+            })
+        })
+        .await?;
+        let (total_tags, tagged_files, associations) = counts;
+
+        let average_tags_per_file = if tagged_files > 0 {
+            associations as f64 / tagged_files as f64
+        } else {
+            0.0
+        };
+
+        Ok(TagStats {
+            total_tags,
+            tagged_files,
+            average_tags_per_file,
+            top_tags,
+        })
+    }
+
+    /// PostgreSQL 实现：统计标签的使用趋势
+    async fn tag_activity_postgres(pool: &Pool<Postgres>, tag_id: i32) -> Result<Vec<(String, i64)>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                TO_CHAR(date_trunc('day', created_at), 'YYYY-MM-DD') AS day,
+                COUNT(*) AS count
+            FROM file_tags
+            WHERE tag_id = $1 AND created_at IS NOT NULL
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(tag_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("统计标签使用趋势失败: {}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("day"), row.get("count")))
+            .collect())
+    }
+
+    /// SQLite 实现：统计标签的使用趋势
+    async fn tag_activity_sqlite(pool: &Pool<Sqlite>, tag_id: i32) -> Result<Vec<(String, i64)>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                date(created_at) AS day,
+                COUNT(*) AS count
+            FROM file_tags
+            WHERE tag_id = ?1 AND created_at IS NOT NULL
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(tag_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("统计标签使用趋势失败: {}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("day"), row.get("count")))
+            .collect())
+    }
+
+    /// PostgreSQL 实现：根据扩展名推荐标签
+    async fn suggest_tags_postgres(pool: &Pool<Postgres>, path: &str, pattern: &str) -> Result<Vec<Tag>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                t.id,
+                t.name,
+                t.color,
+                t.font_color,
+                t.parent_id,
+                t.usage_count,
+                TO_CHAR(t.created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as created_at,
+                TO_CHAR(t.updated_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') as updated_at
+            FROM tags t
+            INNER JOIN file_tags ft ON ft.tag_id = t.id
+            INNER JOIN files f ON f.id = ft.file_id
+            WHERE t.deleted_at IS NULL
+            AND f.deleted_at IS NULL
+            AND f.file_type = 'file'
+            AND f.current_path ILIKE $2 ESCAPE '\'
+            AND t.id NOT IN (
+                SELECT own.tag_id
+                FROM file_tags own
+                INNER JOIN files own_file ON own_file.id = own.file_id
+                WHERE own_file.current_path = $1 AND own_file.deleted_at IS NULL
+            )
+            GROUP BY t.id
+            ORDER BY COUNT(*) DESC, t.usage_count DESC, t.id ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(path)
+        .bind(pattern)
+        .bind(MAX_TAG_SUGGESTIONS)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("推荐标签失败: {}", e))?;
+
+        let mut tags = Vec::new();
+        for row in rows {
+            tags.push(Tag {
+                id: row.get("id"),
+                name: row.get("name"),
+                color: row.get("color"),
+                font_color: row.get("font_color"),
+                parent_id: row.get("parent_id"),
+                usage_count: row.get("usage_count"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            });
+        }
+
+        Ok(tags)
+    }
+
+    /// SQLite 实现：根据扩展名推荐标签
+    ///
+    /// SQLite 的 LIKE 对 ASCII 字符不区分大小写，与 PostgreSQL 的 ILIKE 行为一致
+    async fn suggest_tags_sqlite(pool: &Pool<Sqlite>, path: &str, pattern: &str) -> Result<Vec<Tag>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                t.id,
+                t.name,
+                t.color,
+                t.font_color,
+                t.parent_id,
+                t.usage_count,
+                datetime(t.created_at) as created_at,
+                datetime(t.updated_at) as updated_at
+            FROM tags t
+            INNER JOIN file_tags ft ON ft.tag_id = t.id
+            INNER JOIN files f ON f.id = ft.file_id
+            WHERE t.deleted_at IS NULL
+            AND f.deleted_at IS NULL
+            AND f.file_type = 'file'
+            AND f.current_path LIKE ?2 ESCAPE '\'
+            AND t.id NOT IN (
+                SELECT own.tag_id
+                FROM file_tags own
+                INNER JOIN files own_file ON own_file.id = own.file_id
+                WHERE own_file.current_path = ?1 AND own_file.deleted_at IS NULL
+            )
+            GROUP BY t.id
+            ORDER BY COUNT(*) DESC, t.usage_count DESC, t.id ASC
+            LIMIT ?3
+            "#,
+        )
+        .bind(path)
+        .bind(pattern)
+        .bind(MAX_TAG_SUGGESTIONS)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("推荐标签失败: {}", e))?;
+
+        let mut tags = Vec::new();
+        for row in rows {
+            tags.push(Tag {
+                id: row.get("id"),
+                name: row.get("name"),
+                color: row.get("color"),
+                font_color: row.get("font_color"),
+                parent_id: row.get("parent_id"),
+                usage_count: row.get("usage_count"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            });
+        }
+
+        Ok(tags)
+    }
+
+    /// PostgreSQL 实现：重新统计标签使用次数
+    ///
+    /// 只统计未被软删除的文件
+    pub(crate) async fn recount_usage_postgres(pool: &Pool<Postgres>, tag_id: i32) -> Result<(), String> {
         sqlx::query(
             r#"
             UPDATE tags
             SET usage_count = (
-                SELECT COUNT(DISTINCT file_id)
-                FROM file_tags
-                WHERE tag_id = ?1
+                SELECT COUNT(DISTINCT ft.file_id)
+                FROM file_tags ft
+                INNER JOIN files f ON f.id = ft.file_id
+                WHERE ft.tag_id = $1 AND f.deleted_at IS NULL
             )
-            WHERE id = ?1
+            WHERE id = $1
             "#,
         )
         .bind(tag_id)
@@ -992,12 +2423,113 @@ impl TagService {
         Ok(())
     }
 
+    /// SQLite 实现：重新统计标签使用次数
+    ///
+    /// 只统计未被软删除的文件
+    pub(crate) async fn recount_usage_sqlite(pool: &Pool<Sqlite>, tag_id: i32) -> Result<(), String> {
+        retry_sqlite_busy(|| sqlx::query(
+            r#"
+            UPDATE tags
+            SET usage_count = (
+                SELECT COUNT(DISTINCT ft.file_id)
+                FROM file_tags ft
+                INNER JOIN files f ON f.id = ft.file_id
+                WHERE ft.tag_id = ?1 AND f.deleted_at IS NULL
+            )
+            WHERE id = ?1
+            "#,
+        )
+        .bind(tag_id)
+        .execute(pool))
+        .await
+        .map_err(|e| format!("更新标签使用次数失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// PostgreSQL 实现：在事务中调整标签使用次数
+    ///
+    /// 与关联的插入/删除处于同一事务，并发修改时由数据库保证原子性
+    async fn adjust_usage_postgres(
+        tx: &mut Transaction<'_, Postgres>,
+        tag_id: i32,
+        delta: i32,
+    ) -> Result<(), String> {
+        sqlx::query("UPDATE tags SET usage_count = usage_count + $2 WHERE id = $1")
+            .bind(tag_id)
+            .bind(delta)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| format!("更新标签使用次数失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// SQLite 实现：在事务中调整标签使用次数
+    ///
+    /// 与关联的插入/删除处于同一事务，并发修改时由数据库保证原子性
+    async fn adjust_usage_sqlite(
+        tx: &mut Transaction<'_, Sqlite>,
+        tag_id: i32,
+        delta: i32,
+    ) -> Result<(), String> {
+        sqlx::query("UPDATE tags SET usage_count = usage_count + ?2 WHERE id = ?1")
+            .bind(tag_id)
+            .bind(delta)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| format!("更新标签使用次数失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 重新统计所有标签的使用次数
+    ///
+    /// 增量维护使用次数时定期调用，修正可能出现的偏差（例如文件被软删除后未同步递减）
+    ///
+    /// # 参数
+    /// - `db`: 全局数据库实例
+    ///
+    /// # 返回
+    /// - `Ok(())`: 操作成功
+    /// - `Err(String)`: 错误信息
+    pub async fn recount_all_usage(db: &GlobalDatabase) -> Result<(), String> {
+        let connection = db
+            .get_connection()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let query = r#"
+            UPDATE tags
+            SET usage_count = (
+                SELECT COUNT(DISTINCT ft.file_id)
+                FROM file_tags ft
+                INNER JOIN files f ON f.id = ft.file_id
+                WHERE ft.tag_id = tags.id AND f.deleted_at IS NULL
+            )
+            WHERE deleted_at IS NULL
+        "#;
+
+        match connection {
+            DatabaseConnectionRef::Postgres(pool) => {
+                sqlx::query(query).execute(&pool).await.map(|_| ())
+            }
+            DatabaseConnectionRef::Sqlite(pool) => {
+                retry_sqlite_busy(|| sqlx::query(query).execute(&pool)).await.map(|_| ())
+            }
+        }
+        .map_err(|e| format!("更新标签使用次数失败: {}", e))
+    }
+
     /// PostgreSQL 实现：获取或创建文件记录
+    ///
+    /// `auto_register` 为 false 时不创建新记录，未登记的文件返回 "文件未注册"
     async fn get_or_create_file_postgres(
         pool: &Pool<Postgres>,
         path: &str,
         file_type: &str,
         file_size: i64,
+        auto_register: bool,
     ) -> Result<i32, String> {
         // 先尝试查找现有记录
         let row = sqlx::query("SELECT id FROM files WHERE current_path = $1 AND deleted_at IS NULL")
@@ -1010,6 +2542,10 @@ impl TagService {
             return Ok(row.get("id"));
         }
 
+        if !auto_register {
+            return Err(format!("文件未注册: {}", path));
+        }
+
         // 如果不存在，创建新记录
         let row = sqlx::query(
             r#"
@@ -1034,11 +2570,14 @@ impl TagService {
     }
 
     /// SQLite 实现：获取或创建文件记录
+    ///
+    /// `auto_register` 为 false 时不创建新记录，未登记的文件返回 "文件未注册"
     async fn get_or_create_file_sqlite(
         pool: &Pool<Sqlite>,
         path: &str,
         file_type: &str,
         file_size: i64,
+        auto_register: bool,
     ) -> Result<i32, String> {
         // 先尝试查找现有记录
         let row = sqlx::query("SELECT id FROM files WHERE current_path = ?1 AND deleted_at IS NULL")
@@ -1051,9 +2590,13 @@ impl TagService {
             return Ok(row.get("id"));
         }
 
+        if !auto_register {
+            return Err(format!("文件未注册: {}", path));
+        }
+
         // 如果不存在，创建新记录
         // SQLite 不支持 ON CONFLICT DO UPDATE，需要先尝试插入，如果失败则更新
-        let result = sqlx::query(
+        let result = retry_sqlite_busy(|| sqlx::query(
             r#"
             INSERT INTO files (current_path, file_type, file_size)
             VALUES (?1, ?2, ?3)
@@ -1062,7 +2605,7 @@ impl TagService {
         .bind(path)
         .bind(file_type)
         .bind(file_size)
-        .execute(pool)
+        .execute(pool))
         .await;
 
         match result {
@@ -1077,7 +2620,7 @@ impl TagService {
             }
             Err(_) => {
                 // 插入失败（可能是唯一约束冲突），更新现有记录
-                sqlx::query(
+                retry_sqlite_busy(|| sqlx::query(
                     r#"
                     UPDATE files
                     SET file_type = ?2,
@@ -1090,7 +2633,7 @@ impl TagService {
                 .bind(path)
                 .bind(file_type)
                 .bind(file_size)
-                .execute(pool)
+                .execute(pool))
                 .await
                 .map_err(|e| format!("更新文件记录失败: {}", e))?;
 
@@ -1109,15 +2652,17 @@ impl TagService {
     ///
     /// # 参数
     /// - `db`: 全局数据库实例
+    /// - `global_config`: 全局配置管理器（提供查询默认值）
     /// - `tag_id`: 标签ID
     /// - `page`: 页码（从1开始），默认为1
-    /// - `page_size`: 每页数量，默认为50
+    /// - `page_size`: 每页数量，默认使用配置中的 page_size（50）
     ///
     /// # 返回
     /// - `Ok(SearchResult)`: 搜索结果
     /// - `Err(String)`: 错误信息
     pub async fn search_files_by_tag(
         db: &GlobalDatabase,
+        global_config: &GlobalConfigManager,
         tag_id: i32,
         page: Option<usize>,
         page_size: Option<usize>,
@@ -1127,18 +2672,60 @@ impl TagService {
             .await
             .map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
-        let page = page.unwrap_or(1);
-        let page_size = page_size.unwrap_or(50);
+        let page = page.unwrap_or(1).max(1);
+        let page_size = page_size.unwrap_or(global_config.get_query_defaults().page_size);
         let offset = (page - 1) * page_size;
-
-        match connection {
-            DatabaseConnectionRef::Postgres(pool) => {
-                Self::search_files_by_tag_postgres(&pool, tag_id, page, page_size, offset).await
+        let display = global_config.get_display_options();
+
+        log_slow("TagService::search_files_by_tag", connection.kind(), global_config.get_slow_query_threshold(), async {
+            match connection {
+                DatabaseConnectionRef::Postgres(pool) => {
+                    Self::search_files_by_tag_postgres(&pool, tag_id, page, page_size, offset, display).await
+                }
+                DatabaseConnectionRef::Sqlite(pool) => {
+                    Self::search_files_by_tag_sqlite(&pool, tag_id, page, page_size, offset, display).await
+                }
             }
-            DatabaseConnectionRef::Sqlite(pool) => {
-                Self::search_files_by_tag_sqlite(&pool, tag_id, page, page_size, offset).await
+        })
+        .await
+    }
+
+    /// 根据数据库中的文件记录构建 FileItem 列表
+    ///
+    /// 逐条读取文件元数据的操作放到阻塞线程池中执行，大页查询时不会占住异步工作线程。
+    /// 已不存在的路径会被跳过，大小取数据库登记值
+    ///
+    /// # 参数
+    /// - `records`: 查询到的文件记录
+    /// - `display`: 文件项的展示选项
+    ///
+    /// # 返回
+    /// 仍存在于磁盘上的文件列表，顺序与 `records` 一致
+    async fn registered_file_items(
+        records: Vec<RegisteredRecord>,
+        display: DisplayOptions,
+    ) -> Result<Vec<crate::models::file_system::FileItem>, String> {
+        tokio::task::spawn_blocking(move || {
+            let mut items = Vec::with_capacity(records.len());
+            for record in records {
+                let path = std::path::Path::new(&record.current_path);
+
+                // 检查文件是否存在
+                if !path.exists() {
+                    continue;
+                }
+
+                let mut item = FileSystemService::build_file_item(path, &display)?;
+                item.size = record.file_size as u64;
+                item.size_display = utils::format_size_with(record.file_size as u64, display.size_units);
+                item.registered_at = record.registered_at;
+                item.db_updated_at = record.db_updated_at;
+                items.push(item);
             }
-        }
+            Ok(items)
+        })
+        .await
+        .map_err(|e| format!("读取文件元数据任务失败: {}", e))?
     }
 
     /// PostgreSQL 实现：根据标签ID搜索文件
@@ -1148,10 +2735,9 @@ impl TagService {
         page: usize,
         page_size: usize,
         offset: usize,
+        display: DisplayOptions,
     ) -> Result<crate::models::file_system::SearchResult, String> {
-        use crate::models::file_system::{FileItem, SearchResult};
-        use std::path::Path;
-        use std::fs;
+        use crate::models::file_system::SearchResult;
 
         // 先查询总数
         let total_row = sqlx::query(
@@ -1178,13 +2764,16 @@ impl TagService {
                 f.file_type,
                 f.file_size,
                 f.created_at,
+                TO_CHAR(f.created_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS registered_at,
+                TO_CHAR(f.updated_at, 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS db_updated_at,
                 CASE WHEN f.file_type = 'folder' THEN 0 ELSE 1 END AS sort_priority
             FROM files f
             INNER JOIN file_tags ft ON f.id = ft.file_id
             WHERE ft.tag_id = $1 AND f.deleted_at IS NULL
             ORDER BY
                 sort_priority,
-                f.created_at DESC
+                f.created_at DESC,
+                f.id DESC
             LIMIT $2 OFFSET $3
         "#;
 
@@ -1196,61 +2785,16 @@ impl TagService {
             .await
             .map_err(|e| format!("查询文件列表失败: {}", e))?;
 
-        let mut items = Vec::new();
-        for row in rows {
-            let current_path: String = row.get("current_path");
-            let _file_type: String = row.get("file_type");
-            let file_size: i64 = row.get("file_size");
-
-            let path_obj = Path::new(&current_path);
-
-            // 检查文件是否存在
-            if !path_obj.exists() {
-                continue;
-            }
-
-            // 获取文件名
-            let name = path_obj
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("")
-                .to_string();
-
-            // 获取文件扩展名
-            let extension = path_obj
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|s| s.to_string());
-
-            // 获取文件元数据
-            let metadata = fs::metadata(path_obj)
-                .map_err(|e| format!("获取文件元数据失败 {}: {}", current_path, e))?;
-
-            let modified = metadata
-                .modified()
-                .map_err(|e| format!("获取修改时间失败: {}", e))?;
-            let created = metadata.created().unwrap_or(modified);
-
-            // 转换为 ISO 8601 格式
-            let modified_date = utils::format_iso8601(&modified);
-            let created_date = utils::format_iso8601(&created);
-
-            let is_hidden = name.starts_with('.');
-
-            let item = FileItem {
-                id: current_path.clone(),
-                name,
-                path: current_path,
-                file_type: if metadata.is_dir() { "folder".to_string() } else { "file".to_string() },
-                size: file_size as u64,
-                modified_date,
-                created_date,
-                extension,
-                is_hidden,
-            };
-
-            items.push(item);
-        }
+        let records = rows
+            .into_iter()
+            .map(|row| RegisteredRecord {
+                current_path: row.get("current_path"),
+                file_size: row.get("file_size"),
+                registered_at: row.get("registered_at"),
+                db_updated_at: row.get("db_updated_at"),
+            })
+            .collect();
+        let items = Self::registered_file_items(records, display).await?;
 
         let has_more = offset + items.len() < total;
 
@@ -1270,10 +2814,9 @@ impl TagService {
         page: usize,
         page_size: usize,
         offset: usize,
+        display: DisplayOptions,
     ) -> Result<crate::models::file_system::SearchResult, String> {
-        use crate::models::file_system::{FileItem, SearchResult};
-        use std::path::Path;
-        use std::fs;
+        use crate::models::file_system::SearchResult;
 
         // 先查询总数
         let total_row = sqlx::query(
@@ -1300,12 +2843,15 @@ impl TagService {
                 f.file_type,
                 f.file_size,
                 f.created_at,
+                datetime(f.created_at) AS registered_at,
+                datetime(f.updated_at) AS db_updated_at
             FROM files f
             INNER JOIN file_tags ft ON f.id = ft.file_id
             WHERE ft.tag_id = ?1 AND f.deleted_at IS NULL
             ORDER BY
                 CASE WHEN f.file_type = 'folder' THEN 0 ELSE 1 END,
-                f.created_at DESC
+                f.created_at DESC,
+                f.id DESC
             LIMIT ?2 OFFSET ?3
         "#;
 
@@ -1317,61 +2863,16 @@ impl TagService {
             .await
             .map_err(|e| format!("查询文件列表失败: {}", e))?;
 
-        let mut items = Vec::new();
-        for row in rows {
-            let current_path: String = row.get("current_path");
-            let _file_type: String = row.get("file_type");
-            let file_size: i64 = row.get("file_size");
-
-            let path_obj = Path::new(&current_path);
-
-            // 检查文件是否存在
-            if !path_obj.exists() {
-                continue;
-            }
-
-            // 获取文件名
-            let name = path_obj
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("")
-                .to_string();
-
-            // 获取文件扩展名
-            let extension = path_obj
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|s| s.to_string());
-
-            // 获取文件元数据
-            let metadata = fs::metadata(path_obj)
-                .map_err(|e| format!("获取文件元数据失败 {}: {}", current_path, e))?;
-
-            let modified = metadata
-                .modified()
-                .map_err(|e| format!("获取修改时间失败: {}", e))?;
-            let created = metadata.created().unwrap_or(modified);
-
-            // 转换为 ISO 8601 格式
-            let modified_date = utils::format_iso8601(&modified);
-            let created_date = utils::format_iso8601(&created);
-
-            let is_hidden = name.starts_with('.');
-
-            let item = FileItem {
-                id: current_path.clone(),
-                name,
-                path: current_path,
-                file_type: if metadata.is_dir() { "folder".to_string() } else { "file".to_string() },
-                size: file_size as u64,
-                modified_date,
-                created_date,
-                extension,
-                is_hidden,
-            };
-
-            items.push(item);
-        }
+        let records = rows
+            .into_iter()
+            .map(|row| RegisteredRecord {
+                current_path: row.get("current_path"),
+                file_size: row.get("file_size"),
+                registered_at: row.get("registered_at"),
+                db_updated_at: row.get("db_updated_at"),
+            })
+            .collect();
+        let items = Self::registered_file_items(records, display).await?;
 
         let has_more = offset + items.len() < total;
 
@@ -0,0 +1,3069 @@
+//! 服务模块测试
+//!
+//! 包含文件系统、标签和数据库服务的单元测试，需要数据库的测试使用 `test_support::temp_db` 创建的临时 SQLite 数据库
+
+use super::database::DatabaseService;
+use super::file_system::{CopyError, FileSystemService};
+use super::tag::TagService;
+use crate::config::global::{DisplayOptions, GlobalConfig, QueryDefaults};
+use crate::config::GlobalConfigManager;
+use crate::database::config::{DatabaseConfig, DatabaseType};
+use crate::models::file_system::{BatchOutcome, ConflictPolicy, CopyProgress, EntryFilter, FollowSymlinks, ShellKind, SortBy};
+use crate::models::tag::{Tag, TagSearchMode};
+use crate::database::GlobalDatabase;
+use crate::test_support::{insert_test_tag, temp_db};
+use crate::utils::CancellationToken;
+use sqlx::Row;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+#[cfg(unix)]
+#[test]
+fn test_symlink_flagged_and_not_recursed_with_never() {
+    let temp_dir = tempdir().unwrap();
+    let target_dir = temp_dir.path().join("target");
+    fs::create_dir(&target_dir).unwrap();
+    fs::write(target_dir.join("data.txt"), b"hello").unwrap();
+    std::os::unix::fs::symlink(&target_dir, temp_dir.path().join("link")).unwrap();
+
+    let root = temp_dir.path().to_str().unwrap();
+    let info = FileSystemService::list_directory(root, EntryFilter::All, None, false, &DisplayOptions::default()).unwrap();
+
+    let link = info.items.iter().find(|item| item.name == "link").unwrap();
+    assert!(link.is_symlink);
+    let target = info.items.iter().find(|item| item.name == "target").unwrap();
+    assert!(!target.is_symlink);
+
+    // Never：只统计真实目录中的文件，不进入符号链接
+    let size = FileSystemService::get_directory_size(root, Some(FollowSymlinks::Never)).unwrap();
+    assert_eq!(size, 5);
+
+    // Always：链接指向已访问过的目录，循环保护避免重复统计
+    let size = FileSystemService::get_directory_size(root, Some(FollowSymlinks::Always)).unwrap();
+    assert_eq!(size, 5);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_get_parent_chain_unix() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("a").join("b");
+    fs::create_dir_all(&nested).unwrap();
+
+    let chain = FileSystemService::get_parent_chain(nested.to_str().unwrap(), &DisplayOptions::default()).unwrap();
+
+    assert_eq!(chain.first().unwrap().path, "/");
+    assert_eq!(chain.first().unwrap().name, "/");
+    let names: Vec<&str> = chain.iter().rev().take(2).map(|item| item.name.as_str()).collect();
+    assert_eq!(names, vec!["b", "a"]);
+    assert_eq!(chain.last().unwrap().path, nested.to_str().unwrap());
+    assert!(chain.iter().all(|item| item.file_type == "folder"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_get_parent_chain_uses_hidden_detection() {
+    use crate::config::global::HiddenDetection;
+
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join(".cache").join("b");
+    fs::create_dir_all(&nested).unwrap();
+
+    let hidden_names = |hidden_detection| {
+        let display = DisplayOptions { hidden_detection, ..DisplayOptions::default() };
+        let chain = FileSystemService::get_parent_chain(nested.to_str().unwrap(), &display).unwrap();
+        chain.into_iter().filter(|item| item.is_hidden).map(|item| item.name).collect::<Vec<_>>()
+    };
+    let cache = ".cache".to_string();
+    assert!(hidden_names(HiddenDetection::DotPrefix).contains(&cache));
+    // 只按隐藏属性判断时，没有该属性的点开头文件夹不再标记为隐藏
+    assert!(!hidden_names(HiddenDetection::PlatformAttribute).contains(&cache));
+}
+
+#[cfg(windows)]
+#[test]
+fn test_get_parent_chain_windows() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("a").join("b");
+    fs::create_dir_all(&nested).unwrap();
+
+    let chain = FileSystemService::get_parent_chain(nested.to_str().unwrap(), &DisplayOptions::default()).unwrap();
+
+    // 第一项为驱动盘根目录，格式与 list_drives 一致
+    let root = chain.first().unwrap();
+    assert_eq!(root.name.len(), 2);
+    assert!(root.name.ends_with(':'));
+    assert_eq!(root.path, format!("{}\\", root.name));
+    let names: Vec<&str> = chain.iter().rev().take(2).map(|item| item.name.as_str()).collect();
+    assert_eq!(names, vec!["b", "a"]);
+}
+
+#[cfg(windows)]
+#[test]
+fn test_list_drives_filters_by_drive_type_windows() {
+    use crate::config::global::DriveType;
+
+    let all = FileSystemService::list_drives(&DisplayOptions::default()).unwrap();
+    let local_only = vec![DriveType::Fixed, DriveType::Removable, DriveType::Cdrom, DriveType::Ramdisk];
+
+    for drive_types in [vec![DriveType::Fixed], local_only, Vec::new()] {
+        let display = DisplayOptions { drive_types: drive_types.clone(), ..DisplayOptions::default() };
+        let filtered = FileSystemService::list_drives(&display).unwrap();
+
+        // 恰好保留类型在列表中（或无法识别类型）的驱动盘，顺序不变
+        let expected: Vec<&str> = all
+            .items
+            .iter()
+            .filter(|item| {
+                FileSystemService::drive_type(&item.path).is_none_or(|drive_type| drive_types.contains(&drive_type))
+            })
+            .map(|item| item.path.as_str())
+            .collect();
+        let actual: Vec<&str> = filtered.items.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(actual, expected);
+    }
+
+    // 系统盘是本地硬盘，只显示本地硬盘时仍然保留
+    let system_drive = format!("{}\\", std::env::var("SystemDrive").unwrap());
+    assert_eq!(FileSystemService::drive_type(&system_drive), Some(DriveType::Fixed));
+    let display = DisplayOptions { drive_types: vec![DriveType::Fixed], ..DisplayOptions::default() };
+    let fixed_only = FileSystemService::list_drives(&display).unwrap();
+    assert!(fixed_only.items.iter().any(|item| item.path == system_drive));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_drives_ignores_drive_types_on_unix() {
+    // Unix 没有驱动盘类型，挂载点列表不受 drive_types 影响
+    let display = DisplayOptions { drive_types: Vec::new(), ..DisplayOptions::default() };
+    let filtered = FileSystemService::list_drives(&display).unwrap();
+    let all = FileSystemService::list_drives(&DisplayOptions::default()).unwrap();
+
+    let paths = |info: &crate::models::file_system::DirectoryInfo| {
+        info.items.iter().map(|item| item.path.clone()).collect::<Vec<_>>()
+    };
+    assert_eq!(paths(&filtered), paths(&all));
+    assert!(paths(&filtered).contains(&"/".to_string()));
+}
+
+#[test]
+fn test_get_home_directory_resolution_order() {
+    let config_home = "/custom/home".to_string();
+    let path_var = std::env::var("PATH").unwrap();
+    let home_for = |sources: &[&str]| {
+        let global_config = GlobalConfigManager::new(GlobalConfig {
+            home_path: Some(config_home.clone()),
+            home_resolution: sources.iter().map(|source| source.to_string()).collect(),
+            ..GlobalConfig::default()
+        });
+        FileSystemService::get_home_directory(&global_config)
+    };
+
+    // 未设置的环境变量被跳过，由下一个来源生效
+    assert_eq!(home_for(&["env:FILEMGR_TEST_UNSET_HOME", "config", "env:PATH"]).unwrap(), config_home);
+    assert_eq!(home_for(&["env:PATH", "config"]).unwrap(), path_var);
+    assert_eq!(home_for(&["env:PATH+PATH"]).unwrap(), format!("{}{}", path_var, path_var));
+    assert_eq!(home_for(&["env:FILEMGR_TEST_UNSET_HOME"]).unwrap_err(), "无法获取用户主目录");
+    assert!(home_for(&["registry"]).unwrap_err().starts_with("未知的主目录来源"));
+}
+
+#[test]
+fn test_get_home_listing() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(temp_dir.path().join("welcome.txt"), "hi").unwrap();
+    let home = temp_dir.path().to_str().unwrap().to_string();
+    let listing_for = |sources: &[&str]| {
+        let global_config = GlobalConfigManager::new(GlobalConfig {
+            home_path: Some(home.clone()),
+            home_resolution: sources.iter().map(|source| source.to_string()).collect(),
+            ..GlobalConfig::default()
+        });
+        FileSystemService::get_home_listing(&global_config).unwrap()
+    };
+
+    let info = listing_for(&["config"]);
+    assert_eq!(info.path, home);
+    assert_eq!(info.items.len(), 1);
+    assert_eq!(info.items[0].name, "welcome.txt");
+
+    // 主目录无法解析时回退到根目录
+    #[cfg(unix)]
+    assert_eq!(listing_for(&["env:FILEMGR_TEST_UNSET_HOME"]).path, "/");
+}
+
+#[test]
+fn test_get_home_listing_from_env_home() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(temp_dir.path().join("notes.txt"), "hi").unwrap();
+    let home = temp_dir.path().to_str().unwrap().to_string();
+    let global_config = GlobalConfigManager::new(GlobalConfig {
+        home_resolution: vec!["env:HOME".to_string()],
+        ..GlobalConfig::default()
+    });
+
+    // 替换环境变量读取函数，不修改进程环境，避免影响并行运行的其它测试
+    let env_var = |name: &str| (name == "HOME").then(|| home.clone());
+    assert_eq!(FileSystemService::get_home_directory_with(&global_config, &env_var).unwrap(), home);
+    let info = FileSystemService::get_home_listing_with(&global_config, &env_var).unwrap();
+    assert_eq!(info.path, home);
+    assert_eq!(info.items.len(), 1);
+    assert_eq!(info.items[0].name, "notes.txt");
+}
+
+#[cfg(windows)]
+#[tokio::test]
+async fn test_long_path_copy_and_delete_windows() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::from_default();
+
+    // 构造超过 260 个字符的深层路径
+    let mut deep = db.dir().join("source");
+    for i in 0..12 {
+        deep = deep.join(format!("{:02}_{}", i, "x".repeat(20)));
+    }
+    assert!(deep.to_string_lossy().len() > 260);
+    fs::create_dir_all(crate::utils::to_long_path(&deep)).unwrap();
+    fs::write(crate::utils::to_long_path(&deep.join("data.txt")), b"deep").unwrap();
+
+    let target_dir = db.dir().join("target");
+    fs::create_dir(&target_dir).unwrap();
+    let source = db.dir().join("source");
+
+    FileSystemService::copy_files(
+        &db,
+        &global_config,
+        &[source.to_str().unwrap().to_string()],
+        target_dir.to_str().unwrap(),
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let copied = crate::utils::to_long_path(&target_dir.join(deep.strip_prefix(db.dir()).unwrap()));
+    assert_eq!(fs::read(copied.join("data.txt")).unwrap(), b"deep");
+
+    FileSystemService::delete_files(&db, &global_config, &[source.to_str().unwrap().to_string()]).await.unwrap();
+    assert!(!source.exists());
+}
+
+#[test]
+fn test_get_parent_chain_drives_sentinel() {
+    let chain = FileSystemService::get_parent_chain("drives:", &DisplayOptions::default()).unwrap();
+    assert!(chain.is_empty());
+}
+
+#[test]
+fn test_list_directory_drives_sentinel() {
+    let info = FileSystemService::list_directory("drives:", EntryFilter::All, None, false, &DisplayOptions::default()).unwrap();
+    assert_eq!(info.path, "drives:");
+    assert!(info.parent_path.is_none());
+    assert_eq!(info.total_files, 0);
+}
+
+#[test]
+fn test_unique_destination_skips_existing() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path();
+
+    // 不存在时直接使用原名
+    assert_eq!(FileSystemService::unique_destination(dir, "file.txt"), dir.join("file.txt"));
+
+    fs::write(dir.join("file.txt"), b"").unwrap();
+    assert_eq!(FileSystemService::unique_destination(dir, "file.txt"), dir.join("file (1).txt"));
+
+    fs::write(dir.join("file (1).txt"), b"").unwrap();
+    assert_eq!(FileSystemService::unique_destination(dir, "file.txt"), dir.join("file (2).txt"));
+}
+
+#[test]
+fn test_unique_destination_extensionless() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path();
+
+    fs::write(dir.join("README"), b"").unwrap();
+    assert_eq!(FileSystemService::unique_destination(dir, "README"), dir.join("README (1)"));
+
+    fs::create_dir(dir.join("folder")).unwrap();
+    assert_eq!(FileSystemService::unique_destination(dir, "folder"), dir.join("folder (1)"));
+}
+
+#[test]
+fn test_preview_text_small_file() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("note.txt");
+    fs::write(&file_path, "你好\nworld").unwrap();
+
+    let preview = FileSystemService::preview_text(file_path.to_str().unwrap(), 1024).unwrap();
+    assert_eq!(preview.content, "你好\nworld");
+    assert!(!preview.truncated);
+}
+
+#[test]
+fn test_preview_text_truncated() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("large.txt");
+    fs::write(&file_path, "a".repeat(4096)).unwrap();
+
+    let preview = FileSystemService::preview_text(file_path.to_str().unwrap(), 100).unwrap();
+    assert_eq!(preview.content.len(), 100);
+    assert!(preview.truncated);
+}
+
+#[test]
+fn test_preview_text_binary() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    fs::write(&file_path, [0x89, b'P', b'N', b'G', 0x00, 0x01]).unwrap();
+
+    let result = FileSystemService::preview_text(file_path.to_str().unwrap(), 1024);
+    assert_eq!(result.unwrap_err(), "不是文本文件");
+}
+
+#[test]
+fn test_generate_thumbnail() {
+    let temp_dir = tempdir().unwrap();
+    let image_path = temp_dir.path().join("wide.png");
+    image::RgbImage::from_pixel(40, 20, image::Rgb([200, 30, 30])).save(&image_path).unwrap();
+    let path = image_path.to_str().unwrap();
+
+    let bytes = FileSystemService::generate_thumbnail(path, 10).unwrap();
+    let thumbnail = image::load_from_memory(&bytes).unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (10, 5));
+
+    // 再次生成命中缓存，结果相同
+    assert_eq!(FileSystemService::generate_thumbnail(path, 10).unwrap(), bytes);
+
+    // 比目标尺寸小的图片不放大
+    let original = image::load_from_memory(&FileSystemService::generate_thumbnail(path, 100).unwrap()).unwrap();
+    assert_eq!((original.width(), original.height()), (40, 20));
+
+    let text_path = temp_dir.path().join("notes.txt");
+    fs::write(&text_path, "text").unwrap();
+    let error = FileSystemService::generate_thumbnail(text_path.to_str().unwrap(), 10).unwrap_err();
+    assert_eq!(error, "不支持的预览类型");
+}
+
+#[test]
+fn test_search_content() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("notes")).unwrap();
+    fs::write(root.join("notes").join("q3.txt"), "Draft of the Quarterly Report").unwrap();
+    fs::write(root.join("other.txt"), "nothing to see here").unwrap();
+    // 二进制文件即使包含关键词也不参与匹配
+    let mut binary = b"quarterly report".to_vec();
+    binary.extend_from_slice(&[0x00, 0x01, 0x02]);
+    fs::write(root.join("data.bin"), &binary).unwrap();
+    // 超过大小上限的文件被跳过
+    fs::write(root.join("large.txt"), format!("quarterly report{}", " ".repeat(1024))).unwrap();
+
+    let result = FileSystemService::search_content(root.to_str().unwrap(), "QUARTERLY report", 512, 100, &DisplayOptions::default()).unwrap();
+    let names: Vec<&str> = result.items.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(names, vec!["q3.txt"]);
+    assert!(!result.truncated);
+
+    let error = FileSystemService::search_content(root.to_str().unwrap(), "", 512, 100, &DisplayOptions::default()).unwrap_err();
+    assert_eq!(error, "搜索内容不能为空");
+}
+
+#[test]
+fn test_search_content_truncated_at_max_results() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("nested")).unwrap();
+    for i in 0..5 {
+        fs::write(root.join(format!("top{}.txt", i)), "needle").unwrap();
+        fs::write(root.join("nested").join(format!("deep{}.txt", i)), "needle").unwrap();
+    }
+
+    let result = FileSystemService::search_content(root.to_str().unwrap(), "needle", 512, 3, &DisplayOptions::default()).unwrap();
+    assert_eq!(result.items.len(), 3);
+    assert!(result.truncated);
+
+    // 匹配数量恰好等于上限时不算截断
+    let result = FileSystemService::search_content(root.to_str().unwrap(), "needle", 512, 10, &DisplayOptions::default()).unwrap();
+    assert_eq!(result.items.len(), 10);
+    assert!(!result.truncated);
+}
+
+#[test]
+fn test_list_directory_readonly_flag() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("locked.txt");
+    fs::write(&file_path, b"locked").unwrap();
+    fs::write(temp_dir.path().join("open.txt"), b"open").unwrap();
+
+    let mut permissions = fs::metadata(&file_path).unwrap().permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(&file_path, permissions).unwrap();
+
+    let info = FileSystemService::list_directory(temp_dir.path().to_str().unwrap(), EntryFilter::All, None, false, &DisplayOptions::default()).unwrap();
+    let locked = info.items.iter().find(|item| item.name == "locked.txt").unwrap();
+    let open = info.items.iter().find(|item| item.name == "open.txt").unwrap();
+    assert!(locked.readonly);
+    assert!(!open.readonly);
+
+    #[cfg(unix)]
+    {
+        assert_eq!(locked.mode.unwrap() & 0o222, 0);
+        assert_ne!(open.mode.unwrap() & 0o200, 0);
+    }
+    #[cfg(windows)]
+    assert!(locked.mode.is_none());
+}
+
+#[test]
+fn test_list_directory_paged() {
+    let temp_dir = tempdir().unwrap();
+    for i in 0..25 {
+        fs::write(temp_dir.path().join(format!("file{:02}.txt", i)), vec![0u8; 25 - i]).unwrap();
+    }
+    let path = temp_dir.path().to_str().unwrap();
+
+    let first = FileSystemService::list_directory_paged(path, 1, 10, None, &DisplayOptions::default()).unwrap();
+    assert_eq!(first.total, 25);
+    assert_eq!(first.items.len(), 10);
+    assert_eq!(first.items[0].name, "file00.txt");
+    assert!(first.has_more);
+
+    let last = FileSystemService::list_directory_paged(path, 3, 10, None, &DisplayOptions::default()).unwrap();
+    let names: Vec<&str> = last.items.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(names, ["file20.txt", "file21.txt", "file22.txt", "file23.txt", "file24.txt"]);
+    assert!(!last.has_more);
+
+    let by_size = FileSystemService::list_directory_paged(path, 1, 1, Some(SortBy::Size), &DisplayOptions::default()).unwrap();
+    assert_eq!(by_size.items[0].name, "file24.txt");
+
+    let beyond = FileSystemService::list_directory_paged(path, 4, 10, None, &DisplayOptions::default()).unwrap();
+    assert!(beyond.items.is_empty());
+    assert_eq!(beyond.total, 25);
+
+    let error = FileSystemService::list_directory_paged(path, 1, 0, None, &DisplayOptions::default()).unwrap_err();
+    assert_eq!(error, "每页数量必须大于0");
+}
+
+#[test]
+fn test_set_readonly_toggle() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("toggle.txt");
+    fs::write(&file_path, b"data").unwrap();
+    let path = file_path.to_str().unwrap();
+
+    // Unix 只切换所有者写权限位，Windows 切换只读属性
+    let is_readonly = |file_path: &std::path::Path| {
+        let permissions = fs::metadata(file_path).unwrap().permissions();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            permissions.mode() & 0o200 == 0
+        }
+        #[cfg(not(unix))]
+        {
+            permissions.readonly()
+        }
+    };
+
+    FileSystemService::set_readonly(path, true).unwrap();
+    assert!(is_readonly(&file_path));
+
+    FileSystemService::set_readonly(path, false).unwrap();
+    assert!(!is_readonly(&file_path));
+
+    assert!(FileSystemService::set_readonly(
+        temp_dir.path().join("missing.txt").to_str().unwrap(),
+        true
+    )
+    .is_err());
+}
+
+#[test]
+fn test_count_entries() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path();
+    fs::write(dir.join("a.txt"), b"").unwrap();
+    fs::write(dir.join("b.txt"), b"").unwrap();
+    fs::write(dir.join(".hidden"), b"").unwrap();
+    fs::create_dir(dir.join("folder")).unwrap();
+
+    let path = dir.to_str().unwrap();
+    assert_eq!(FileSystemService::count_entries(path, false).unwrap(), (2, 1));
+    assert_eq!(FileSystemService::count_entries(path, true).unwrap(), (3, 1));
+}
+
+#[tokio::test]
+async fn test_concurrent_sqlite_tagging_succeeds() {
+    let db = Arc::new(temp_db().await);
+    let tag_id = insert_test_tag(&db, "stress").await;
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap().clone();
+
+    let mut handles = Vec::new();
+    for index in 0..20 {
+        let file_path = db.dir().join(format!("file_{}.txt", index));
+        fs::write(&file_path, b"data").unwrap();
+        let path = file_path.to_str().unwrap().to_string();
+        let db = Arc::clone(&db);
+        handles.push(tokio::spawn(async move {
+            let global_config = GlobalConfigManager::from_default();
+            TagService::add_tags_to_files(&db, &global_config, vec![path], tag_id).await
+        }));
+    }
+
+    for handle in handles {
+        assert!(handle.await.unwrap().is_ok());
+    }
+
+    let usage_count: i32 = sqlx::query("SELECT usage_count FROM tags WHERE id = ?1")
+        .bind(tag_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("usage_count");
+    assert_eq!(usage_count, 20);
+}
+
+#[tokio::test]
+async fn test_write_file_creates_new_file() {
+    let db = temp_db().await;
+    let file_path = db.dir().join("new.txt");
+    let path = file_path.to_str().unwrap();
+
+    FileSystemService::write_file(&db, path, b"hello", true).await.unwrap();
+    assert_eq!(fs::read(&file_path).unwrap(), b"hello");
+}
+
+#[tokio::test]
+async fn test_write_file_overwrites_and_updates_record() {
+    let db = temp_db().await;
+    let file_path = db.dir().join("existing.txt");
+    let path = file_path.to_str().unwrap();
+    fs::write(&file_path, b"old").unwrap();
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    sqlx::query("INSERT INTO files (current_path, file_type, file_size) VALUES (?1, 'file', 3)")
+        .bind(path)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    FileSystemService::write_file(&db, path, b"new content", false).await.unwrap();
+    assert_eq!(fs::read(&file_path).unwrap(), b"new content");
+
+    let file_size: i64 = sqlx::query("SELECT file_size FROM files WHERE current_path = ?1")
+        .bind(path)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("file_size");
+    assert_eq!(file_size, 11);
+}
+
+#[tokio::test]
+async fn test_write_file_create_new_conflict() {
+    let db = temp_db().await;
+    let file_path = db.dir().join("taken.txt");
+    let path = file_path.to_str().unwrap();
+    fs::write(&file_path, b"original").unwrap();
+
+    let result = FileSystemService::write_file(&db, path, b"replacement", true).await;
+    assert!(result.is_err());
+    assert_eq!(fs::read(&file_path).unwrap(), b"original");
+}
+
+#[tokio::test]
+async fn test_add_tags_auto_registers_files() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "auto").await;
+    let file_path = db.dir().join("photo.png");
+    fs::write(&file_path, b"png").unwrap();
+    let path = file_path.to_str().unwrap().to_string();
+
+    let global_config = GlobalConfigManager::from_default();
+    TagService::add_tags_to_files(&db, &global_config, vec![path.clone()], tag_id)
+        .await
+        .unwrap();
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let count: i64 = sqlx::query("SELECT COUNT(*) AS count FROM files WHERE current_path = ?1")
+        .bind(&path)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(count, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_add_tags_to_many_files_completes() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "bulk").await;
+    let files_dir = db.dir().join("files");
+    fs::create_dir(&files_dir).unwrap();
+
+    let paths: Vec<String> = (0..200)
+        .map(|i| {
+            let file_path = files_dir.join(format!("file{}.txt", i));
+            fs::write(&file_path, b"data").unwrap();
+            file_path.to_str().unwrap().to_string()
+        })
+        .collect();
+
+    // 打标签期间另一个任务仍能被调度执行
+    let ticker = tokio::spawn(async {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    });
+
+    let global_config = GlobalConfigManager::from_default();
+    let started = std::time::Instant::now();
+    TagService::add_tags_to_files(&db, &global_config, paths, tag_id)
+        .await
+        .unwrap();
+    assert!(started.elapsed() < std::time::Duration::from_secs(30));
+    ticker.await.unwrap();
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let count: i64 = sqlx::query("SELECT COUNT(*) AS count FROM file_tags WHERE tag_id = ?1")
+        .bind(tag_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(count, 200);
+}
+
+#[tokio::test]
+async fn test_search_files_by_tag_does_not_block_runtime() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Duration, Instant};
+
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "bulk").await;
+    let files_dir = db.dir().join("files");
+    fs::create_dir(&files_dir).unwrap();
+
+    let paths: Vec<String> = (0..3000)
+        .map(|i| {
+            let file_path = files_dir.join(format!("file{}.txt", i));
+            fs::write(&file_path, b"data").unwrap();
+            file_path.to_str().unwrap().to_string()
+        })
+        .collect();
+
+    {
+        let connection = db.get_connection().await.unwrap();
+        let pool = connection.as_sqlite().unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        for path in &paths {
+            let file_id: i32 = sqlx::query_scalar(
+                "INSERT INTO files (current_path, file_type, file_size) VALUES (?1, 'file', 4) RETURNING id",
+            )
+            .bind(path)
+            .fetch_one(&mut *tx)
+            .await
+            .unwrap();
+            sqlx::query("INSERT INTO file_tags (file_id, tag_id) VALUES (?1, ?2)")
+                .bind(file_id)
+                .bind(tag_id)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+        tx.commit().await.unwrap();
+    }
+
+    // 同步读取全部元数据的耗时：若搜索在运行时线程上逐条读取，心跳间隔至少为这么长
+    let started = Instant::now();
+    for path in &paths {
+        FileSystemService::build_file_item(Path::new(path), &DisplayOptions::default()).unwrap();
+    }
+    let build_cost = started.elapsed();
+
+    // 单线程运行时上的心跳任务，记录两次被调度之间的最大间隔
+    let stop = Arc::new(AtomicBool::new(false));
+    let heartbeat = tokio::spawn({
+        let stop = stop.clone();
+        async move {
+            let mut last = Instant::now();
+            let mut max_gap = Duration::ZERO;
+            while !stop.load(Ordering::Relaxed) {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                let now = Instant::now();
+                max_gap = max_gap.max(now - last);
+                last = now;
+            }
+            max_gap
+        }
+    });
+    tokio::task::yield_now().await;
+
+    let global_config = GlobalConfigManager::from_default();
+    let result = TagService::search_files_by_tag(&db, &global_config, tag_id, None, Some(paths.len()))
+        .await
+        .unwrap();
+    stop.store(true, Ordering::Relaxed);
+    let max_gap = heartbeat.await.unwrap();
+
+    assert_eq!(result.items.len(), paths.len());
+    assert!(
+        max_gap < build_cost,
+        "心跳最大间隔 {:?} 不小于元数据读取耗时 {:?}，运行时线程被阻塞",
+        max_gap,
+        build_cost
+    );
+}
+
+#[tokio::test]
+async fn test_add_tags_requires_registration_when_disabled() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "manual").await;
+    let file_path = db.dir().join("photo.png");
+    fs::write(&file_path, b"png").unwrap();
+    let path = file_path.to_str().unwrap().to_string();
+
+    let global_config = GlobalConfigManager::new(GlobalConfig {
+        auto_register_files: false,
+        ..GlobalConfig::default()
+    });
+
+    // 未登记的文件不能添加标签
+    let result = TagService::add_tags_to_files(&db, &global_config, vec![path.clone()], tag_id).await;
+    assert!(result.unwrap_err().starts_with("文件未注册"));
+
+    // 登记后可以正常添加
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    sqlx::query("INSERT INTO files (current_path, file_type, file_size) VALUES (?1, 'file', 3)")
+        .bind(&path)
+        .execute(pool)
+        .await
+        .unwrap();
+    TagService::add_tags_to_files(&db, &global_config, vec![path], tag_id)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_rename_folder_updates_child_paths() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "child").await;
+
+    let folder = db.dir().join("album");
+    fs::create_dir(&folder).unwrap();
+    let child = folder.join("photo.png");
+    fs::write(&child, b"png").unwrap();
+
+    let global_config = GlobalConfigManager::from_default();
+    TagService::add_tags_to_files(
+        &db,
+        &global_config,
+        vec![child.to_str().unwrap().to_string()],
+        tag_id,
+    )
+    .await
+    .unwrap();
+
+    FileSystemService::rename_file(&db, folder.to_str().unwrap(), "album_2024")
+        .await
+        .unwrap();
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let current_path: String = sqlx::query("SELECT current_path FROM files")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("current_path");
+    let expected = db.dir().join("album_2024").join("photo.png");
+    assert_eq!(current_path, expected.to_str().unwrap());
+}
+
+#[tokio::test]
+async fn test_delete_folder_cascades_soft_delete() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "nested").await;
+
+    let folder = db.dir().join("trip");
+    let nested = folder.join("day1");
+    fs::create_dir_all(&nested).unwrap();
+    let child = nested.join("photo.png");
+    fs::write(&child, b"png").unwrap();
+
+    let global_config = GlobalConfigManager::from_default();
+    TagService::add_tags_to_files(
+        &db,
+        &global_config,
+        vec![child.to_str().unwrap().to_string()],
+        tag_id,
+    )
+    .await
+    .unwrap();
+
+    FileSystemService::delete_files(&db, &global_config, &[folder.to_str().unwrap().to_string()])
+        .await
+        .unwrap();
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let live_count: i64 = sqlx::query("SELECT COUNT(*) AS count FROM files WHERE deleted_at IS NULL")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(live_count, 0);
+
+    let usage_count: i32 = sqlx::query("SELECT usage_count FROM tags WHERE id = ?1")
+        .bind(tag_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("usage_count");
+    assert_eq!(usage_count, 0);
+}
+
+#[tokio::test]
+async fn test_delete_files_each_reports_per_path() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "draft").await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let good = db.dir().join("good.txt");
+    fs::write(&good, b"good").unwrap();
+    let good = good.to_str().unwrap().to_string();
+    let missing = db.dir().join("missing.txt").to_str().unwrap().to_string();
+    TagService::add_tags_to_files(&db, &global_config, vec![good.clone()], tag_id)
+        .await
+        .unwrap();
+
+    let results = FileSystemService::delete_files_each(&db, &global_config, &[good.clone(), missing.clone()])
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0], (good.clone(), Ok(())));
+    assert_eq!(results[1].0, missing);
+    assert!(results[1].1.as_ref().unwrap_err().starts_with("路径不存在"));
+    assert!(!Path::new(&good).exists());
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let deleted: bool = sqlx::query("SELECT deleted_at IS NOT NULL AS deleted FROM files WHERE current_path = ?1")
+        .bind(&good)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("deleted");
+    assert!(deleted);
+}
+
+#[tokio::test]
+async fn test_get_tag_list_uses_configured_default_limit() {
+    let db = temp_db().await;
+    for name in ["alpha", "beta", "gamma"] {
+        insert_test_tag(&db, name).await;
+    }
+
+    let global_config = GlobalConfigManager::new(GlobalConfig {
+        query_defaults: QueryDefaults {
+            tag_list_limit: 2,
+            ..QueryDefaults::default()
+        },
+        ..GlobalConfig::default()
+    });
+
+    let tags = TagService::get_tag_list(&db, &global_config, None, None)
+        .await
+        .unwrap();
+    assert_eq!(tags.len(), 2);
+
+    // 显式传入的参数优先于配置
+    let tags = TagService::get_tag_list(&db, &global_config, Some(3), None)
+        .await
+        .unwrap();
+    assert_eq!(tags.len(), 3);
+}
+
+#[tokio::test]
+async fn test_search_tags_case_sensitivity() {
+    let db = temp_db().await;
+    for name in ["Work", "homework", "WORKSHOP"] {
+        insert_test_tag(&db, name).await;
+    }
+    let global_config = GlobalConfigManager::from_default();
+
+    let search = |keyword: &'static str, case_sensitive: bool| {
+        let db = &db;
+        let global_config = &global_config;
+        async move {
+            let mut names: Vec<String> = TagService::search_tags(
+                db,
+                global_config,
+                keyword.to_string(),
+                None,
+                Some(case_sensitive),
+                None,
+            )
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+            names.sort();
+            names
+        }
+    };
+
+    // 不区分大小写时匹配全部
+    assert_eq!(search("work", false).await, vec!["WORKSHOP", "Work", "homework"]);
+
+    // 区分大小写时只匹配字面一致的标签
+    assert_eq!(search("work", true).await, vec!["homework"]);
+    assert_eq!(search("Work", true).await, vec!["Work"]);
+
+    // 关键词中的 GLOB 通配符按字面匹配
+    assert!(search("W*", true).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_search_tags_escapes_like_wildcards() {
+    let db = temp_db().await;
+    for name in ["50%off", "5000", "a_b", "axb"] {
+        insert_test_tag(&db, name).await;
+    }
+    let global_config = GlobalConfigManager::from_default();
+
+    let tags = TagService::search_tags(&db, &global_config, "50%".to_string(), None, None, None)
+        .await
+        .unwrap();
+    let names: Vec<String> = tags.into_iter().map(|tag| tag.name).collect();
+    assert_eq!(names, vec!["50%off"]);
+
+    let tags = TagService::search_tags(&db, &global_config, "a_b".to_string(), None, None, None)
+        .await
+        .unwrap();
+    let names: Vec<String> = tags.into_iter().map(|tag| tag.name).collect();
+    assert_eq!(names, vec!["a_b"]);
+}
+
+#[tokio::test]
+async fn test_search_tags_prefix_mode() {
+    let db = temp_db().await;
+    for name in ["work", "homework"] {
+        insert_test_tag(&db, name).await;
+    }
+    let global_config = GlobalConfigManager::from_default();
+
+    for case_sensitive in [false, true] {
+        let tags = TagService::search_tags(
+            &db,
+            &global_config,
+            "work".to_string(),
+            None,
+            Some(case_sensitive),
+            Some(TagSearchMode::Prefix),
+        )
+        .await
+        .unwrap();
+        let names: Vec<String> = tags.into_iter().map(|tag| tag.name).collect();
+        assert_eq!(names, vec!["work"]);
+    }
+}
+
+#[tokio::test]
+async fn test_move_file_keeps_tags() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "moved").await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let source_dir = db.dir().join("source");
+    let target_dir = db.dir().join("target");
+    fs::create_dir(&source_dir).unwrap();
+    fs::create_dir(&target_dir).unwrap();
+    let file_path = source_dir.join("photo.png");
+    fs::write(&file_path, b"png").unwrap();
+
+    TagService::add_tags_to_files(
+        &db,
+        &global_config,
+        vec![file_path.to_str().unwrap().to_string()],
+        tag_id,
+    )
+    .await
+    .unwrap();
+
+    FileSystemService::move_files(
+        &db,
+        &[file_path.to_str().unwrap().to_string()],
+        target_dir.to_str().unwrap(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let result = TagService::search_files_by_tag(&db, &global_config, tag_id, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].path, target_dir.join("photo.png").to_str().unwrap());
+}
+
+#[tokio::test]
+async fn test_move_files_overwrite_replaces_destination() {
+    let db = temp_db().await;
+    let source_dir = db.dir().join("source");
+    let target_dir = db.dir().join("target");
+    fs::create_dir(&source_dir).unwrap();
+    fs::create_dir(&target_dir).unwrap();
+    let file_path = source_dir.join("notes.txt");
+    fs::write(&file_path, b"new").unwrap();
+    fs::write(target_dir.join("notes.txt"), b"old").unwrap();
+
+    FileSystemService::move_files(
+        &db,
+        &[file_path.to_str().unwrap().to_string()],
+        target_dir.to_str().unwrap(),
+        Some(ConflictPolicy::Overwrite),
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert!(!file_path.exists());
+    assert_eq!(fs::read(target_dir.join("notes.txt")).unwrap(), b"new");
+    // 临时备份在移动成功后删除
+    assert_eq!(fs::read_dir(&target_dir).unwrap().count(), 1);
+}
+
+#[test]
+fn test_move_replacing_failure_keeps_destination() {
+    let temp_dir = tempdir().unwrap();
+    let dest = temp_dir.path().join("notes.txt");
+    fs::write(&dest, b"old").unwrap();
+    let dest_dir = temp_dir.path().join("album");
+    fs::create_dir(&dest_dir).unwrap();
+    fs::write(dest_dir.join("photo.png"), b"png").unwrap();
+
+    // 源不存在，移动必然失败，原目标应恢复原样
+    let missing = temp_dir.path().join("missing.txt");
+    assert!(FileSystemService::move_replacing(&missing, &dest, false, false).is_err());
+    assert_eq!(fs::read(&dest).unwrap(), b"old");
+
+    let missing_dir = temp_dir.path().join("missing_dir");
+    assert!(FileSystemService::move_replacing(&missing_dir, &dest_dir, true, true).is_err());
+    assert_eq!(fs::read(dest_dir.join("photo.png")).unwrap(), b"png");
+
+    // 没有留下临时备份
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+}
+
+#[tokio::test]
+async fn test_move_many_files_updates_paths_in_batch() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    fs::File::create(&db_path).unwrap();
+    let mut config = DatabaseConfig::new(
+        DatabaseType::Sqlite,
+        "test".to_string(),
+        None,
+        None,
+        None,
+        None,
+        Some(db_path.to_str().unwrap().to_string()),
+    );
+    // 只用一个连接，提交钩子才能统计到所有语句
+    config.max_connections = 1;
+    let db = GlobalDatabase::new(config);
+    db.init().await.unwrap();
+    db.migrate().await.unwrap();
+    let connection = db.get_connection().await.unwrap();
+
+    let tag_id = insert_test_tag(&db, "bulk").await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let source_dir = temp_dir.path().join("source");
+    let target_dir = temp_dir.path().join("target");
+    fs::create_dir(&source_dir).unwrap();
+    fs::create_dir(&target_dir).unwrap();
+    let paths: Vec<String> = (0..20)
+        .map(|i| {
+            let path = source_dir.join(format!("file_{:02}.txt", i));
+            fs::write(&path, b"data").unwrap();
+            path.to_str().unwrap().to_string()
+        })
+        .collect();
+    TagService::add_tags_to_files(&db, &global_config, paths.clone(), tag_id).await.unwrap();
+
+    // 自动提交模式下每条写语句提交一次，用提交钩子统计 move_files 实际执行的写语句数
+    let commits = Arc::new(AtomicUsize::new(0));
+    {
+        let counter = Arc::clone(&commits);
+        let mut conn = connection.as_sqlite().unwrap().acquire().await.unwrap();
+        conn.lock_handle().await.unwrap().set_commit_hook(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            true
+        });
+    }
+
+    FileSystemService::move_files(&db, &paths, target_dir.to_str().unwrap(), None, None)
+        .await
+        .unwrap();
+
+    // 20 个文件：一条批量 UPDATE，加上操作日志的首条记录和其余记录各一条 INSERT
+    assert_eq!(commits.load(Ordering::SeqCst), 3);
+
+    let stored: Vec<String> = sqlx::query("SELECT current_path FROM files ORDER BY current_path")
+        .fetch_all(connection.as_sqlite().unwrap())
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get("current_path"))
+        .collect();
+    let expected: Vec<String> = (0..20)
+        .map(|i| target_dir.join(format!("file_{:02}.txt", i)).to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(stored, expected);
+
+    let logged: i64 = sqlx::query("SELECT COUNT(DISTINCT COALESCE(batch_id, id)) AS batches FROM file_operations")
+        .fetch_one(connection.as_sqlite().unwrap())
+        .await
+        .unwrap()
+        .get("batches");
+    assert_eq!(logged, 1);
+}
+
+#[tokio::test]
+async fn test_move_folder_with_rename_policy() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "album").await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let folder = db.dir().join("album");
+    fs::create_dir(&folder).unwrap();
+    let child = folder.join("photo.png");
+    fs::write(&child, b"png").unwrap();
+
+    // 目标目录中已存在同名文件夹
+    let target_dir = db.dir().join("target");
+    fs::create_dir_all(target_dir.join("album")).unwrap();
+
+    TagService::add_tags_to_files(
+        &db,
+        &global_config,
+        vec![child.to_str().unwrap().to_string()],
+        tag_id,
+    )
+    .await
+    .unwrap();
+
+    let paths = [folder.to_str().unwrap().to_string()];
+    let error = FileSystemService::move_files(&db, &paths, target_dir.to_str().unwrap(), None, None)
+        .await
+        .unwrap_err();
+    assert!(error.starts_with("目标路径已存在"));
+
+    FileSystemService::move_files(
+        &db,
+        &paths,
+        target_dir.to_str().unwrap(),
+        Some(ConflictPolicy::Rename),
+        None,
+    )
+    .await
+    .unwrap();
+
+    let expected = target_dir.join("album (1)").join("photo.png");
+    assert!(expected.exists());
+    let result = TagService::search_files_by_tag(&db, &global_config, tag_id, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.items[0].path, expected.to_str().unwrap());
+}
+
+#[tokio::test]
+async fn test_cut_then_copy_folder_keeps_tags() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "trip").await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let folder = db.dir().join("trip");
+    fs::create_dir(&folder).unwrap();
+    let child = folder.join("photo.png");
+    fs::write(&child, b"png").unwrap();
+    let moved_dir = db.dir().join("moved");
+    let copied_dir = db.dir().join("copied");
+    fs::create_dir(&moved_dir).unwrap();
+    fs::create_dir(&copied_dir).unwrap();
+
+    TagService::add_tags_to_files(
+        &db,
+        &global_config,
+        vec![child.to_str().unwrap().to_string()],
+        tag_id,
+    )
+    .await
+    .unwrap();
+
+    // 剪切后标签仍然指向新路径
+    FileSystemService::cut_files(&db, &[folder.to_str().unwrap().to_string()], moved_dir.to_str().unwrap())
+        .await
+        .unwrap();
+    let moved_child = moved_dir.join("trip").join("photo.png");
+    let result = TagService::search_files_by_tag(&db, &global_config, tag_id, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].path, moved_child.to_str().unwrap());
+
+    // 复制文件夹后，子文件的标签同样复制到新文件
+    FileSystemService::copy_files(
+        &db,
+        &global_config,
+        &[moved_dir.join("trip").to_str().unwrap().to_string()],
+        copied_dir.to_str().unwrap(),
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let copied_child = copied_dir.join("trip").join("photo.png");
+    let result = TagService::search_files_by_tag(&db, &global_config, tag_id, None, None)
+        .await
+        .unwrap();
+    let mut paths: Vec<String> = result.items.into_iter().map(|item| item.path).collect();
+    paths.sort();
+    let mut expected = vec![
+        copied_child.to_str().unwrap().to_string(),
+        moved_child.to_str().unwrap().to_string(),
+    ];
+    expected.sort();
+    assert_eq!(paths, expected);
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let usage_count: i32 = sqlx::query("SELECT usage_count FROM tags WHERE id = ?1")
+        .bind(tag_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("usage_count");
+    assert_eq!(usage_count, 2);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_copy_symlink_cycle_errors() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::from_default();
+
+    // loop/inner/back -> loop，跟随时形成循环
+    let source = db.dir().join("loop");
+    fs::create_dir_all(source.join("inner")).unwrap();
+    std::os::unix::fs::symlink(&source, source.join("inner").join("back")).unwrap();
+    let target_dir = db.dir().join("target");
+    fs::create_dir(&target_dir).unwrap();
+
+    let error = FileSystemService::copy_files(
+        &db,
+        &global_config,
+        &[source.to_str().unwrap().to_string()],
+        target_dir.to_str().unwrap(),
+        Some(FollowSymlinks::Always),
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap_err();
+    assert!(error.starts_with("检测到符号链接循环"));
+}
+
+#[tokio::test]
+async fn test_copy_directory_depth_limit() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::new(GlobalConfig {
+        max_directory_depth: 2,
+        ..GlobalConfig::default()
+    });
+
+    let source = db.dir().join("deep");
+    fs::create_dir_all(source.join("a").join("b").join("c")).unwrap();
+    let target_dir = db.dir().join("target");
+    fs::create_dir(&target_dir).unwrap();
+
+    let error = FileSystemService::copy_files(
+        &db,
+        &global_config,
+        &[source.to_str().unwrap().to_string()],
+        target_dir.to_str().unwrap(),
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap_err();
+    assert!(error.starts_with("目录层级过深"));
+}
+
+#[tokio::test]
+async fn test_copy_files_with_verify() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let source = db.dir().join("data.bin");
+    fs::write(&source, vec![7u8; 200 * 1024]).unwrap();
+    let target_dir = db.dir().join("target");
+    fs::create_dir(&target_dir).unwrap();
+
+    FileSystemService::copy_files(
+        &db,
+        &global_config,
+        &[source.to_str().unwrap().to_string()],
+        target_dir.to_str().unwrap(),
+        None,
+        Some(true),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(fs::read(target_dir.join("data.bin")).unwrap(), fs::read(&source).unwrap());
+}
+
+#[test]
+fn test_verify_copy_detects_corruption() {
+    let temp_dir = tempdir().unwrap();
+    let source = temp_dir.path().join("data.bin");
+    let dest = temp_dir.path().join("copy.bin");
+    fs::write(&source, b"original").unwrap();
+    // 模拟复制过程中损坏的目标文件
+    fs::write(&dest, b"originaX").unwrap();
+
+    let error = FileSystemService::verify_copy(&source, &dest).unwrap_err();
+    assert!(error.starts_with("文件校验失败"));
+    assert!(!dest.exists());
+}
+
+#[tokio::test]
+async fn test_add_multiple_tags_to_files() {
+    let db = temp_db().await;
+    let first_tag = insert_test_tag(&db, "first").await;
+    let second_tag = insert_test_tag(&db, "second").await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let mut paths = Vec::new();
+    for name in ["a.txt", "b.txt"] {
+        let file_path = db.dir().join(name);
+        fs::write(&file_path, b"text").unwrap();
+        paths.push(file_path.to_str().unwrap().to_string());
+    }
+
+    TagService::add_multiple_tags_to_files(&db, &global_config, paths, vec![first_tag, second_tag])
+        .await
+        .unwrap();
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let associations: i64 = sqlx::query("SELECT COUNT(*) AS count FROM file_tags")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(associations, 4);
+
+    for tag_id in [first_tag, second_tag] {
+        let usage_count: i32 = sqlx::query("SELECT usage_count FROM tags WHERE id = ?1")
+            .bind(tag_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+            .get("usage_count");
+        assert_eq!(usage_count, 2);
+    }
+
+    // 不存在的标签在执行前即被拒绝
+    let result = TagService::add_multiple_tags_to_files(&db, &global_config, vec![], vec![first_tag, 9999]).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_clear_tags_from_files() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::from_default();
+    let mut tag_ids = Vec::new();
+    for name in ["red", "green", "blue"] {
+        tag_ids.push(insert_test_tag(&db, name).await);
+    }
+
+    let file_path = db.dir().join("photo.png");
+    fs::write(&file_path, b"png").unwrap();
+    let path = file_path.to_str().unwrap().to_string();
+    TagService::add_multiple_tags_to_files(&db, &global_config, vec![path.clone()], tag_ids.clone())
+        .await
+        .unwrap();
+
+    // 未登记的路径会被跳过
+    let unregistered = db.dir().join("other.png").to_str().unwrap().to_string();
+    TagService::clear_tags_from_files(&db, &global_config, vec![path, unregistered])
+        .await
+        .unwrap();
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let associations: i64 = sqlx::query("SELECT COUNT(*) AS count FROM file_tags")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(associations, 0);
+
+    for tag_id in tag_ids {
+        let usage_count: i32 = sqlx::query("SELECT usage_count FROM tags WHERE id = ?1")
+            .bind(tag_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+            .get("usage_count");
+        assert_eq!(usage_count, 0);
+    }
+}
+
+#[tokio::test]
+async fn test_reconcile_soft_deletes_missing_files() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "reconcile").await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let kept = db.dir().join("kept.txt");
+    let removed = db.dir().join("removed.txt");
+    fs::write(&kept, b"kept").unwrap();
+    fs::write(&removed, b"removed").unwrap();
+    let kept_path = kept.to_str().unwrap().to_string();
+    let removed_path = removed.to_str().unwrap().to_string();
+    TagService::add_tags_to_files(&db, &global_config, vec![kept_path, removed_path.clone()], tag_id)
+        .await
+        .unwrap();
+
+    // 绕过应用直接在磁盘上删除
+    fs::remove_file(&removed).unwrap();
+
+    let report = FileSystemService::reconcile(&db).await.unwrap();
+    assert_eq!(report.checked, 2);
+    assert_eq!(report.removed, 1);
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let deleted_paths: Vec<String> = sqlx::query_scalar("SELECT current_path FROM files WHERE deleted_at IS NOT NULL")
+        .fetch_all(pool)
+        .await
+        .unwrap();
+    assert_eq!(deleted_paths, vec![removed_path]);
+
+    let usage_count: i32 = sqlx::query_scalar("SELECT usage_count FROM tags WHERE id = ?1")
+        .bind(tag_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    assert_eq!(usage_count, 1);
+}
+
+#[tokio::test]
+async fn test_search_registered_files_pagination() {
+    let db = temp_db().await;
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+
+    let mut names: Vec<String> = (0..5).map(|i| format!("report_{}.txt", i)).collect();
+    names.push("notes.txt".to_string());
+    for name in &names {
+        let path = db.dir().join(name);
+        fs::write(&path, b"data").unwrap();
+        sqlx::query("INSERT INTO files (current_path, file_type, file_size) VALUES (?1, 'file', 4)")
+            .bind(path.to_str().unwrap())
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    let first = FileSystemService::search_registered_files(&db, "REPORT_", 1, 2, &DisplayOptions::default()).await.unwrap();
+    assert_eq!(first.total, 5);
+    assert_eq!(first.page, 1);
+    assert_eq!(first.page_size, 2);
+    let first_names: Vec<&str> = first.items.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(first_names, ["report_0.txt", "report_1.txt"]);
+    assert!(first.has_more);
+
+    let last = FileSystemService::search_registered_files(&db, "report_", 3, 2, &DisplayOptions::default()).await.unwrap();
+    assert_eq!(last.total, 5);
+    assert_eq!(last.items.len(), 1);
+    assert_eq!(last.items[0].name, "report_4.txt");
+    assert!(!last.has_more);
+
+    // `_` 按字面匹配，不作为通配符
+    let literal = FileSystemService::search_registered_files(&db, "t_", 1, 10, &DisplayOptions::default()).await.unwrap();
+    assert_eq!(literal.total, 5);
+}
+
+#[tokio::test]
+async fn test_search_files_by_tag_pagination() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "busy").await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let files_dir = db.dir().join("files");
+    fs::create_dir(&files_dir).unwrap();
+    let paths: Vec<String> = (0..15)
+        .map(|i| {
+            let path = files_dir.join(format!("file_{:02}.txt", i));
+            fs::write(&path, b"data").unwrap();
+            path.to_str().unwrap().to_string()
+        })
+        .collect();
+    TagService::add_tags_to_files(&db, &global_config, paths.clone(), tag_id).await.unwrap();
+
+    let mut seen = Vec::new();
+    for (page, expected_len, expected_more) in [(1, 6, true), (2, 6, true), (3, 3, false)] {
+        let result = TagService::search_files_by_tag(&db, &global_config, tag_id, Some(page), Some(6))
+            .await
+            .unwrap();
+        assert_eq!(result.total, 15);
+        assert_eq!(result.page, page);
+        assert_eq!(result.items.len(), expected_len);
+        assert_eq!(result.has_more, expected_more);
+        seen.extend(result.items.into_iter().map(|item| item.path));
+    }
+
+    // 各页互不重叠，合起来正好是全部文件
+    seen.sort();
+    assert_eq!(seen, paths);
+
+    let beyond = TagService::search_files_by_tag(&db, &global_config, tag_id, Some(4), Some(6))
+        .await
+        .unwrap();
+    assert_eq!(beyond.total, 15);
+    assert!(beyond.items.is_empty());
+    assert!(!beyond.has_more);
+}
+
+#[tokio::test]
+async fn test_registered_timestamps_only_from_database_listings() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "timestamps").await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let files_dir = db.dir().join("files");
+    fs::create_dir(&files_dir).unwrap();
+    let registered = files_dir.join("registered.txt");
+    fs::write(&registered, b"registered").unwrap();
+    fs::write(files_dir.join("unregistered.txt"), b"unregistered").unwrap();
+    TagService::add_tags_to_files(&db, &global_config, vec![registered.to_str().unwrap().to_string()], tag_id)
+        .await
+        .unwrap();
+
+    let result = TagService::search_files_by_tag(&db, &global_config, tag_id, None, None).await.unwrap();
+    assert_eq!(result.items.len(), 1);
+    assert!(result.items[0].registered_at.is_some());
+    assert!(result.items[0].db_updated_at.is_some());
+
+    // 文件系统列表不查询数据库，登记与未登记的文件都没有数据库时间
+    let info = FileSystemService::list_directory(files_dir.to_str().unwrap(), EntryFilter::All, None, false, &DisplayOptions::default()).unwrap();
+    assert_eq!(info.items.len(), 2);
+    assert!(info.items.iter().all(|item| item.registered_at.is_none() && item.db_updated_at.is_none()));
+}
+
+#[tokio::test]
+async fn test_find_by_name_matches_last_component() {
+    let db = temp_db().await;
+
+    let first = db.dir().join("2023").join("report.pdf");
+    let second = db.dir().join("2024").join("report.pdf");
+    let other = db.dir().join("2024").join("old_report.pdf");
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    for path in [&first, &second, &other] {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"pdf").unwrap();
+        sqlx::query("INSERT INTO files (current_path, file_type, file_size) VALUES (?1, 'file', 3)")
+            .bind(path.to_str().unwrap())
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    let items = FileSystemService::find_by_name(&db, "report.pdf", &DisplayOptions::default()).await.unwrap();
+    let paths: Vec<String> = items.into_iter().map(|item| item.path).collect();
+    assert_eq!(
+        paths,
+        vec![
+            first.to_str().unwrap().to_string(),
+            second.to_str().unwrap().to_string(),
+        ]
+    );
+
+    assert!(FileSystemService::find_by_name(&db, "2024/report.pdf", &DisplayOptions::default()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_tag_activity_groups_by_day() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "daily").await;
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let associations = [
+        ("/a.txt", "2024-03-01 09:00:00"),
+        ("/b.txt", "2024-03-01 18:30:00"),
+        ("/c.txt", "2024-03-02 08:15:00"),
+    ];
+    for (path, created_at) in associations {
+        sqlx::query("INSERT INTO files (current_path, file_type, file_size) VALUES (?1, 'file', 0)")
+            .bind(path)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO file_tags (file_id, tag_id, created_at) \
+             SELECT id, ?2, ?3 FROM files WHERE current_path = ?1",
+        )
+        .bind(path)
+        .bind(tag_id)
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    let activity = TagService::tag_activity(&db, &GlobalConfigManager::from_default(), tag_id).await.unwrap();
+    assert_eq!(
+        activity,
+        vec![
+            ("2024-03-01".to_string(), 2),
+            ("2024-03-02".to_string(), 1),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_get_tag_by_id() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "invoices").await;
+
+    let tag = TagService::get_tag(&db, tag_id).await.unwrap();
+    assert_eq!(tag.id, tag_id);
+    assert_eq!(tag.name, "invoices");
+
+    let error = TagService::get_tag(&db, tag_id + 100).await.unwrap_err();
+    assert_eq!(error, format!("标签 ID {} 不存在", tag_id + 100));
+}
+
+#[tokio::test]
+async fn test_rename_tag_records_audit_log() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "draft").await;
+    insert_test_tag(&db, "final").await;
+
+    let tag = TagService::rename_tag(&db, tag_id, "  reviewed  ").await.unwrap();
+    assert_eq!(tag.name, "reviewed");
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let rows = sqlx::query("SELECT action, old_value, new_value FROM tag_audit_log WHERE tag_id = ?1")
+        .bind(tag_id)
+        .fetch_all(pool)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<String, _>("action"), "rename");
+    assert_eq!(rows[0].get::<String, _>("old_value"), "draft");
+    assert_eq!(rows[0].get::<String, _>("new_value"), "reviewed");
+
+    // 与其他标签重名时拒绝，名称和审计日志都不变
+    let error = TagService::rename_tag(&db, tag_id, "final").await.unwrap_err();
+    assert_eq!(error, "标签 \"final\" 已存在");
+    assert_eq!(TagService::get_tag(&db, tag_id).await.unwrap().name, "reviewed");
+    let audit_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tag_audit_log")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    assert_eq!(audit_count, 1);
+}
+
+#[tokio::test]
+async fn test_suggest_tags_by_extension() {
+    let db = temp_db().await;
+    let docs = insert_test_tag(&db, "docs").await;
+    let misc = insert_test_tag(&db, "misc").await;
+    let notes = insert_test_tag(&db, "notes").await;
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let associations = [
+        ("/a.pdf", docs),
+        ("/b.PDF", docs),
+        ("/c.pdf", docs),
+        ("/c.pdf", misc),
+        ("/notes.txt", notes),
+    ];
+    for (path, tag_id) in associations {
+        sqlx::query("INSERT OR IGNORE INTO files (current_path, file_type, file_size) VALUES (?1, 'file', 0)")
+            .bind(path)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO file_tags (file_id, tag_id) SELECT id, ?2 FROM files WHERE current_path = ?1")
+            .bind(path)
+            .bind(tag_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    let names = |tags: Vec<Tag>| tags.into_iter().map(|tag| tag.name).collect::<Vec<_>>();
+    let global_config = GlobalConfigManager::from_default();
+
+    // 按同扩展名文件上的出现次数排序，不区分扩展名大小写，其他扩展名的标签不推荐
+    let suggested = TagService::suggest_tags(&db, &global_config, "/new.pdf").await.unwrap();
+    assert_eq!(names(suggested), vec!["docs", "misc"]);
+
+    // 文件自身已有的标签不再推荐
+    let suggested = TagService::suggest_tags(&db, &global_config, "/c.pdf").await.unwrap();
+    assert!(suggested.is_empty());
+
+    let suggested = TagService::suggest_tags(&db, &global_config, "/README").await.unwrap();
+    assert!(suggested.is_empty());
+}
+
+#[tokio::test]
+async fn test_rename_keep_extension() {
+    let db = temp_db().await;
+
+    let file_path = db.dir().join("draft.txt");
+    fs::write(&file_path, b"text").unwrap();
+    FileSystemService::rename_keep_extension(&db, file_path.to_str().unwrap(), "final")
+        .await
+        .unwrap();
+    assert!(db.dir().join("final.txt").exists());
+    assert!(!file_path.exists());
+}
+
+#[tokio::test]
+async fn test_rename_keep_extension_without_extension() {
+    let db = temp_db().await;
+
+    let file_path = db.dir().join("Makefile");
+    fs::write(&file_path, b"all:").unwrap();
+    FileSystemService::rename_keep_extension(&db, file_path.to_str().unwrap(), "GNUmakefile")
+        .await
+        .unwrap();
+    assert!(db.dir().join("GNUmakefile").exists());
+
+    // 新名称仍按普通重命名规则校验
+    let result = FileSystemService::rename_keep_extension(
+        &db,
+        db.dir().join("GNUmakefile").to_str().unwrap(),
+        "a/b",
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_create_directory_collision_suggests_name() {
+    let temp_dir = tempdir().unwrap();
+    let parent = temp_dir.path().to_str().unwrap();
+
+    let created = FileSystemService::create_directory(parent, "Foo").unwrap();
+    assert!(Path::new(&created).is_dir());
+
+    let error = FileSystemService::create_directory(parent, "Foo").unwrap_err();
+    assert!(error.message.starts_with("目标路径已存在"));
+    assert_eq!(error.suggestion.as_deref(), Some("Foo (1)"));
+}
+
+#[test]
+fn test_create_file_collision_suggests_name() {
+    let temp_dir = tempdir().unwrap();
+    let parent = temp_dir.path().to_str().unwrap();
+    fs::write(temp_dir.path().join("notes.txt"), b"").unwrap();
+
+    let error = FileSystemService::create_file(parent, "notes.txt").unwrap_err();
+    assert_eq!(error.suggestion.as_deref(), Some("notes (1).txt"));
+
+    // 非冲突错误不带建议名称
+    let error = FileSystemService::create_file(parent, "a/b.txt").unwrap_err();
+    assert!(error.suggestion.is_none());
+}
+
+#[test]
+fn test_check_paths_exist_preserves_order() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("exists.txt");
+    fs::write(&file_path, b"").unwrap();
+    let missing = temp_dir.path().join("missing.txt");
+
+    let paths = vec![
+        missing.to_str().unwrap().to_string(),
+        file_path.to_str().unwrap().to_string(),
+        temp_dir.path().to_str().unwrap().to_string(),
+    ];
+    let result = FileSystemService::check_paths_exist(&paths);
+    assert_eq!(
+        result,
+        vec![
+            (paths[0].clone(), false),
+            (paths[1].clone(), true),
+            (paths[2].clone(), true),
+        ]
+    );
+}
+
+#[test]
+fn test_get_file_infos_per_path_results() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("photo.jpg");
+    fs::write(&file_path, b"jpeg").unwrap();
+    let missing = temp_dir.path().join("missing.txt");
+
+    let paths = vec![
+        file_path.to_str().unwrap().to_string(),
+        missing.to_str().unwrap().to_string(),
+        temp_dir.path().to_str().unwrap().to_string(),
+    ];
+    let results = FileSystemService::get_file_infos(&paths, &DisplayOptions::default());
+    assert_eq!(results.len(), 3);
+
+    let photo = results[0].as_ref().unwrap();
+    assert_eq!(photo.name, "photo.jpg");
+    assert_eq!(photo.size, 4);
+    assert_eq!(photo.category, "image");
+    assert!(results[1].is_err());
+    assert_eq!(results[2].as_ref().unwrap().file_type, "folder");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_get_file_infos_uses_hidden_detection() {
+    use crate::config::global::HiddenDetection;
+
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join(".profile");
+    fs::write(&file_path, b"export").unwrap();
+    let paths = vec![file_path.to_str().unwrap().to_string()];
+
+    let is_hidden = |hidden_detection| {
+        let display = DisplayOptions { hidden_detection, ..DisplayOptions::default() };
+        FileSystemService::get_file_infos(&paths, &display)[0].as_ref().unwrap().is_hidden
+    };
+
+    assert!(is_hidden(HiddenDetection::DotPrefix));
+    assert!(is_hidden(HiddenDetection::Both));
+    // Linux 没有隐藏属性，只按属性判断时以 . 开头的文件也不算隐藏
+    #[cfg(not(target_os = "macos"))]
+    assert!(!is_hidden(HiddenDetection::PlatformAttribute));
+}
+
+#[test]
+fn test_list_directory_uses_size_units() {
+    use crate::config::global::SizeUnits;
+
+    let temp_dir = tempdir().unwrap();
+    fs::write(temp_dir.path().join("data.bin"), vec![0u8; 1536]).unwrap();
+    let path = temp_dir.path().to_str().unwrap();
+
+    let size_display = |size_units| {
+        let display = DisplayOptions { size_units, ..DisplayOptions::default() };
+        let info = FileSystemService::list_directory(path, EntryFilter::All, None, false, &display).unwrap();
+        info.items[0].size_display.clone()
+    };
+
+    assert_eq!(size_display(SizeUnits::Binary), "1.5 KiB");
+    assert_eq!(size_display(SizeUnits::Decimal), "1.5 KB");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_run_limited_caps_concurrency() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let service = Arc::new(FileSystemService::with_concurrency(2));
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+    let barrier = Arc::new(tokio::sync::Barrier::new(8));
+
+    let mut handles = Vec::new();
+    for _ in 0..8 {
+        let service = service.clone();
+        let running = running.clone();
+        let max_running = max_running.clone();
+        let barrier = barrier.clone();
+        handles.push(tokio::spawn(async move {
+            // 所有任务同时开始争抢许可
+            barrier.wait().await;
+            service
+                .run_limited(async {
+                    let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+                .await;
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(max_running.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_resolve_font_color_only_when_not_provided() {
+    let dark = Some("#202020".to_string());
+    assert_eq!(
+        TagService::resolve_font_color(Some(&dark), None),
+        Some(Some("#FFFFFF".to_string()))
+    );
+
+    // 显式传入的字体颜色保持不变
+    assert_eq!(
+        TagService::resolve_font_color(Some(&dark), Some(Some("#FF0000".to_string()))),
+        Some(Some("#FF0000".to_string()))
+    );
+
+    // 未修改或清空背景颜色时不改动字体颜色
+    assert_eq!(TagService::resolve_font_color(None, None), None);
+    assert_eq!(TagService::resolve_font_color(Some(&None), None), None);
+}
+
+#[cfg(windows)]
+#[tokio::test]
+async fn test_rename_rejects_reserved_names_windows() {
+    let db = temp_db().await;
+    let original = db.dir().join("draft.txt");
+    fs::write(&original, b"draft").unwrap();
+
+    for name in ["CON", "nul.txt", "COM1", "draft.", "draft "] {
+        let error = FileSystemService::rename_file(&db, original.to_str().unwrap(), name)
+            .await
+            .unwrap_err();
+        assert!(error.starts_with("名称为系统保留名"), "{}: {}", name, error);
+    }
+    assert!(original.exists());
+
+    FileSystemService::rename_file(&db, original.to_str().unwrap(), "console.txt")
+        .await
+        .unwrap();
+    assert!(db.dir().join("console.txt").exists());
+}
+
+#[tokio::test]
+async fn test_undo_last_rename() {
+    let db = temp_db().await;
+    let original = db.dir().join("draft.txt");
+    fs::write(&original, b"draft").unwrap();
+
+    FileSystemService::rename_file(&db, original.to_str().unwrap(), "final.txt").await.unwrap();
+    assert!(db.dir().join("final.txt").exists());
+
+    FileSystemService::undo_last(&db).await.unwrap();
+    assert_eq!(fs::read(&original).unwrap(), b"draft");
+    assert!(!db.dir().join("final.txt").exists());
+
+    // 撤销本身不会被记录，已撤销的操作不能再次撤销
+    let error = FileSystemService::undo_last(&db).await.unwrap_err();
+    assert_eq!(error, "没有可撤销的操作");
+}
+
+#[tokio::test]
+async fn test_undo_last_move() {
+    let db = temp_db().await;
+    let source_dir = db.dir().join("source");
+    let target_dir = db.dir().join("target");
+    fs::create_dir(&source_dir).unwrap();
+    fs::create_dir(&target_dir).unwrap();
+    let file_path = source_dir.join("data.txt");
+    fs::write(&file_path, b"data").unwrap();
+    // 目标目录已有同名文件，移动时自动重命名为 data (1).txt
+    fs::write(target_dir.join("data.txt"), b"other").unwrap();
+
+    FileSystemService::move_files(
+        &db,
+        &[file_path.to_str().unwrap().to_string()],
+        target_dir.to_str().unwrap(),
+        Some(ConflictPolicy::Rename),
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(target_dir.join("data (1).txt").exists());
+
+    FileSystemService::undo_last(&db).await.unwrap();
+    assert_eq!(fs::read(&file_path).unwrap(), b"data");
+    assert!(!target_dir.join("data (1).txt").exists());
+    assert_eq!(fs::read(target_dir.join("data.txt")).unwrap(), b"other");
+}
+
+#[tokio::test]
+async fn test_undo_last_batch_move() {
+    let db = temp_db().await;
+    let first_dir = db.dir().join("first");
+    let second_dir = db.dir().join("second");
+    let target_dir = db.dir().join("target");
+    fs::create_dir(&first_dir).unwrap();
+    fs::create_dir(&second_dir).unwrap();
+    fs::create_dir(&target_dir).unwrap();
+    let paths: Vec<String> = [first_dir.join("a.txt"), first_dir.join("b.txt"), second_dir.join("c.txt")]
+        .iter()
+        .map(|path| {
+            fs::write(path, b"data").unwrap();
+            path.to_str().unwrap().to_string()
+        })
+        .collect();
+
+    FileSystemService::move_files(&db, &paths, target_dir.to_str().unwrap(), None, None).await.unwrap();
+    assert_eq!(fs::read_dir(&target_dir).unwrap().count(), 3);
+
+    // 一次撤销恢复整批移动，包括来自不同目录的文件
+    FileSystemService::undo_last(&db).await.unwrap();
+    for path in &paths {
+        assert!(Path::new(path).exists(), "{} 未恢复", path);
+    }
+    assert_eq!(fs::read_dir(&target_dir).unwrap().count(), 0);
+
+    let error = FileSystemService::undo_last(&db).await.unwrap_err();
+    assert_eq!(error, "没有可撤销的操作");
+}
+
+#[tokio::test]
+async fn test_undo_last_organize_by_extension() {
+    let db = temp_db().await;
+    let dir = db.dir().join("downloads");
+    fs::create_dir(&dir).unwrap();
+    for name in ["photo.png", "notes.txt", "README"] {
+        fs::write(dir.join(name), b"data").unwrap();
+    }
+
+    FileSystemService::organize_by_extension(&db, dir.to_str().unwrap()).await.unwrap();
+    assert!(dir.join("png").join("photo.png").exists());
+
+    // 所有分组的移动属于同一批，一次撤销全部移回
+    FileSystemService::undo_last(&db).await.unwrap();
+    for name in ["photo.png", "notes.txt", "README"] {
+        assert!(dir.join(name).exists(), "{} 未恢复", name);
+    }
+}
+
+#[tokio::test]
+async fn test_undo_last_delete_not_reversible() {
+    let db = temp_db().await;
+    let file_path = db.dir().join("gone.txt");
+    fs::write(&file_path, b"gone").unwrap();
+
+    FileSystemService::delete_files(&db, &GlobalConfigManager::from_default(), &[file_path.to_str().unwrap().to_string()]).await.unwrap();
+
+    let error = FileSystemService::undo_last(&db).await.unwrap_err();
+    assert_eq!(error, "无法撤销该操作");
+}
+
+#[tokio::test]
+async fn test_tag_statistics() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::from_default();
+    let popular = insert_test_tag(&db, "popular").await;
+    let rare = insert_test_tag(&db, "rare").await;
+    insert_test_tag(&db, "unused").await;
+
+    let paths: Vec<String> = ["a.txt", "b.txt", "c.txt"]
+        .iter()
+        .map(|name| {
+            let path = db.dir().join(name);
+            fs::write(&path, b"data").unwrap();
+            path.to_str().unwrap().to_string()
+        })
+        .collect();
+    TagService::add_tags_to_files(&db, &global_config, paths.clone(), popular).await.unwrap();
+    TagService::add_tags_to_files(&db, &global_config, vec![paths[0].clone()], rare).await.unwrap();
+
+    let stats = TagService::get_statistics(&db, &global_config).await.unwrap();
+    assert_eq!(stats.total_tags, 3);
+    assert_eq!(stats.tagged_files, 3);
+    assert!((stats.average_tags_per_file - 4.0 / 3.0).abs() < 1e-9);
+    let top: Vec<&str> = stats.top_tags.iter().map(|tag| tag.name.as_str()).collect();
+    assert_eq!(top, ["popular", "rare", "unused"]);
+    assert_eq!(stats.top_tags[0].usage_count, 3);
+}
+
+#[tokio::test]
+async fn test_delete_files_rejects_protected_path() {
+    let db = temp_db().await;
+    let protected = db.dir().join("protected");
+    fs::create_dir_all(protected.join("inner")).unwrap();
+    let global_config = GlobalConfigManager::new(GlobalConfig {
+        protected_paths: vec![protected.join("inner").to_str().unwrap().to_string()],
+        ..GlobalConfig::default()
+    });
+
+    // 受保护路径本身和它的上级目录都不能删除
+    for path in [protected.join("inner"), protected.clone()] {
+        let result = FileSystemService::delete_files(&db, &global_config, &[path.to_str().unwrap().to_string()]).await;
+        assert!(result.unwrap_err().starts_with("拒绝删除受保护路径"));
+        assert!(path.exists());
+    }
+
+    // 内置的根目录同样受保护
+    let result = FileSystemService::delete_files(&db, &global_config, &["/".to_string()]).await;
+    assert!(result.unwrap_err().starts_with("拒绝删除受保护路径"));
+
+    // 普通路径不受影响
+    let normal = db.dir().join("normal.txt");
+    fs::write(&normal, b"data").unwrap();
+    FileSystemService::delete_files(&db, &global_config, &[normal.to_str().unwrap().to_string()]).await.unwrap();
+    assert!(!normal.exists());
+}
+
+#[tokio::test]
+async fn test_copy_file_streamed() {
+    let db = temp_db().await;
+    let source = db.dir().join("large.bin");
+    let content: Vec<u8> = (0..3 * 1024 * 1024 + 123).map(|i| (i % 251) as u8).collect();
+    fs::write(&source, &content).unwrap();
+    let dest = db.dir().join("large_copy.bin");
+
+    let mut reports = Vec::new();
+    let copied = FileSystemService::copy_file_streamed(&source, &dest, 1024 * 1024, None, |done, total| {
+        reports.push((done, total));
+    })
+    .unwrap();
+
+    assert_eq!(copied, content.len() as u64);
+    assert_eq!(fs::read(&dest).unwrap(), content);
+    assert!(reports.len() > 1);
+    assert_eq!(reports.last().copied(), Some((content.len() as u64, content.len() as u64)));
+
+    // 超过阈值的文件在 copy_files 中同样按块复制
+    let global_config = GlobalConfigManager::new(GlobalConfig {
+        copy_buffer_size: 4096,
+        stream_copy_threshold: 1024,
+        ..GlobalConfig::default()
+    });
+    let target_dir = db.dir().join("target");
+    fs::create_dir(&target_dir).unwrap();
+    let small = db.dir().join("small.txt");
+    fs::write(&small, b"tiny").unwrap();
+    let source_str = source.to_str().unwrap().to_string();
+    let small_str = small.to_str().unwrap().to_string();
+
+    let progress = std::sync::Mutex::new(Vec::new());
+    let record = |report: &CopyProgress| progress.lock().unwrap().push(report.clone());
+    FileSystemService::copy_files(
+        &db,
+        &global_config,
+        &[source_str.clone(), small_str.clone()],
+        target_dir.to_str().unwrap(),
+        None,
+        Some(true),
+        None,
+        Some(&record),
+    )
+    .await
+    .unwrap();
+    assert_eq!(fs::read(target_dir.join("large.bin")).unwrap(), content);
+
+    // 大文件逐块报告进度，小文件复制完成后报告一次
+    let progress = progress.into_inner().unwrap();
+    let total = content.len() as u64;
+    let large_reports: Vec<&CopyProgress> = progress.iter().filter(|report| report.path == source_str).collect();
+    assert_eq!(large_reports.len() as u64, total.div_ceil(4096));
+    assert!(large_reports.iter().all(|report| report.total == total));
+    assert_eq!(large_reports.last().unwrap().copied, total);
+    assert_eq!(
+        progress.last(),
+        Some(&CopyProgress { path: small_str, copied: 4, total: 4 })
+    );
+}
+
+#[tokio::test]
+async fn test_copy_files_cancelled_after_first_file() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::from_default();
+    let paths: Vec<String> = ["first.txt", "second.txt"]
+        .iter()
+        .map(|name| {
+            let path = db.dir().join(name);
+            fs::write(&path, b"data").unwrap();
+            path.to_str().unwrap().to_string()
+        })
+        .collect();
+    let target_dir = db.dir().join("target");
+    fs::create_dir(&target_dir).unwrap();
+
+    let token = CancellationToken::new();
+    let outcome = FileSystemService::copy_entries(
+        &db,
+        &global_config,
+        &paths,
+        target_dir.to_str().unwrap(),
+        None,
+        None,
+        Some(&token),
+        None,
+        |_| token.cancel(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(outcome, BatchOutcome::Cancelled { completed: vec![paths[0].clone()] });
+    assert!(target_dir.join("first.txt").exists());
+    assert!(!target_dir.join("second.txt").exists());
+
+    // 已取消的令牌使移动在开始前即停止
+    let outcome = FileSystemService::move_files(&db, &paths, target_dir.to_str().unwrap(), None, Some(&token))
+        .await
+        .unwrap();
+    assert_eq!(outcome, BatchOutcome::Cancelled { completed: Vec::new() });
+    assert!(Path::new(&paths[1]).exists());
+}
+
+#[tokio::test]
+async fn test_copy_file_streamed_cancelled_removes_partial_file() {
+    let temp_dir = tempdir().unwrap();
+    let source = temp_dir.path().join("large.bin");
+    fs::write(&source, vec![7u8; 64 * 1024]).unwrap();
+    let dest = temp_dir.path().join("large_copy.bin");
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let error = FileSystemService::copy_file_streamed(&source, &dest, 1024, Some(&token), |_, _| {}).unwrap_err();
+    assert_eq!(error, CopyError::Cancelled);
+    assert!(!dest.exists());
+}
+
+#[test]
+fn test_available_space() {
+    let temp_dir = tempdir().unwrap();
+    let available = FileSystemService::available_space(temp_dir.path().to_str().unwrap()).unwrap();
+    assert!(available > 0);
+
+    let missing = temp_dir.path().join("missing");
+    let error = FileSystemService::available_space(missing.to_str().unwrap()).unwrap_err();
+    assert!(error.starts_with("路径不存在"));
+}
+
+#[test]
+fn test_preflight_copy() {
+    let temp_dir = tempdir().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    fs::create_dir_all(source_dir.join("folder")).unwrap();
+    fs::write(source_dir.join("a.txt"), vec![0u8; 100]).unwrap();
+    fs::write(source_dir.join("folder").join("b.txt"), vec![0u8; 50]).unwrap();
+    let target_dir = temp_dir.path().join("target");
+    fs::create_dir(&target_dir).unwrap();
+    fs::write(target_dir.join("a.txt"), b"existing").unwrap();
+
+    let paths = vec![
+        source_dir.join("a.txt").to_str().unwrap().to_string(),
+        source_dir.join("folder").to_str().unwrap().to_string(),
+    ];
+
+    let report = FileSystemService::preflight_copy(&paths, target_dir.to_str().unwrap()).unwrap();
+    assert_eq!(report.total_size, 150);
+    assert!(report.enough_space);
+    assert_eq!(report.conflicts, vec![target_dir.join("a.txt").to_str().unwrap().to_string()]);
+
+    // 可用空间小于总大小时标记空间不足
+    let report = FileSystemService::preflight_copy_with_space(&paths, target_dir.to_str().unwrap(), 10).unwrap();
+    assert!(!report.enough_space);
+    assert_eq!(report.available_space, 10);
+    assert!(!target_dir.join("folder").exists());
+}
+
+#[tokio::test]
+async fn test_tag_name_constraints() {
+    let db = temp_db().await;
+
+    let error = TagService::create_tag(&db, &GlobalConfigManager::from_default(), "a".repeat(101), false).await.unwrap_err();
+    assert!(error.starts_with("标签名称过长"));
+    let error = TagService::create_tag(&db, &GlobalConfigManager::from_default(), "line\nbreak".to_string(), false).await.unwrap_err();
+    assert_eq!(error, "标签名称包含非法字符");
+
+    // 长度按字符计算，首尾空白不计入
+    let tag = TagService::create_tag(&db, &GlobalConfigManager::from_default(), format!(" {} ", "标".repeat(100)), false).await.unwrap();
+    assert_eq!(tag.name.chars().count(), 100);
+
+    let tag_id = insert_test_tag(&db, "work").await;
+    let error = TagService::modify_tag(&db, tag_id, Some("a".repeat(101)), None, None, None, None)
+        .await
+        .unwrap_err();
+    assert!(error.starts_with("标签名称过长"));
+    let error = TagService::modify_tag(&db, tag_id, Some("tab\there".to_string()), None, None, None, None)
+        .await
+        .unwrap_err();
+    assert_eq!(error, "标签名称包含非法字符");
+}
+
+#[test]
+fn test_relativize() {
+    let base = Path::new("/home/user");
+    let path = base.join("docs").join("a.txt");
+    assert_eq!(
+        FileSystemService::relativize(path.to_str().unwrap(), base.to_str().unwrap()),
+        Path::new("docs").join("a.txt").to_str().unwrap()
+    );
+    assert_eq!(FileSystemService::relativize("/home/user", "/home/user/"), ".");
+
+    // 不在基准目录下（包括名称前缀相同的兄弟目录）时原样返回
+    assert_eq!(FileSystemService::relativize("/home/username/a.txt", "/home/user"), "/home/username/a.txt");
+    assert_eq!(FileSystemService::relativize("/var/log", "/home/user"), "/var/log");
+}
+
+#[test]
+fn test_format_path_for_shell() {
+    let path = "/home/user/My Documents/report.txt";
+    assert_eq!(FileSystemService::format_path_for_shell(path, ShellKind::Cmd), "\"/home/user/My Documents/report.txt\"");
+    assert_eq!(FileSystemService::format_path_for_shell(path, ShellKind::PowerShell), "'/home/user/My Documents/report.txt'");
+    assert_eq!(FileSystemService::format_path_for_shell(path, ShellKind::PosixSh), "'/home/user/My Documents/report.txt'");
+
+    // 单引号按各 shell 的规则转义
+    assert_eq!(FileSystemService::format_path_for_shell("/tmp/it's", ShellKind::PowerShell), "'/tmp/it''s'");
+    assert_eq!(FileSystemService::format_path_for_shell("/tmp/it's", ShellKind::PosixSh), "'/tmp/it'\\''s'");
+
+    // 不含特殊字符的路径原样返回，反斜杠只在 POSIX shell 中需要引号
+    assert_eq!(FileSystemService::format_path_for_shell(r"C:\Data\a.txt", ShellKind::Cmd), r"C:\Data\a.txt");
+    assert_eq!(FileSystemService::format_path_for_shell(r"C:\Data\a.txt", ShellKind::PosixSh), r"'C:\Data\a.txt'");
+}
+
+#[cfg(windows)]
+#[test]
+fn test_relativize_case_insensitive_on_windows() {
+    assert_eq!(FileSystemService::relativize(r"C:\Users\Me\Docs\a.txt", r"c:\users\me"), r"Docs\a.txt");
+    assert_eq!(FileSystemService::relativize(r"D:\Data\a.txt", r"C:\Data"), r"D:\Data\a.txt");
+}
+
+#[tokio::test]
+async fn test_create_tags_bulk() {
+    let db = temp_db().await;
+    insert_test_tag(&db, "existing").await;
+
+    let names = vec![
+        "alpha".to_string(),
+        " alpha ".to_string(),
+        "existing".to_string(),
+        "bad\nname".to_string(),
+        "beta".to_string(),
+    ];
+    let result = TagService::create_tags_bulk(&db, &GlobalConfigManager::from_default(), names).await.unwrap();
+
+    let created: Vec<&str> = result.created.iter().map(|tag| tag.name.as_str()).collect();
+    assert_eq!(created, ["alpha", "beta"]);
+    assert_eq!(result.skipped, ["existing"]);
+    assert_eq!(result.invalid.len(), 1);
+    assert_eq!(result.invalid[0].name, "bad\nname");
+    assert_eq!(result.invalid[0].reason, "标签名称包含非法字符");
+
+    let connection = db.get_connection().await.unwrap();
+    let count: i64 = sqlx::query("SELECT COUNT(*) AS count FROM tags")
+        .fetch_one(connection.as_sqlite().unwrap())
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(count, 3);
+}
+
+#[tokio::test]
+async fn test_create_tags_bulk_restores_deleted_and_uses_palette() {
+    let db = temp_db().await;
+    let palette = vec!["#E57373".to_string(), "#1E1E1E".to_string()];
+    let global_config = GlobalConfigManager::new(GlobalConfig {
+        default_tag_colors: palette.clone(),
+        ..GlobalConfig::default()
+    });
+
+    let archived = insert_test_tag(&db, "archive").await;
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    sqlx::query("UPDATE tags SET deleted_at = CURRENT_TIMESTAMP WHERE id = ?1")
+        .bind(archived)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let names = vec!["archive".to_string(), "fresh".to_string(), "dark".to_string()];
+    let result = TagService::create_tags_bulk(&db, &global_config, names).await.unwrap();
+
+    // 已删除的同名标签被恢复，保留原 ID
+    assert_eq!(result.created[0].id, archived);
+    assert_eq!(TagService::get_tag(&db, archived).await.unwrap().name, "archive");
+    let count: i64 = sqlx::query("SELECT COUNT(*) AS count FROM tags WHERE name = 'archive'")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(count, 1);
+
+    // 新标签与 create_tag 一样按默认颜色列表循环选取颜色
+    assert_eq!(result.created[1].color.as_deref(), Some(palette[1].as_str()));
+    assert_eq!(result.created[2].color.as_deref(), Some(palette[0].as_str()));
+    assert_eq!(result.created[2].font_color.as_deref(), Some("#000000"));
+}
+
+#[tokio::test]
+async fn test_create_tag_uses_default_color_palette() {
+    let db = temp_db().await;
+    let palette = vec!["#E57373".to_string(), "#1E1E1E".to_string(), "#81C784".to_string()];
+    let global_config = GlobalConfigManager::new(GlobalConfig {
+        default_tag_colors: palette.clone(),
+        ..GlobalConfig::default()
+    });
+
+    let mut colors = Vec::new();
+    for name in ["one", "two", "three", "four"] {
+        let tag = TagService::create_tag(&db, &global_config, name.to_string(), false).await.unwrap();
+        colors.push(tag.color.unwrap());
+        if tag.name == "two" {
+            assert_eq!(tag.font_color.as_deref(), Some("#FFFFFF"));
+        }
+    }
+    assert_eq!(colors[..3], palette[..]);
+    assert_eq!(colors[3], palette[0]);
+
+    // 未配置颜色列表时使用数据库默认颜色
+    let tag = TagService::create_tag(&db, &GlobalConfigManager::from_default(), "five".to_string(), false).await.unwrap();
+    assert_eq!(tag.color.as_deref(), Some("#FFFF00"));
+}
+
+#[tokio::test]
+async fn test_create_tag_restores_deleted_tag() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let original = TagService::create_tag(&db, &global_config, "archive".to_string(), false).await.unwrap();
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    sqlx::query("UPDATE tags SET deleted_at = CURRENT_TIMESTAMP WHERE id = ?1")
+        .bind(original.id)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let restored = TagService::create_tag(&db, &global_config, " archive ".to_string(), true).await.unwrap();
+    assert_eq!(restored.id, original.id);
+    assert_eq!(TagService::get_tag(&db, original.id).await.unwrap().name, "archive");
+
+    let count: i64 = sqlx::query("SELECT COUNT(*) AS count FROM tags WHERE name = 'archive'")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(count, 1);
+
+    // 不要求恢复时仍新建标签
+    sqlx::query("UPDATE tags SET deleted_at = CURRENT_TIMESTAMP WHERE id = ?1")
+        .bind(original.id)
+        .execute(pool)
+        .await
+        .unwrap();
+    let created = TagService::create_tag(&db, &global_config, "archive".to_string(), false).await.unwrap();
+    assert_ne!(created.id, original.id);
+}
+
+#[test]
+fn test_default_tag_colors_validated_on_load() {
+    let temp_dir = tempdir().unwrap();
+    let config_path = temp_dir.path().join("global.toml");
+    fs::write(&config_path, "default_tag_colors = [\"#E57373\", \"red\"]\n").unwrap();
+
+    let error = GlobalConfig::from_toml_file(&config_path).unwrap_err();
+    assert!(error.starts_with("默认标签颜色格式无效"));
+}
+
+#[tokio::test]
+async fn test_list_untagged() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::from_default();
+    let tag_id = insert_test_tag(&db, "work").await;
+
+    let tagged = db.dir().join("tagged.txt");
+    let untagged = db.dir().join("untagged.txt");
+    fs::write(&tagged, b"data").unwrap();
+    fs::write(&untagged, b"data").unwrap();
+    let paths = vec![tagged.to_str().unwrap().to_string(), untagged.to_str().unwrap().to_string()];
+
+    // 两个文件都登记后，移除其中一个的标签
+    TagService::add_tags_to_files(&db, &global_config, paths.clone(), tag_id).await.unwrap();
+    TagService::clear_tags_from_files(&db, &global_config, vec![paths[1].clone()]).await.unwrap();
+
+    let items = FileSystemService::list_untagged(&db, 10, 0, &DisplayOptions::default()).await.unwrap();
+    let listed: Vec<&str> = items.iter().map(|item| item.path.as_str()).collect();
+    assert_eq!(listed, [paths[1].as_str()]);
+    assert!(items[0].registered_at.is_some());
+
+    assert!(FileSystemService::list_untagged(&db, 10, 1, &DisplayOptions::default()).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_incremental_usage_count_matches_recount() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::new(GlobalConfig {
+        incremental_usage_count: true,
+        ..GlobalConfig::default()
+    });
+    let red = insert_test_tag(&db, "red").await;
+    let blue = insert_test_tag(&db, "blue").await;
+
+    let mut paths = Vec::new();
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        let file_path = db.dir().join(name);
+        fs::write(&file_path, b"data").unwrap();
+        paths.push(file_path.to_str().unwrap().to_string());
+    }
+
+    // 重复添加不会重复计数
+    TagService::add_tags_to_files(&db, &global_config, paths.clone(), red).await.unwrap();
+    TagService::add_tags_to_files(&db, &global_config, paths[..2].to_vec(), red).await.unwrap();
+    TagService::add_multiple_tags_to_files(&db, &global_config, paths[1..].to_vec(), vec![red, blue])
+        .await
+        .unwrap();
+    TagService::clear_tags_from_files(&db, &global_config, vec![paths[0].clone(), paths[1].clone()])
+        .await
+        .unwrap();
+    TagService::add_tags_to_files(&db, &global_config, vec![paths[0].clone()], blue).await.unwrap();
+
+    async fn usage_counts(pool: &sqlx::SqlitePool, tag_ids: &[i32]) -> Vec<i32> {
+        let mut counts = Vec::new();
+        for tag_id in tag_ids {
+            let usage_count: i32 = sqlx::query("SELECT usage_count FROM tags WHERE id = ?1")
+                .bind(tag_id)
+                .fetch_one(pool)
+                .await
+                .unwrap()
+                .get("usage_count");
+            counts.push(usage_count);
+        }
+        counts
+    }
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let incremental = usage_counts(pool, &[red, blue]).await;
+    assert_eq!(incremental, [1, 2]);
+
+    // 全量重新统计的结果与增量维护一致
+    TagService::recount_all_usage(&db).await.unwrap();
+    assert_eq!(usage_counts(pool, &[red, blue]).await, incremental);
+}
+
+#[tokio::test]
+async fn test_add_tags_rejects_oversized_batch() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "bulk").await;
+    let global_config = GlobalConfigManager::new(GlobalConfig {
+        max_batch_size: 2,
+        ..GlobalConfig::default()
+    });
+
+    let mut paths = Vec::new();
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        let file_path = db.dir().join(name);
+        fs::write(&file_path, b"data").unwrap();
+        paths.push(file_path.to_str().unwrap().to_string());
+    }
+
+    let result = TagService::add_tags_to_files(&db, &global_config, paths.clone(), tag_id).await;
+    assert!(result.unwrap_err().starts_with("批量操作数量超过限制"));
+    let result = TagService::add_multiple_tags_to_files(&db, &global_config, paths.clone(), vec![tag_id]).await;
+    assert!(result.unwrap_err().starts_with("批量操作数量超过限制"));
+
+    // 被拒绝的调用不会写入任何关联
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    let associations: i64 = sqlx::query("SELECT COUNT(*) AS count FROM file_tags")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(associations, 0);
+
+    // 未超过上限时正常执行
+    TagService::add_tags_to_files(&db, &global_config, paths[..2].to_vec(), tag_id)
+        .await
+        .unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_directory_skips_unreadable_entries() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // root 不受权限位限制，无法构造不可读的目录项
+    if unsafe { libc::geteuid() } == 0 {
+        return;
+    }
+
+    let temp_dir = tempdir().unwrap();
+    let locked_dir = temp_dir.path().join("locked");
+    fs::create_dir(&locked_dir).unwrap();
+    fs::write(locked_dir.join("a.txt"), b"a").unwrap();
+    fs::write(locked_dir.join("b.txt"), b"b").unwrap();
+
+    // 只有读权限没有执行权限：可以列出名称，但无法获取目录项的元数据
+    fs::set_permissions(&locked_dir, fs::Permissions::from_mode(0o444)).unwrap();
+    let result = FileSystemService::list_directory(locked_dir.to_str().unwrap(), EntryFilter::All, None, false, &DisplayOptions::default());
+    fs::set_permissions(&locked_dir, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let info = result.unwrap();
+    assert!(info.items.is_empty());
+    let mut skipped = info.skipped.clone();
+    skipped.sort();
+    assert_eq!(
+        skipped,
+        [
+            locked_dir.join("a.txt").to_string_lossy().to_string(),
+            locked_dir.join("b.txt").to_string_lossy().to_string(),
+        ]
+    );
+
+    // 可读的目录不受影响
+    let info = FileSystemService::list_directory(temp_dir.path().to_str().unwrap(), EntryFilter::All, None, false, &DisplayOptions::default()).unwrap();
+    assert_eq!(info.items.len(), 1);
+    assert!(info.skipped.is_empty());
+}
+
+#[test]
+fn test_export_tree() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path().join("project");
+    fs::create_dir_all(root.join("src").join("deep")).unwrap();
+    fs::write(root.join("README.md"), b"readme").unwrap();
+    fs::write(root.join("src").join("main.rs"), b"fn main() {}").unwrap();
+    fs::write(root.join("src").join("deep").join("data.bin"), b"0123456789").unwrap();
+
+    let json = FileSystemService::export_tree(root.to_str().unwrap(), 1, 100).unwrap();
+    let snapshot: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot["truncated"], false);
+    let tree = &snapshot["root"];
+
+    assert_eq!(tree["name"], "project");
+    assert_eq!(tree["file_type"], "folder");
+    assert_eq!(tree["size"], 28);
+    assert_eq!(tree["children"][0]["name"], "README.md");
+    assert_eq!(tree["children"][0]["file_type"], "file");
+    assert_eq!(tree["children"][0]["size"], 6);
+    // 超过导出深度的文件夹只统计大小，不列出子节点
+    assert_eq!(tree["children"][1]["name"], "src");
+    assert_eq!(tree["children"][1]["size"], 22);
+    assert!(tree["children"][1].get("children").is_none());
+
+    let json = FileSystemService::export_tree(root.to_str().unwrap(), 3, 100).unwrap();
+    let snapshot: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let src = &snapshot["root"]["children"][1];
+    assert_eq!(src["children"][0]["name"], "deep");
+    assert_eq!(src["children"][0]["children"][0]["name"], "data.bin");
+    assert_eq!(src["children"][1]["name"], "main.rs");
+}
+
+#[test]
+fn test_export_tree_truncated_at_max_results() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path().join("project");
+    fs::create_dir_all(root.join("nested")).unwrap();
+    for i in 0..5 {
+        fs::write(root.join(format!("file{}.txt", i)), b"data").unwrap();
+        fs::write(root.join("nested").join(format!("file{}.txt", i)), b"data").unwrap();
+    }
+
+    fn count_nodes(node: &serde_json::Value) -> usize {
+        node["children"].as_array().map_or(0, |children| {
+            children.len() + children.iter().map(count_nodes).sum::<usize>()
+        })
+    }
+
+    // 共 11 个节点，上限为 4 时只导出 4 个并标记截断
+    let json = FileSystemService::export_tree(root.to_str().unwrap(), 5, 4).unwrap();
+    let snapshot: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot["truncated"], true);
+    assert_eq!(count_nodes(&snapshot["root"]), 4);
+
+    // 上限恰好等于节点数时不算截断
+    let json = FileSystemService::export_tree(root.to_str().unwrap(), 5, 11).unwrap();
+    let snapshot: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot["truncated"], false);
+    assert_eq!(count_nodes(&snapshot["root"]), 11);
+}
+
+#[cfg(windows)]
+#[test]
+fn test_list_directory_flags_attribute_hidden_windows() {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::{SetFileAttributesW, FILE_ATTRIBUTE_HIDDEN};
+
+    let temp_dir = tempdir().unwrap();
+    let hidden = temp_dir.path().join("secret.txt");
+    fs::write(&hidden, b"secret").unwrap();
+    fs::write(temp_dir.path().join("visible.txt"), b"visible").unwrap();
+
+    let wide: Vec<u16> = hidden.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    // SAFETY: wide 以 0 结尾且在调用期间有效
+    assert_ne!(unsafe { SetFileAttributesW(wide.as_ptr(), FILE_ATTRIBUTE_HIDDEN) }, 0);
+
+    let info = FileSystemService::list_directory(temp_dir.path().to_str().unwrap(), EntryFilter::All, None, false, &DisplayOptions::default()).unwrap();
+    let secret = info.items.iter().find(|item| item.name == "secret.txt").unwrap();
+    let visible = info.items.iter().find(|item| item.name == "visible.txt").unwrap();
+    assert!(secret.is_hidden);
+    assert!(!visible.is_hidden);
+}
+
+#[test]
+fn test_list_directory_entry_filter() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("docs")).unwrap();
+    fs::create_dir(root.join("images")).unwrap();
+    for name in ["a.txt", "b.png", "c.md"] {
+        fs::write(root.join(name), name).unwrap();
+    }
+    let path = root.to_str().unwrap();
+
+    let all = FileSystemService::list_directory(path, EntryFilter::All, None, false, &DisplayOptions::default()).unwrap();
+    assert_eq!(all.items.len(), 5);
+    assert_eq!((all.total_files, all.total_folders), (3, 2));
+
+    let files = FileSystemService::list_directory(path, EntryFilter::FilesOnly, None, false, &DisplayOptions::default()).unwrap();
+    let names: Vec<&str> = files.items.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(names, vec!["a.txt", "b.png", "c.md"]);
+    assert_eq!((files.total_files, files.total_folders), (3, 0));
+
+    let folders = FileSystemService::list_directory(path, EntryFilter::FoldersOnly, None, false, &DisplayOptions::default()).unwrap();
+    let names: Vec<&str> = folders.items.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(names, vec!["docs", "images"]);
+    assert_eq!((folders.total_files, folders.total_folders), (0, 2));
+}
+
+#[test]
+fn test_list_directory_extension_filter() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("photos")).unwrap();
+    for name in ["a.png", "b.JPG", "c.txt", "d.pdf", "README"] {
+        fs::write(root.join(name), name).unwrap();
+    }
+    let path = root.to_str().unwrap();
+    let extensions = vec!["png".to_string(), "jpg".to_string()];
+
+    let info = FileSystemService::list_directory(path, EntryFilter::All, Some(&extensions), false, &DisplayOptions::default()).unwrap();
+    let names: Vec<&str> = info.items.iter().map(|item| item.name.as_str()).collect();
+    // 文件夹始终保留，扩展名匹配不区分大小写
+    assert_eq!(names, vec!["photos", "a.png", "b.JPG"]);
+    assert_eq!((info.total_files, info.total_folders), (2, 1));
+
+    let folders = FileSystemService::list_directory(path, EntryFilter::FoldersOnly, Some(&extensions), false, &DisplayOptions::default()).unwrap();
+    assert_eq!(folders.items.len(), 1);
+    assert_eq!((folders.total_files, folders.total_folders), (0, 1));
+}
+
+#[test]
+fn test_list_directory_child_count() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    let folder = root.join("album");
+    fs::create_dir_all(folder.join("nested")).unwrap();
+    fs::write(folder.join("a.png"), b"a").unwrap();
+    fs::write(folder.join("b.png"), b"b").unwrap();
+    // 列表中不显示的隐藏项不计入
+    fs::write(folder.join(".thumbs"), b"cache").unwrap();
+    fs::create_dir(root.join("empty")).unwrap();
+    fs::write(root.join("file.txt"), b"text").unwrap();
+    let path = root.to_str().unwrap();
+
+    let info = FileSystemService::list_directory(path, EntryFilter::All, None, true, &DisplayOptions::default()).unwrap();
+    let counts: Vec<(&str, Option<usize>)> =
+        info.items.iter().map(|item| (item.name.as_str(), item.child_count)).collect();
+    assert_eq!(counts, vec![("album", Some(3)), ("empty", Some(0)), ("file.txt", None)]);
+
+    // 未开启时不统计
+    let info = FileSystemService::list_directory(path, EntryFilter::All, None, false, &DisplayOptions::default()).unwrap();
+    assert!(info.items.iter().all(|item| item.child_count.is_none()));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_directory_child_count_uses_hidden_detection() {
+    use crate::config::global::HiddenDetection;
+
+    let temp_dir = tempdir().unwrap();
+    let folder = temp_dir.path().join("album");
+    fs::create_dir(&folder).unwrap();
+    fs::write(folder.join("a.png"), b"a").unwrap();
+    fs::write(folder.join(".thumbs"), b"cache").unwrap();
+
+    let count_with = |hidden_detection| {
+        let display = DisplayOptions { hidden_detection, ..DisplayOptions::default() };
+        let info = FileSystemService::list_directory(temp_dir.path().to_str().unwrap(), EntryFilter::All, None, true, &display)
+            .unwrap();
+        info.items[0].child_count
+    };
+    assert_eq!(count_with(HiddenDetection::DotPrefix), Some(1));
+    // Unix 文件没有隐藏属性，只按属性判断时点开头的文件同样计入
+    assert_eq!(count_with(HiddenDetection::PlatformAttribute), Some(2));
+}
+
+#[tokio::test]
+async fn test_list_directory_async_matches_sync() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("docs")).unwrap();
+    fs::write(root.join("docs").join("a.md"), "a").unwrap();
+    fs::write(root.join("b.txt"), "b").unwrap();
+    fs::write(root.join("c.png"), "c").unwrap();
+    let path = root.to_str().unwrap();
+
+    let sync = FileSystemService::list_directory(path, EntryFilter::All, None, true, &DisplayOptions::default()).unwrap();
+    let async_info = FileSystemService::list_directory_async(path.to_string(), EntryFilter::All, None, true, DisplayOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(serde_json::to_value(&async_info).unwrap(), serde_json::to_value(&sync).unwrap());
+
+    let error = FileSystemService::list_directory_async(root.join("missing").to_str().unwrap().to_string(), EntryFilter::All, None, false, DisplayOptions::default())
+        .await
+        .unwrap_err();
+    assert!(error.starts_with("路径不存在"));
+}
+
+#[tokio::test]
+async fn test_organize_by_extension() {
+    let db = temp_db().await;
+    let tag_id = insert_test_tag(&db, "notes").await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let dir = db.dir().join("downloads");
+    fs::create_dir(&dir).unwrap();
+    for name in ["a.png", "b.PNG", "notes.txt", "README", ".hidden"] {
+        fs::write(dir.join(name), name).unwrap();
+    }
+    fs::create_dir(dir.join("keep")).unwrap();
+    // 已存在的 png 子文件夹中有同名文件
+    fs::create_dir(dir.join("png")).unwrap();
+    fs::write(dir.join("png").join("a.png"), b"old").unwrap();
+
+    TagService::add_tags_to_files(
+        &db,
+        &global_config,
+        vec![dir.join("notes.txt").to_str().unwrap().to_string()],
+        tag_id,
+    )
+    .await
+    .unwrap();
+
+    let report = FileSystemService::organize_by_extension(&db, dir.to_str().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(report.moved, 4);
+    let mut created = report.created_folders.clone();
+    created.sort();
+    assert_eq!(
+        created,
+        [
+            dir.join("other").to_str().unwrap().to_string(),
+            dir.join("txt").to_str().unwrap().to_string(),
+        ]
+    );
+
+    assert_eq!(fs::read(dir.join("png").join("a.png")).unwrap(), b"old");
+    assert_eq!(fs::read(dir.join("png").join("a (1).png")).unwrap(), b"a.png");
+    assert!(dir.join("png").join("b.PNG").exists());
+    assert!(dir.join("txt").join("notes.txt").exists());
+    assert!(dir.join("other").join("README").exists());
+    // 隐藏文件和文件夹保持不动
+    assert!(dir.join(".hidden").exists());
+    assert!(dir.join("keep").is_dir());
+
+    let result = TagService::search_files_by_tag(&db, &global_config, tag_id, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.items[0].path, dir.join("txt").join("notes.txt").to_str().unwrap());
+}
+
+#[tokio::test]
+async fn test_organize_by_extension_folder_name_taken_by_file() {
+    let db = temp_db().await;
+
+    let dir = db.dir().join("downloads");
+    fs::create_dir(&dir).unwrap();
+    // 无扩展名的文件 doc 占用了 doc 分组的文件夹名
+    fs::write(dir.join("doc"), b"plain").unwrap();
+    fs::write(dir.join("report.doc"), b"report").unwrap();
+
+    let report = FileSystemService::organize_by_extension(&db, dir.to_str().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(report.moved, 2);
+    assert_eq!(
+        report.created_folders,
+        [
+            dir.join("doc (1)").to_str().unwrap().to_string(),
+            dir.join("other").to_str().unwrap().to_string(),
+        ]
+    );
+    assert_eq!(fs::read(dir.join("doc (1)").join("report.doc")).unwrap(), b"report");
+    assert_eq!(fs::read(dir.join("other").join("doc")).unwrap(), b"plain");
+}
+
+#[test]
+fn test_sanitize_name_windows_rules() {
+    assert_eq!(FileSystemService::sanitize_name_for("report: Q1?.txt", true), "report_ Q1_.txt");
+    assert_eq!(FileSystemService::sanitize_name_for("a<b>c\"d|e*f", true), "a_b_c_d_e_f");
+    assert_eq!(FileSystemService::sanitize_name_for("tab\there", true), "tab_here");
+    // 去掉末尾的点和空格
+    assert_eq!(FileSystemService::sanitize_name_for("notes. . ", true), "notes");
+    // 只剩点和空格的名称使用占位符
+    assert_eq!(FileSystemService::sanitize_name_for("..", true), "_");
+    assert_eq!(FileSystemService::sanitize_name_for("...", true), "_");
+    assert_eq!(FileSystemService::sanitize_name_for(" . ", true), "_");
+    // 保留名称追加下划线
+    assert_eq!(FileSystemService::sanitize_name_for("con", true), "con_");
+    assert_eq!(FileSystemService::sanitize_name_for("NUL.txt", true), "NUL_.txt");
+    assert_eq!(FileSystemService::sanitize_name_for("COM1", true), "COM1_");
+    assert_eq!(FileSystemService::sanitize_name_for("COM0", true), "COM0");
+    assert_eq!(FileSystemService::sanitize_name_for("console.log", true), "console.log");
+    // 合法名称保持不变
+    assert_eq!(FileSystemService::sanitize_name_for("照片 (1).png", true), "照片 (1).png");
+}
+
+#[test]
+fn test_sanitize_name_other_platforms() {
+    // 非 Windows 规则只替换路径分隔符和空字符
+    assert_eq!(FileSystemService::sanitize_name_for("a/b\\c\0d", false), "a_b_c_d");
+    assert_eq!(FileSystemService::sanitize_name_for("time: 10:30.", false), "time: 10:30.");
+
+    let name = "a:b.txt";
+    let expected = if cfg!(windows) { "a_b.txt" } else { "a:b.txt" };
+    assert_eq!(FileSystemService::sanitize_name(name), expected);
+}
+
+#[test]
+fn test_capabilities_match_build_target() {
+    let capabilities = FileSystemService::capabilities();
+    assert_eq!(capabilities.supports_drives, cfg!(windows));
+    assert!(!capabilities.supports_trash);
+    assert_eq!(capabilities.os, std::env::consts::OS);
+}
+
+#[tokio::test]
+async fn test_purge_deleted_records() {
+    let db = temp_db().await;
+    let old_tag = insert_test_tag(&db, "old").await;
+    let live_tag = insert_test_tag(&db, "live").await;
+
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    for (path, deleted_at) in [
+        ("/old.txt", Some("-40 days")),
+        ("/recent.txt", Some("-1 days")),
+        ("/live.txt", None),
+    ] {
+        sqlx::query(
+            "INSERT INTO files (current_path, file_type, file_size, deleted_at) VALUES (?1, 'file', 1, datetime('now', ?2))",
+        )
+        .bind(path)
+        .bind(deleted_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query("UPDATE tags SET deleted_at = datetime('now', '-40 days') WHERE id = ?1")
+        .bind(old_tag)
+        .execute(pool)
+        .await
+        .unwrap();
+    // 旧文件和旧标签的关联一并清除
+    sqlx::query("INSERT INTO file_tags (file_id, tag_id) SELECT id, ?1 FROM files")
+        .bind(old_tag)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO file_tags (file_id, tag_id) SELECT id, ?1 FROM files")
+        .bind(live_tag)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let purged = DatabaseService::purge_deleted(&db, 30).await.unwrap();
+    assert_eq!(purged, 2);
+
+    let paths: Vec<String> = sqlx::query("SELECT current_path FROM files ORDER BY current_path")
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get("current_path"))
+        .collect();
+    assert_eq!(paths, ["/live.txt", "/recent.txt"]);
+
+    let tag_ids: Vec<i32> = sqlx::query("SELECT id FROM tags")
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get("id"))
+        .collect();
+    assert_eq!(tag_ids, [live_tag]);
+
+    let associations: i64 = sqlx::query("SELECT COUNT(*) AS count FROM file_tags")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(associations, 2);
+
+    assert!(DatabaseService::purge_deleted(&db, -1).await.is_err());
+}
+
+#[tokio::test]
+async fn test_switch_database_to_sqlite_runs_migrations() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::from_default();
+
+    let target_path = db.dir().join("switched.db");
+    fs::File::create(&target_path).unwrap();
+    let config = DatabaseConfig::new(
+        DatabaseType::Sqlite,
+        "switched".to_string(),
+        None,
+        None,
+        None,
+        None,
+        Some(target_path.to_str().unwrap().to_string()),
+    );
+    DatabaseService::switch_database(&db, config).await.unwrap();
+    assert_eq!(db.config().database, "switched");
+
+    // 切换后的数据库已执行 SQLite 迁移，可以直接使用
+    assert!(db.verify_schema().await.unwrap().is_empty());
+    let tag = TagService::create_tag(&db, &global_config, "after-switch".to_string(), false).await.unwrap();
+    assert_eq!(tag.name, "after-switch");
+}
+
+#[tokio::test]
+async fn test_temp_db_create_and_read_tag() {
+    let db = temp_db().await;
+    let global_config = GlobalConfigManager::from_default();
+
+    TagService::create_tag(&db, &global_config, "helper".to_string(), false).await.unwrap();
+
+    let tags = TagService::get_tag_list(&db, &global_config, None, None).await.unwrap();
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].name, "helper");
+    assert!(db.dir().join("test.db").exists());
+}
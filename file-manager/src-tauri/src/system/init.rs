@@ -1,16 +1,49 @@
 //! 系统初始化模块
 //!
-//! 负责应用启动时的系统级初始化操作，包括数据库初始化
+//! 负责应用启动时的系统级初始化操作，包括配置文件定位和数据库初始化
 
 use crate::database::{DatabaseResult, GlobalDatabase};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// 应用标识符，与 tauri.conf.json 中的 identifier 保持一致，用作应用数据目录名
+const APP_IDENTIFIER: &str = "com.file-manager.app";
+
+/// 开发环境下配置文件所在的目录（相对于当前工作目录）
+const DEV_CONFIG_DIR: &str = "config";
+
+/// 解析配置文件路径
+///
+/// 优先使用平台应用数据目录下的配置文件（Windows 为 `%APPDATA%`，macOS 为
+/// `~/Library/Application Support`，Linux 为 `$XDG_CONFIG_HOME` 或 `~/.config`），
+/// 该文件不存在时回退到当前工作目录下的 `config/`，便于开发时直接使用仓库中的配置
+///
+/// # 参数
+/// - `name`: 配置文件名，如 "database.toml"
+///
+/// # 返回
+/// 配置文件路径（不保证文件存在）
+pub fn resolve_config_path(name: &str) -> PathBuf {
+    resolve_config_path_in(dirs::config_dir().as_deref(), name)
+}
+
+/// 在指定的平台配置目录下解析配置文件路径
+pub(crate) fn resolve_config_path_in(config_dir: Option<&Path>, name: &str) -> PathBuf {
+    if let Some(config_dir) = config_dir {
+        let app_config_path = config_dir.join(APP_IDENTIFIER).join(name);
+        if app_config_path.exists() {
+            return app_config_path;
+        }
+    }
+
+    Path::new(DEV_CONFIG_DIR).join(name)
+}
 
 /// 初始化数据库
 ///
 /// 优先尝试从配置文件初始化数据库，如果失败则使用默认配置
 ///
 /// # 参数
-/// - `config_path`: 配置文件路径，默认为 "config/database.toml"
+/// - `config_path`: 配置文件路径，通常由 `resolve_config_path("database.toml")` 解析得到
 ///
 /// # 返回
 /// - `Ok(GlobalDatabase)`: 初始化成功的数据库实例
@@ -21,9 +54,7 @@ pub async fn init_database<P: AsRef<Path>>(config_path: P) -> DatabaseResult<Glo
         match GlobalDatabase::init_from_config_file(config_path).await {
             Ok(db) => {
                 println!("从配置文件初始化数据库成功");
-                // 执行数据库迁移
-                db.migrate().await?;
-                println!("数据库迁移完成");
+                prepare_schema(&db).await?;
                 Ok(db)
             }
             Err(e) => {
@@ -45,9 +76,22 @@ pub async fn init_database<P: AsRef<Path>>(config_path: P) -> DatabaseResult<Glo
 pub async fn init_database_with_default() -> DatabaseResult<GlobalDatabase> {
     let db = GlobalDatabase::init_from_default_config().await?;
     println!("使用默认配置初始化数据库成功");
-    // 执行数据库迁移
-    db.migrate().await?;
-    println!("数据库迁移完成");
+    prepare_schema(&db).await?;
     Ok(db)
 }
 
+/// 准备数据库架构
+///
+/// 开启 `auto_migrate`（默认）时执行数据库迁移；关闭时由外部管理架构，
+/// 只检查架构版本，版本过旧时返回错误
+async fn prepare_schema(db: &GlobalDatabase) -> DatabaseResult<()> {
+    if db.config().auto_migrate {
+        db.migrate().await?;
+        println!("数据库迁移完成");
+    } else {
+        db.verify_schema_version().await?;
+        println!("已跳过自动迁移，数据库架构版本检查通过");
+    }
+    Ok(())
+}
+
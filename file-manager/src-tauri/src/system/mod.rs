@@ -6,3 +6,5 @@ pub mod init;
 pub mod runtime;
 pub mod runtime_config;
 
+#[cfg(test)]
+mod tests;
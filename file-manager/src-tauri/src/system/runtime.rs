@@ -126,7 +126,7 @@ impl RuntimeManager {
     /// 从配置文件创建运行时管理器
     ///
     /// # 参数
-    /// - `config_path`: 配置文件路径，默认为 "config/runtime.toml"
+    /// - `config_path`: 配置文件路径，通常由 `resolve_config_path("runtime.toml")` 解析得到
     ///
     /// # 返回
     /// - `Ok(Self)`: 成功创建运行时管理器
@@ -0,0 +1,81 @@
+//! 系统模块测试
+//!
+//! 包含配置文件路径解析和数据库初始化的单元测试
+
+use super::init::{init_database, resolve_config_path, resolve_config_path_in};
+use crate::database::config::DatabaseType;
+use crate::database::connection::required_schema_version;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+fn test_resolve_config_path_prefers_app_config_dir() {
+    // 临时目录充当平台配置目录（如 Linux 上的 $HOME/.config）
+    let temp_home = tempdir().unwrap();
+    let app_dir = temp_home.path().join("com.file-manager.app");
+    fs::create_dir_all(&app_dir).unwrap();
+    fs::write(app_dir.join("database.toml"), "").unwrap();
+
+    let resolved = resolve_config_path_in(Some(temp_home.path()), "database.toml");
+    assert_eq!(resolved, app_dir.join("database.toml"));
+    assert!(resolved.ends_with("database.toml"));
+}
+
+#[test]
+fn test_resolve_config_path_falls_back_to_cwd() {
+    let temp_home = tempdir().unwrap();
+
+    let resolved = resolve_config_path_in(Some(temp_home.path()), "runtime.toml");
+    assert_eq!(resolved, Path::new("config").join("runtime.toml"));
+
+    let resolved = resolve_config_path_in(None, "global.toml");
+    assert_eq!(resolved, Path::new("config").join("global.toml"));
+    assert!(resolve_config_path("global.toml").ends_with("global.toml"));
+}
+
+#[tokio::test]
+async fn test_init_database_skips_migration_when_auto_migrate_disabled() {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("external.db");
+    fs::File::create(&db_path).unwrap();
+    let config_path = temp_dir.path().join("database.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "db_type = \"sqlite\"\nauto_migrate = false\n\n[sqlite]\ndatabase = \"external\"\nsqlite_path = '{}'\n",
+            db_path.display()
+        ),
+    )
+    .unwrap();
+
+    // 未执行过迁移的空数据库：不会自动迁移，架构版本检查失败
+    let error = init_database(&config_path).await.err().unwrap().to_string();
+    assert!(error.contains("数据库架构版本过旧"), "{}", error);
+
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", db_path.display())).await.unwrap();
+    let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(tables, 0);
+
+    // 外部已应用到最新版本时初始化成功，且不会创建任何表
+    sqlx::query("CREATE TABLE _sqlx_migrations (version BIGINT PRIMARY KEY, success BOOLEAN NOT NULL)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO _sqlx_migrations (version, success) VALUES (?1, 1)")
+        .bind(required_schema_version(DatabaseType::Sqlite))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let db = init_database(&config_path).await.unwrap();
+    assert!(!db.config().auto_migrate);
+    let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'tags'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(tables, 0);
+}
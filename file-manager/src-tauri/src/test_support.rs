@@ -0,0 +1,82 @@
+//! 测试辅助模块
+//!
+//! 提供已执行迁移的 SQLite 测试数据库，省去各测试重复的建库代码
+
+use crate::database::config::{DatabaseConfig, DatabaseType};
+use crate::database::GlobalDatabase;
+use sqlx::Row;
+use std::fs;
+use std::ops::Deref;
+use std::path::Path;
+use tempfile::{tempdir, TempDir};
+
+/// 在指定目录下创建 SQLite 测试数据库，并执行 `migrations/sqlite` 下的全部迁移
+async fn setup_sqlite_database(dir: &Path) -> GlobalDatabase {
+    let db_path = dir.join("test.db");
+    fs::File::create(&db_path).unwrap();
+
+    let config = DatabaseConfig::new(
+        DatabaseType::Sqlite,
+        "test".to_string(),
+        None,
+        None,
+        None,
+        None,
+        Some(db_path.to_str().unwrap().to_string()),
+    );
+
+    let db = GlobalDatabase::new(config);
+    db.init().await.unwrap();
+    db.migrate().await.unwrap();
+
+    db
+}
+
+/// 直接插入测试标签，返回标签ID
+pub async fn insert_test_tag(db: &GlobalDatabase, name: &str) -> i32 {
+    let connection = db.get_connection().await.unwrap();
+    let pool = connection.as_sqlite().unwrap();
+    sqlx::query("INSERT INTO tags (name) VALUES (?1)")
+        .bind(name)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query("SELECT id FROM tags WHERE name = ?1")
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("id")
+}
+
+/// 临时目录中的 SQLite 测试数据库
+///
+/// 可直接当作 `&GlobalDatabase` 使用，drop 时删除临时目录
+pub struct TestDatabase {
+    db: GlobalDatabase,
+    dir: TempDir,
+}
+
+impl TestDatabase {
+    /// 数据库所在的临时目录，测试可在其中创建文件
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+impl Deref for TestDatabase {
+    type Target = GlobalDatabase;
+
+    fn deref(&self) -> &GlobalDatabase {
+        &self.db
+    }
+}
+
+/// 创建已执行迁移的临时 SQLite 测试数据库
+///
+/// 表结构、索引、触发器和视图与正式数据库一致，测试文件可以放在 [`TestDatabase::dir`] 中
+pub async fn temp_db() -> TestDatabase {
+    let dir = tempdir().unwrap();
+    let db = setup_sqlite_database(dir.path()).await;
+    TestDatabase { db, dir }
+}
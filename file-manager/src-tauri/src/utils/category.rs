@@ -0,0 +1,31 @@
+//! 文件分类工具函数
+
+/// 根据扩展名判断文件分类
+///
+/// # 参数
+/// - `extension`: 文件扩展名（不含点号，大小写不敏感）
+///
+/// # 返回
+/// 文件分类：`image`、`video`、`audio`、`document`、`archive`、`code` 或 `other`
+pub fn detect_category(extension: Option<&str>) -> &'static str {
+    let extension = match extension {
+        Some(ext) => ext.to_ascii_lowercase(),
+        None => return "other",
+    };
+
+    match extension.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg" | "ico" | "tif" | "tiff"
+        | "heic" | "heif" | "raw" | "psd" => "image",
+        "mp4" | "mkv" | "avi" | "mov" | "wmv" | "flv" | "webm" | "m4v" | "mpg" | "mpeg"
+        | "3gp" | "ts" | "rmvb" => "video",
+        "mp3" | "wav" | "flac" | "aac" | "ogg" | "wma" | "m4a" | "opus" | "ape" => "audio",
+        "txt" | "md" | "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "odt"
+        | "ods" | "odp" | "rtf" | "csv" | "epub" => "document",
+        "zip" | "rar" | "7z" | "tar" | "gz" | "bz2" | "xz" | "zst" | "tgz" | "iso" => "archive",
+        "rs" | "js" | "mjs" | "cjs" | "jsx" | "tsx" | "vue" | "py" | "java" | "kt" | "c" | "h"
+        | "cpp" | "hpp" | "cc" | "cs" | "go" | "rb" | "php" | "swift" | "sh" | "bat" | "ps1"
+        | "html" | "htm" | "css" | "scss" | "less" | "json" | "toml" | "yaml" | "yml" | "xml"
+        | "sql" | "lua" => "code",
+        _ => "other",
+    }
+}
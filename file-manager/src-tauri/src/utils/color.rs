@@ -0,0 +1,35 @@
+//! 颜色工具函数
+
+/// 根据背景颜色计算可读的字体颜色
+///
+/// 按感知亮度（ITU-R BT.601 权重）判断背景深浅，浅色背景返回黑色，深色背景返回白色
+///
+/// # 参数
+/// - `background`: 背景颜色，格式为 `#RRGGBB`
+///
+/// # 返回
+/// - `Some(String)`: `#000000` 或 `#FFFFFF`
+/// - `None`: 背景颜色格式无效
+pub fn contrast_font_color(background: &str) -> Option<String> {
+    let hex = background.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+    let (r, g, b) = (channel(0..2)?, channel(2..4)?, channel(4..6)?);
+
+    let luminance = (299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000;
+    if luminance >= 128 {
+        Some("#000000".to_string())
+    } else {
+        Some("#FFFFFF".to_string())
+    }
+}
+
+/// 判断字符串是否为 `#RRGGBB` 格式的颜色
+pub fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
@@ -0,0 +1,51 @@
+//! 磁盘空间工具函数
+
+use std::io;
+use std::path::Path;
+
+/// 获取路径所在文件系统的可用空间
+///
+/// 返回当前用户可用的字节数（Unix 不含仅 root 可用的保留块）
+///
+/// # 参数
+/// - `path`: 文件系统中任意已存在的路径
+///
+/// # 返回
+/// - `Ok(u64)`: 可用字节数
+/// - `Err(io::Error)`: 系统调用失败
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    #[cfg(unix)]
+    {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        // SAFETY: statvfs 是纯 C 结构体，全零是合法的初始值；c_path 在调用期间有效
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        #[allow(clippy::useless_conversion)]
+        let available = u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize));
+        Ok(available)
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        let mut available = 0u64;
+        // SAFETY: wide 以 0 结尾且在调用期间有效，不需要的输出参数传空指针
+        let ok = unsafe {
+            GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut())
+        };
+        if ok == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(available)
+    }
+}
@@ -0,0 +1,53 @@
+//! 文件哈希工具函数
+
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// 流式读取时每次读取的字节数
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// 计算文件的 SHA-256 哈希值
+///
+/// 按固定大小分块读取，大文件不会一次性载入内存
+///
+/// # 参数
+/// - `path`: 文件路径
+///
+/// # 返回
+/// - `Ok(String)`: 小写十六进制表示的哈希值
+/// - `Err(String)`: 错误信息
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path)
+        .map_err(|e| format!("打开文件失败 {}: {}", path.display(), e))?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)
+            .map_err(|e| format!("读取文件失败 {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// 计算内存数据的 SHA-256 哈希值
+///
+/// # 参数
+/// - `data`: 要计算哈希的数据
+///
+/// # 返回
+/// 小写十六进制表示的哈希值
+pub fn sha256_hex(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+/// 将字节转为小写十六进制字符串
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,45 @@
+//! 隐藏文件判断工具函数
+
+use std::fs::Metadata;
+
+use crate::config::global::HiddenDetection;
+
+/// 按指定策略判断文件是否隐藏
+///
+/// # 参数
+/// - `name`: 文件名
+/// - `metadata`: 文件元数据
+/// - `detection`: 隐藏文件判断策略，通常取自全局配置的 `hidden_detection`
+pub fn is_hidden(name: &str, metadata: &Metadata, detection: HiddenDetection) -> bool {
+    match detection {
+        HiddenDetection::DotPrefix => name.starts_with('.'),
+        HiddenDetection::PlatformAttribute => has_hidden_attribute(metadata),
+        HiddenDetection::Both => name.starts_with('.') || has_hidden_attribute(metadata),
+    }
+}
+
+/// 文件是否带有系统的隐藏属性
+///
+/// Windows 为 `FILE_ATTRIBUTE_HIDDEN`，macOS 为 `UF_HIDDEN` 标志，其它系统没有隐藏属性
+fn has_hidden_attribute(metadata: &Metadata) -> bool {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_HIDDEN;
+
+        metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::macos::fs::MetadataExt;
+
+        metadata.st_flags() & libc::UF_HIDDEN != 0
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        let _ = metadata;
+        false
+    }
+}
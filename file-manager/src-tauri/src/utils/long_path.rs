@@ -0,0 +1,64 @@
+//! Windows 长路径工具函数
+//!
+//! Windows 默认限制路径长度为 260 个字符，使用 `\\?\` 前缀的扩展长度路径可绕过该限制
+
+use std::borrow::Cow;
+use std::path::Path;
+
+/// 扩展长度路径前缀
+const LONG_PATH_PREFIX: &str = r"\\?\";
+
+/// UNC 路径的扩展长度前缀
+const LONG_UNC_PREFIX: &str = r"\\?\UNC\";
+
+/// 转换为可传给 `fs` 调用的路径
+///
+/// Windows 上为绝对路径添加 `\\?\` 前缀（UNC 路径 `\\server\share` 转为 `\\?\UNC\server\share`），
+/// 已带前缀的路径和相对路径保持不变；其它系统原样返回
+///
+/// # 参数
+/// - `path`: 原始路径
+///
+/// # 返回
+/// 可用于文件系统操作的路径
+pub fn to_long_path(path: &Path) -> Cow<'_, Path> {
+    #[cfg(windows)]
+    {
+        let raw = path.to_string_lossy();
+        if raw.starts_with(LONG_PATH_PREFIX) || !path.is_absolute() {
+            return Cow::Borrowed(path);
+        }
+
+        // 扩展长度路径不做任何规范化，必须统一使用反斜杠
+        let raw = raw.replace('/', "\\");
+        let long_path = match raw.strip_prefix(r"\\") {
+            Some(unc) => format!("{}{}", LONG_UNC_PREFIX, unc),
+            None => format!("{}{}", LONG_PATH_PREFIX, raw),
+        };
+        Cow::Owned(std::path::PathBuf::from(long_path))
+    }
+
+    #[cfg(not(windows))]
+    {
+        Cow::Borrowed(path)
+    }
+}
+
+/// 去掉扩展长度路径前缀
+///
+/// 写入数据库的 current_path 统一使用不带前缀的形式，保持路径可读且与前端传入的路径一致
+///
+/// # 参数
+/// - `path`: 可能带有 `\\?\` 前缀的路径
+///
+/// # 返回
+/// 去掉前缀后的路径
+pub fn strip_long_path_prefix(path: &str) -> String {
+    if let Some(unc) = path.strip_prefix(LONG_UNC_PREFIX) {
+        format!(r"\\{}", unc)
+    } else if let Some(local) = path.strip_prefix(LONG_PATH_PREFIX) {
+        local.to_string()
+    } else {
+        path.to_string()
+    }
+}
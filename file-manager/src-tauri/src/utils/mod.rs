@@ -2,6 +2,29 @@
 //!
 //! 提供通用的工具函数，供各个服务模块使用
 
+pub mod cancellation;
+pub mod category;
+pub mod color;
+pub mod disk_space;
+pub mod hash;
+pub mod hidden;
+pub mod long_path;
+pub mod permissions;
+pub mod protected_path;
+pub mod size;
 pub mod time;
 
-pub use time::format_iso8601;
+#[cfg(test)]
+mod tests;
+
+pub use cancellation::CancellationToken;
+pub use category::detect_category;
+pub use color::{contrast_font_color, is_hex_color};
+pub use disk_space::available_space;
+pub use hash::{sha256_file, sha256_hex};
+pub use hidden::is_hidden;
+pub use long_path::{strip_long_path_prefix, to_long_path};
+pub use permissions::unix_mode;
+pub use protected_path::is_protected_path;
+pub use size::format_size_with;
+pub use time::{created_time, format_iso8601};
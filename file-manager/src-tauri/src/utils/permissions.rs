@@ -0,0 +1,24 @@
+//! 文件权限工具函数
+
+use std::fs::Metadata;
+
+/// 获取文件的 Unix 权限位
+///
+/// # 参数
+/// - `metadata`: 文件元数据
+///
+/// # 返回
+/// Unix 系统返回权限位（如 `0o644`），其它系统返回 `None`
+pub fn unix_mode(metadata: &Metadata) -> Option<u32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        Some(metadata.permissions().mode() & 0o7777)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
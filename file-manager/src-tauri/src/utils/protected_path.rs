@@ -0,0 +1,37 @@
+//! 受保护路径工具函数
+//!
+//! 防止误删系统目录、根目录和用户主目录等关键路径
+
+use std::path::{Path, PathBuf};
+
+use super::long_path::strip_long_path_prefix;
+
+/// 判断路径是否受保护
+///
+/// 路径与任一受保护路径相同，或者是某个受保护路径的上级目录（删除它会连带删除受保护路径）时视为受保护。
+/// 路径存在时先解析为规范路径，避免通过 `..` 或符号链接绕过检查；Windows 上比较时忽略大小写
+///
+/// # 参数
+/// - `path`: 待检查的路径
+/// - `protected`: 受保护路径列表
+///
+/// # 返回
+/// 是否受保护
+pub fn is_protected_path(path: &Path, protected: &[String]) -> bool {
+    let target = normalize(path);
+    protected
+        .iter()
+        .filter(|entry| !entry.trim().is_empty())
+        .any(|entry| normalize(Path::new(entry)).starts_with(&target))
+}
+
+/// 规范化路径用于比较
+fn normalize(path: &Path) -> PathBuf {
+    let resolved = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let raw = strip_long_path_prefix(&resolved.to_string_lossy());
+
+    #[cfg(windows)]
+    let raw = raw.replace('/', "\\").to_lowercase();
+
+    PathBuf::from(raw)
+}
@@ -0,0 +1,35 @@
+//! 文件大小格式化工具函数
+
+use crate::config::global::SizeUnits;
+
+/// 按指定单位制将字节数格式化为便于阅读的字符串
+///
+/// 不足 1 个单位时显示整数字节，其余保留一位小数
+///
+/// # 参数
+/// - `bytes`: 字节数
+/// - `units`: 展示单位制，通常取自全局配置的 `size_units`
+///
+/// # 返回
+/// 格式化的大小，例如 `"1.5 KiB"` 或 `"1.5 KB"`
+pub fn format_size_with(bytes: u64, units: SizeUnits) -> String {
+    let (base, suffixes) = match units {
+        SizeUnits::Binary => (1024.0, ["KiB", "MiB", "GiB", "TiB", "PiB"]),
+        SizeUnits::Decimal => (1000.0, ["KB", "MB", "GB", "TB", "PB"]),
+    };
+
+    let mut value = bytes as f64;
+    if value < base {
+        return format!("{} B", bytes);
+    }
+
+    let mut suffix = suffixes[0];
+    for next in suffixes {
+        value /= base;
+        suffix = next;
+        if value < base {
+            break;
+        }
+    }
+    format!("{:.1} {}", value, suffix)
+}
@@ -0,0 +1,149 @@
+//! 工具函数模块测试
+//!
+//! 包含通用工具函数的单元测试
+
+use super::category::detect_category;
+use super::color::contrast_font_color;
+use super::hash::{sha256_file, sha256_hex};
+use super::long_path::{strip_long_path_prefix, to_long_path};
+use super::protected_path::is_protected_path;
+use super::size::format_size_with;
+use super::time::format_iso8601;
+use crate::config::global::{DisplayTimezone, SizeUnits};
+
+#[test]
+fn test_detect_category() {
+    assert_eq!(detect_category(Some("png")), "image");
+    assert_eq!(detect_category(Some("JPG")), "image");
+    assert_eq!(detect_category(Some("mp4")), "video");
+    assert_eq!(detect_category(Some("rs")), "code");
+    assert_eq!(detect_category(Some("zip")), "archive");
+}
+
+#[test]
+fn test_detect_category_unknown() {
+    assert_eq!(detect_category(Some("xyz123")), "other");
+    assert_eq!(detect_category(Some("")), "other");
+    assert_eq!(detect_category(None), "other");
+}
+
+#[test]
+fn test_contrast_font_color_dark_background() {
+    assert_eq!(contrast_font_color("#1E1E1E").as_deref(), Some("#FFFFFF"));
+    assert_eq!(contrast_font_color("#0000FF").as_deref(), Some("#FFFFFF"));
+}
+
+#[test]
+fn test_contrast_font_color_light_background() {
+    assert_eq!(contrast_font_color("#FFFF00").as_deref(), Some("#000000"));
+    assert_eq!(contrast_font_color("#f0f0f0").as_deref(), Some("#000000"));
+}
+
+#[test]
+fn test_contrast_font_color_invalid() {
+    assert_eq!(contrast_font_color("FFFF00"), None);
+    assert_eq!(contrast_font_color("#FFF"), None);
+    assert_eq!(contrast_font_color("#GGGGGG"), None);
+}
+
+#[test]
+fn test_sha256_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("hello.txt");
+    std::fs::write(&path, b"hello").unwrap();
+
+    assert_eq!(
+        sha256_file(&path).unwrap(),
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+    assert!(sha256_file(&temp_dir.path().join("missing")).is_err());
+    // 内存数据与文件内容相同时哈希一致
+    assert_eq!(sha256_hex(b"hello"), sha256_file(&path).unwrap());
+}
+
+#[test]
+fn test_strip_long_path_prefix() {
+    assert_eq!(strip_long_path_prefix(r"\\?\C:\data\file.txt"), r"C:\data\file.txt");
+    assert_eq!(strip_long_path_prefix(r"\\?\UNC\server\share\file.txt"), r"\\server\share\file.txt");
+    assert_eq!(strip_long_path_prefix("/home/user/file.txt"), "/home/user/file.txt");
+}
+
+#[cfg(windows)]
+#[test]
+fn test_to_long_path_windows() {
+    use std::path::Path;
+
+    assert_eq!(to_long_path(Path::new(r"C:\data\file.txt")), Path::new(r"\\?\C:\data\file.txt"));
+    assert_eq!(to_long_path(Path::new(r"\\server\share\a")), Path::new(r"\\?\UNC\server\share\a"));
+    assert_eq!(to_long_path(Path::new(r"\\?\C:\data")), Path::new(r"\\?\C:\data"));
+    assert_eq!(to_long_path(Path::new("relative")), Path::new("relative"));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_to_long_path_unchanged() {
+    let path = std::path::Path::new("/home/user/file.txt");
+    assert_eq!(to_long_path(path), path);
+}
+
+#[test]
+fn test_is_protected_path() {
+    let protected = vec!["/".to_string(), "/home/user".to_string()];
+    assert!(is_protected_path(std::path::Path::new("/"), &protected));
+    assert!(is_protected_path(std::path::Path::new("/home/user"), &protected));
+    assert!(is_protected_path(std::path::Path::new("/home"), &protected));
+    assert!(!is_protected_path(std::path::Path::new("/home/user/notes.txt"), &protected));
+    assert!(!is_protected_path(std::path::Path::new("/home/other"), &protected));
+}
+
+#[test]
+fn test_format_iso8601_timezones() {
+    use chrono::{DateTime, Local, Offset, TimeZone};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let utc = format_iso8601(&time, DisplayTimezone::Utc);
+    assert_eq!(utc, "2023-11-14T22:13:20.000Z");
+
+    // 本地时间与 UTC 表示同一时刻，偏移等于系统时区偏移
+    let local = format_iso8601(&time, DisplayTimezone::Local);
+    let utc_parsed = DateTime::parse_from_rfc3339(&utc).unwrap();
+    let local_parsed = DateTime::parse_from_rfc3339(&local).unwrap();
+    assert_eq!(utc_parsed, local_parsed);
+    let system_offset = Local.timestamp_opt(1_700_000_000, 0).unwrap().offset().fix();
+    assert_eq!(*local_parsed.offset(), system_offset);
+    assert!(!local.ends_with('Z'));
+}
+
+#[test]
+fn test_format_size_units() {
+    assert_eq!(format_size_with(1536, SizeUnits::Binary), "1.5 KiB");
+    assert_eq!(format_size_with(1536, SizeUnits::Decimal), "1.5 KB");
+
+    assert_eq!(format_size_with(0, SizeUnits::Binary), "0 B");
+    assert_eq!(format_size_with(1023, SizeUnits::Binary), "1023 B");
+    assert_eq!(format_size_with(1000, SizeUnits::Decimal), "1.0 KB");
+    assert_eq!(format_size_with(5 * 1024 * 1024, SizeUnits::Binary), "5.0 MiB");
+    assert_eq!(format_size_with(2_500_000_000, SizeUnits::Decimal), "2.5 GB");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_created_time_uses_statx_on_linux() {
+    use super::time::created_time;
+    use std::time::{Duration, SystemTime};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("born.txt");
+    std::fs::write(&path, b"data").unwrap();
+    let metadata = std::fs::metadata(&path).unwrap();
+
+    // 旧内核或不记录 btime 的文件系统上拿不到创建时间，此时跳过
+    let Some(created) = created_time(&path, &metadata) else {
+        eprintln!("当前系统不支持 statx btime，跳过");
+        return;
+    };
+    let now = SystemTime::now();
+    assert!(created <= now);
+    assert!(now.duration_since(created).unwrap() < Duration::from_secs(60));
+}
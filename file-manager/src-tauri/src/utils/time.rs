@@ -1,32 +1,84 @@
 //! 时间相关工具函数
 
-/// 格式化时间为 ISO 8601 格式
+use std::fs::Metadata;
+use std::path::Path;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+
+use crate::config::global::DisplayTimezone;
+
+/// 按指定时区格式化时间为 ISO 8601 格式
 ///
 /// # 参数
 /// - `time`: 系统时间
+/// - `timezone`: 展示时区，UTC 以 `Z` 结尾，本地时间带 `+08:00` 形式的偏移
 ///
 /// # 返回
-/// 格式化的时间字符串（Unix 时间戳格式：`"{秒数}.{纳秒数}Z"`）
+/// 精确到毫秒的 RFC 3339 时间字符串，例如 `"2024-01-01T08:00:00.000Z"` 或 `"2024-01-01T16:00:00.000+08:00"`
+pub fn format_iso8601(time: &SystemTime, timezone: DisplayTimezone) -> String {
+    let utc: DateTime<Utc> = (*time).into();
+    match timezone {
+        DisplayTimezone::Utc => utc.to_rfc3339_opts(SecondsFormat::Millis, true),
+        DisplayTimezone::Local => utc
+            .with_timezone(&Local)
+            .to_rfc3339_opts(SecondsFormat::Millis, false),
+    }
+}
+
+/// 获取文件的创建时间
+///
+/// Linux 上直接通过 `statx` 查询 btime：标准库只在部分目标（如 glibc）上使用 statx，
+/// 其它情况下 `Metadata::created` 总是失败。statx 不可用（内核早于 4.11）或
+/// 文件系统不记录 btime 时回退到 `Metadata::created`
 ///
-/// # 示例
-/// ```
-/// use std::time::SystemTime;
-/// use crate::utils::format_iso8601;
+/// # 参数
+/// - `path`: 文件路径（跟随符号链接）
+/// - `metadata`: 同一文件已获取的元数据
 ///
-/// let now = SystemTime::now();
-/// let formatted = format_iso8601(&now);
-/// println!("{}", formatted); // 例如: "1234567890.123456789Z"
-/// ```
-pub fn format_iso8601(time: &std::time::SystemTime) -> String {
-    use std::time::UNIX_EPOCH;
-
-    let duration = time.duration_since(UNIX_EPOCH)
-        .unwrap_or_default();
-
-    let secs = duration.as_secs();
-    let nanos = duration.subsec_nanos();
-
-    // 简化的 ISO 8601 格式
-    // 实际应该使用 chrono 库，但这里为了简单直接格式化
-    format!("{}.{:09}Z", secs, nanos)
+/// # 返回
+/// 创建时间，系统无法提供时返回 None（调用方通常回退为修改时间）
+pub fn created_time(path: &Path, metadata: &Metadata) -> Option<SystemTime> {
+    #[cfg(target_os = "linux")]
+    {
+        statx_birth_time(path).or_else(|| metadata.created().ok())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = path;
+        metadata.created().ok()
+    }
+}
+
+/// 通过 `statx` 查询文件的 btime
+#[cfg(target_os = "linux")]
+fn statx_birth_time(path: &Path) -> Option<SystemTime> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statx 是纯 C 结构体，全零是合法的初始值；c_path 在调用期间有效
+    let mut stat: libc::statx = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        libc::statx(
+            libc::AT_FDCWD,
+            c_path.as_ptr(),
+            libc::AT_STATX_SYNC_AS_STAT,
+            libc::STATX_BTIME,
+            &mut stat,
+        )
+    };
+    // 文件系统不支持时调用仍会成功，但返回的掩码中不含 STATX_BTIME
+    if result != 0 || stat.stx_mask & libc::STATX_BTIME == 0 {
+        return None;
+    }
+
+    let offset = Duration::new(stat.stx_btime.tv_sec.unsigned_abs(), stat.stx_btime.tv_nsec);
+    if stat.stx_btime.tv_sec >= 0 {
+        UNIX_EPOCH.checked_add(offset)
+    } else {
+        UNIX_EPOCH.checked_sub(offset)
+    }
 }
@@ -14,14 +14,32 @@ export interface FileItem {
   file_type: 'file' | 'folder';
   /** 文件大小（字节） */
   size: number;
+  /** 便于阅读的文件大小（如 1.5 KiB） */
+  size_display: string;
   /** 修改日期 */
   modified_date: string; // ISO 8601 格式
   /** 创建日期 */
   created_date: string; // ISO 8601 格式
   /** 文件扩展名（仅文件） */
   extension?: string;
+  /** 文件分类 */
+  category?: 'image' | 'video' | 'audio' | 'document' | 'archive' | 'code' | 'other';
   /** 是否为隐藏文件 */
   is_hidden?: boolean;
+  /** 是否为符号链接 */
+  is_symlink?: boolean;
+  /** 是否为只读 */
+  readonly?: boolean;
+  /** Unix 权限位（仅 Unix 系统有值） */
+  mode?: number;
+  /** 首次登记到数据库的时间（仅来自数据库的列表有值） */
+  registered_at?: string | null;
+  /** 数据库记录最后更新时间（仅来自数据库的列表有值） */
+  db_updated_at?: string | null;
+  /** 相对于基准目录的路径（仅搜索时指定了基准目录有值） */
+  relative_path?: string | null;
+  /** 文件夹的直接子项数量（仅列目录时要求统计的文件夹有值） */
+  child_count?: number | null;
 }
 
 /**
@@ -38,6 +56,8 @@ export interface DirectoryInfo {
   total_files: number;
   /** 总文件夹数 */
   total_folders: number;
+  /** 无法读取而被跳过的目录项路径 */
+  skipped: string[];
 }
 
 /**
@@ -66,4 +86,18 @@ export interface SearchResult {
   page_size: number;
   /** 是否有更多数据 */
   has_more: boolean;
-}
\ No newline at end of file
+}
+
+/**
+ * 文件系统服务在当前平台上支持的功能
+ */
+export interface Capabilities {
+  /** 是否有驱动盘列表（仅 Windows） */
+  supports_drives: boolean;
+  /** 是否支持移到回收站 */
+  supports_trash: boolean;
+  /** 是否能识别和移动符号链接 */
+  supports_symlinks: boolean;
+  /** 操作系统名称（如 "windows"、"linux"、"macos"） */
+  os: string;
+}
@@ -25,8 +25,9 @@ export function formatDate(dateString: string): string {
   let date: Date;
 
   // 尝试解析不同的时间格式
-  // 格式可能是: "1234567890.123456789Z" (Unix 时间戳 + 纳秒)
-  if (dateString.includes('.')) {
+  // 旧格式: "1234567890.123456789Z" (Unix 时间戳 + 纳秒)
+  // 新格式: "2024-01-01T08:00:00.000Z" 或 "2024-01-01T16:00:00.000+08:00" (ISO 8601)
+  if (/^\d+\.\d+Z$/.test(dateString)) {
     const parts = dateString.split('.');
     const seconds = parseInt(parts[0], 10);
     date = new Date(seconds * 1000);